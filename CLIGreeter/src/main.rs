@@ -1,45 +1,234 @@
+use std::env;
+use std::fs;
 use std::io;
+use std::path::{Path, PathBuf};
 
+use chrono::{Local, NaiveTime, Timelike};
+use clap::{Parser, ValueEnum};
+use serde::Serialize;
+
+/// Greets you by name, politely if the name is capitalized properly.
+/// Values not given on the command line are asked for interactively.
+#[derive(Parser)]
+#[command(name = "greeter")]
+struct Args {
+    /// Your name, e.g. "Mary Ann"
+    #[arg(long)]
+    name: Option<String>,
+
+    /// Your age in years
+    #[arg(long, value_parser = parse_age)]
+    age: Option<u8>,
+
+    /// Greeting language: en, es, fr or de (defaults to the language of LANG)
+    #[arg(long, value_name = "CODE")]
+    lang: Option<String>,
+
+    /// Greet as if it were this time of day, e.g. 18:30 (defaults to the local time)
+    #[arg(long, value_name = "HH:MM", value_parser = parse_time)]
+    time: Option<NaiveTime>,
+
+    /// Tone of the greeting (defaults to polite or rude, depending on how the name is capitalized)
+    #[arg(long, value_enum)]
+    manner: Option<Manner>,
+
+    /// Greet everyone listed in this file, one name per line, instead of asking
+    #[arg(long, value_name = "PATH", conflicts_with_all = ["name", "age"])]
+    from_file: Option<PathBuf>,
+
+    /// Write the greetings of --from-file here instead of printing them
+    #[arg(short, long, value_name = "PATH", requires = "from_file")]
+    output: Option<PathBuf>,
+
+    /// Print the result as JSON instead of text; questions are then asked on stderr
+    #[arg(long, conflicts_with = "from_file")]
+    json: bool,
+}
+
+#[derive(Clone, Copy, ValueEnum, Serialize)]
 enum Manner {
     Polite,
     Rude,
+    /// "Good day, Mr./Ms. <name>."
+    Formal,
+    /// "Hey <name>!"
+    Casual,
 }
 
 impl Manner {
-    fn greet(&self, name: &str) -> String {
-        match self {
-            Manner::Polite => format!("\nHello, nice to meet you {}!\n", name),
-            Manner::Rude => format!(
-                "\nOh, it's you {}...,
----\nI guess we have to say hi.\n",
-                name
-            ),
+    fn greet(&self, name: &str, language: Language, time: TimeOfDay) -> String {
+        language
+            .template(self)
+            .replace("{salutation}", language.salutation(time))
+            .replace("{snark}", language.snark(time))
+            .replace("{name}", name)
+    }
+}
+
+#[derive(Clone, Copy)]
+enum TimeOfDay {
+    Morning,
+    Afternoon,
+    Evening,
+}
+
+impl TimeOfDay {
+    /// Morning from 5:00, afternoon from 12:00, evening from 18:00
+    fn at(time: NaiveTime) -> TimeOfDay {
+        match time.hour() {
+            5..=11 => TimeOfDay::Morning,
+            12..=17 => TimeOfDay::Afternoon,
+            _ => TimeOfDay::Evening,
+        }
+    }
+
+    fn now() -> TimeOfDay {
+        TimeOfDay::at(Local::now().time())
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Language {
+    English,
+    Spanish,
+    French,
+    German,
+}
+
+impl Language {
+    /// Accepts a plain code like `fr` as well as a locale like `fr_FR.UTF-8`
+    fn from_code(code: &str) -> Option<Language> {
+        let code = code.split(['_', '-', '.']).next().unwrap_or_default();
+        match code.to_lowercase().as_str() {
+            "en" => Some(Language::English),
+            "es" => Some(Language::Spanish),
+            "fr" => Some(Language::French),
+            "de" => Some(Language::German),
+            _ => None,
+        }
+    }
+
+    /// `--lang` if given, otherwise `LANG`, otherwise English
+    fn select(code: Option<&str>) -> Language {
+        match code {
+            Some(code) => Language::from_code(code).unwrap_or_else(|| {
+                eprintln!("Warning: unknown language '{}', greeting in English.", code);
+                Language::English
+            }),
+            None => env::var("LANG")
+                .ok()
+                .and_then(|lang| Language::from_code(&lang))
+                .unwrap_or(Language::English),
+        }
+    }
+
+    fn template(self, manner: &Manner) -> &'static str {
+        match (self, manner) {
+            (Language::English, Manner::Polite) => "\n{salutation}, nice to meet you {name}!\n",
+            (Language::English, Manner::Rude) => "\nOh, it's you {name}...,\n---\n{snark}\n",
+            (Language::English, Manner::Formal) => "\nGood day, Mr./Ms. {name}.\n",
+            (Language::English, Manner::Casual) => "\nHey {name}!\n",
+            (Language::Spanish, Manner::Polite) => {
+                "\n¡{salutation}, encantado de conocerte, {name}!\n"
+            }
+            (Language::Spanish, Manner::Rude) => "\nAh, eres tú, {name}...,\n---\n{snark}\n",
+            (Language::Spanish, Manner::Formal) => "\nBuen día, Sr./Sra. {name}.\n",
+            (Language::Spanish, Manner::Casual) => "\n¡Hola, {name}!\n",
+            (Language::French, Manner::Polite) => {
+                "\n{salutation}, ravi de vous rencontrer, {name} !\n"
+            }
+            (Language::French, Manner::Rude) => "\nOh, c'est toi, {name}...,\n---\n{snark}\n",
+            (Language::French, Manner::Formal) => "\nBonjour, M./Mme {name}.\n",
+            (Language::French, Manner::Casual) => "\nSalut {name} !\n",
+            (Language::German, Manner::Polite) => {
+                "\n{salutation}, schön, Sie kennenzulernen, {name}!\n"
+            }
+            (Language::German, Manner::Rude) => "\nAch, du bist es, {name}...,\n---\n{snark}\n",
+            (Language::German, Manner::Formal) => "\nGuten Tag, Herr/Frau {name}.\n",
+            (Language::German, Manner::Casual) => "\nHey {name}!\n",
+        }
+    }
+
+    fn salutation(self, time: TimeOfDay) -> &'static str {
+        match (self, time) {
+            (Language::English, TimeOfDay::Morning) => "Good morning",
+            (Language::English, TimeOfDay::Afternoon) => "Good afternoon",
+            (Language::English, TimeOfDay::Evening) => "Good evening",
+            (Language::Spanish, TimeOfDay::Morning) => "Buenos días",
+            (Language::Spanish, TimeOfDay::Afternoon) => "Buenas tardes",
+            (Language::Spanish, TimeOfDay::Evening) => "Buenas noches",
+            (Language::French, TimeOfDay::Morning | TimeOfDay::Afternoon) => "Bonjour",
+            (Language::French, TimeOfDay::Evening) => "Bonsoir",
+            (Language::German, TimeOfDay::Morning) => "Guten Morgen",
+            (Language::German, TimeOfDay::Afternoon) => "Guten Tag",
+            (Language::German, TimeOfDay::Evening) => "Guten Abend",
+        }
+    }
+
+    fn snark(self, time: TimeOfDay) -> &'static str {
+        match (self, time) {
+            (Language::English, TimeOfDay::Morning) => {
+                "Too early for this, but I guess we have to say hi."
+            }
+            (Language::English, TimeOfDay::Afternoon) => "I guess we have to say hi.",
+            (Language::English, TimeOfDay::Evening) => {
+                "I was about to leave, but I guess we have to say hi."
+            }
+            (Language::Spanish, TimeOfDay::Morning) => {
+                "Es muy temprano, pero supongo que hay que saludar."
+            }
+            (Language::Spanish, TimeOfDay::Afternoon) => "Supongo que hay que saludar.",
+            (Language::Spanish, TimeOfDay::Evening) => {
+                "Ya me iba, pero supongo que hay que saludar."
+            }
+            (Language::French, TimeOfDay::Morning) => {
+                "C'est bien trop tôt, mais il faut bien dire bonjour."
+            }
+            (Language::French, TimeOfDay::Afternoon) => "Il faut bien dire bonjour.",
+            (Language::French, TimeOfDay::Evening) => {
+                "J'allais partir, mais il faut bien dire bonsoir."
+            }
+            (Language::German, TimeOfDay::Morning) => "Viel zu früh dafür, aber dann eben hallo.",
+            (Language::German, TimeOfDay::Afternoon) => "Dann eben hallo.",
+            (Language::German, TimeOfDay::Evening) => {
+                "Ich wollte gerade gehen, aber dann eben hallo."
+            }
         }
     }
 }
 
+const NAME_ATTEMPTS: usize = 3;
+
 fn check_greeting(name: &str) -> Manner {
     if name.is_empty() {
         return Manner::Rude;
     }
 
     let mut is_start_of_word = true;
+    // Apostrophes and hyphens may only join two capitalized parts, as in O'Brien or Jean-Luc
+    let mut after_joiner = false;
 
     let is_polite = name.chars().all(|c| {
         if c.is_whitespace() {
             is_start_of_word = true;
-            true 
+            !after_joiner
         } else if c.is_alphabetic() {
+            after_joiner = false;
             if is_start_of_word {
                 is_start_of_word = false;
                 c.is_uppercase()
             } else {
                 c.is_lowercase()
             }
+        } else if matches!(c, '\'' | '\u{2019}' | '-') {
+            let follows_letter = !is_start_of_word;
+            is_start_of_word = true;
+            after_joiner = true;
+            follows_letter
         } else {
             false
         }
-    });
+    }) && !after_joiner;
 
     if is_polite {
         Manner::Polite
@@ -48,38 +237,167 @@ fn check_greeting(name: &str) -> Manner {
     }
 }
 
+fn parse_time(input: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(input, "%H:%M")
+        .map_err(|_| format!("Invalid time '{}'. Use HH:MM, e.g. 09:30.", input))
+}
 
-fn main() {
-    println!(
-        "
-Nice to meet you!
-What is your name? (e.g., John or mary)"
-    );
-    let mut name_input = String::new();
+fn parse_age(input: &str) -> Result<u8, String> {
+    input
+        .trim()
+        .parse::<u8>()
+        .map_err(|_| "That's not a valid age! Please enter a number.".to_string())
+}
+
+/// A follow-up remark on the given age
+fn age_comment(age: u8) -> &'static str {
+    match age {
+        0..=12 => "You're still a kid!",
+        13..=19 => "Enjoy your teens!",
+        20..=29 => "Make the most of your twenties!",
+        30..=64 => "You're in your prime!",
+        _ => "Respect your wisdom!",
+    }
+}
+
+fn read_line() -> String {
+    let mut input = String::new();
     io::stdin()
-        .read_line(&mut name_input)
+        .read_line(&mut input)
         .expect("Failed to read line");
+    input
+}
 
-    let name = name_input.trim().to_string();
+/// What `--json` prints
+#[derive(Serialize)]
+struct GreetingResult {
+    name: String,
+    manner: Manner,
+    greeting: String,
+    age: Option<u8>,
+    age_valid: bool,
+}
 
-    let manner = check_greeting(&name);
-    let display_greeting = manner.greet(&name);
+/// Shows a question, on stderr when stdout is reserved for JSON
+fn prompt(json: bool, text: &str) {
+    if json {
+        eprintln!("{}", text);
+    } else {
+        println!("{}", text);
+    }
+}
 
-    println!("{}", display_greeting);
+/// Asks for a name until a non-blank one is given, at most `NAME_ATTEMPTS` times.
+/// After that the blank name is used, and gets the rude greeting.
+fn prompt_for_name(json: bool) -> String {
+    let mut name = String::new();
+    for attempt in 1..=NAME_ATTEMPTS {
+        name = read_line().trim().to_string();
+        if !name.is_empty() {
+            break;
+        }
+        if attempt < NAME_ATTEMPTS {
+            prompt(json, "Please enter a name.");
+        }
+    }
+    name
+}
 
-    println!("What is your age?");
+/// Greets every non-blank line of `path`, judging each name on its own unless a manner is forced
+fn greet_roster(
+    path: &Path,
+    manner: Option<Manner>,
+    language: Language,
+    time_of_day: TimeOfDay,
+) -> io::Result<(String, usize)> {
+    let mut greetings = String::new();
+    let mut count = 0;
+    for name in fs::read_to_string(path)?
+        .lines()
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+    {
+        let manner = manner.unwrap_or_else(|| check_greeting(name));
+        greetings.push_str(&manner.greet(name, language, time_of_day));
+        count += 1;
+    }
+    Ok((greetings, count))
+}
 
-    let mut age = String::new();
-    io::stdin()
-        .read_line(&mut age)
-        .expect("Failed to read line");
+fn main() {
+    let args = Args::parse();
+    let language = Language::select(args.lang.as_deref());
+    let time_of_day = args.time.map_or_else(TimeOfDay::now, TimeOfDay::at);
+
+    if let Some(path) = &args.from_file {
+        let (greetings, count) = greet_roster(path, args.manner, language, time_of_day)
+            .unwrap_or_else(|e| {
+                eprintln!("Could not read {}: {}", path.display(), e);
+                std::process::exit(1);
+            });
+        match &args.output {
+            Some(output) => {
+                if let Err(e) = fs::write(output, greetings) {
+                    eprintln!("Could not write {}: {}", output.display(), e);
+                    std::process::exit(1);
+                }
+                println!("Wrote {} greeting(s) to {}", count, output.display());
+            }
+            None => print!("{}", greetings),
+        }
+        return;
+    }
+
+    let name = args
+        .name
+        .map(|name| name.trim().to_string())
+        .unwrap_or_else(|| {
+            prompt(
+                args.json,
+                "
+Nice to meet you!
+What is your name? (e.g., John or mary)",
+            );
+            prompt_for_name(args.json)
+        });
+
+    let manner = args.manner.unwrap_or_else(|| check_greeting(&name));
+    let display_greeting = manner.greet(&name, language, time_of_day);
+
+    if !args.json {
+        println!("{}", display_greeting);
+    }
+
+    let age = match args.age {
+        Some(age) => Ok(age),
+        None => {
+            prompt(args.json, "What is your age?");
+            parse_age(&read_line())
+        }
+    };
+
+    if args.json {
+        let result = GreetingResult {
+            name,
+            manner,
+            greeting: display_greeting.trim().to_string(),
+            age: age.as_ref().ok().copied(),
+            age_valid: age.is_ok(),
+        };
+        println!(
+            "{}",
+            serde_json::to_string(&result).expect("Failed to serialize the greeting")
+        );
+        return;
+    }
 
-    match age.trim().parse::<u8>() {
+    match age {
         Ok(age) => {
             print!("\nYou are {} years old!", age);
+            print!("\n{}", age_comment(age));
         }
-        Err(_) => {
-            print!("\nThat's not a valid age! Please enter a number.");
+        Err(message) => {
+            print!("\n{}", message);
         }
     }
 }
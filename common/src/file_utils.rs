@@ -0,0 +1,40 @@
+//! File helpers for the read/append/rewrite patterns the CLIs have in common.
+
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Read a file to a string, treating a missing file as empty
+pub fn read_to_string_or_empty(path: &Path) -> io::Result<String> {
+    match fs::read_to_string(path) {
+        Ok(contents) => Ok(contents),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(String::new()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Replace a file's contents without ever leaving it half-written.
+/// The data goes to a sibling `.tmp` file first which is then renamed over the target.
+/// Being in the same directory, the temp file is on the same filesystem, so the
+/// rename is atomic and never fails with `ErrorKind::CrossesDevices`. That is why
+/// there is deliberately no copy-and-fsync fallback: a copy could be interrupted
+/// halfway, which is exactly what this function exists to prevent.
+pub fn write_atomic(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let tmp_path = tmp_path_for(path);
+    fs::write(&tmp_path, contents)?;
+
+    fs::rename(&tmp_path, path).inspect_err(|_| {
+        let _ = fs::remove_file(&tmp_path);
+    })
+}
+
+/// Open a file for appending, creating it if it does not exist yet
+pub fn open_append_create(path: &Path) -> io::Result<File> {
+    File::options().append(true).create(true).open(path)
+}
+
+fn tmp_path_for(path: &Path) -> PathBuf {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    PathBuf::from(tmp)
+}
@@ -0,0 +1,3 @@
+//! Helpers shared between the workspace projects.
+
+pub mod file_utils;
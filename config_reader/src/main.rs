@@ -2,11 +2,12 @@ use std::collections::HashMap;
 use std::env;
 use std::fs;
 use std::path::Path;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::{Duration, SystemTime};
 use serde::{Deserialize, Serialize};
-use clap::{Arg, Command};
-use toml;
-use serde_json;
-use serde_yaml;
+use clap::{Arg, ArgMatches, Command};
+use common::file_utils::write_atomic;
 
 /// Configuration structure that can be loaded from multiple sources
 /// Supports TOML, JSON, YAML files, environment variables, and CLI arguments
@@ -32,6 +33,8 @@ pub struct DatabaseConfig {
     pub host: String,
     pub port: u16,
     pub username: String,
+    /// Left out of saved files when empty
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub password: String,
     pub database: String,
     pub max_connections: Option<u32>,
@@ -59,6 +62,27 @@ pub enum CliCommand {
     Info,
     Validate,
     Defaults,
+    Watch,
+    Schema,
+    Merge,
+}
+
+/// How `merge` resolves values present in both files
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MergeStrategy {
+    /// Overlay values replace base values
+    Overlay,
+    /// Like `Overlay`, except a feature flag enabled in either file stays enabled
+    Additive,
+}
+
+/// Arguments of the `merge` subcommand
+#[derive(Debug, Clone)]
+pub struct MergeArgs {
+    pub base: String,
+    pub overlay: String,
+    pub output: Option<String>,
+    pub strategy: MergeStrategy,
 }
 
 /// CLI arguments structure
@@ -66,6 +90,11 @@ pub enum CliCommand {
 pub struct CliArgs {
     pub command: CliCommand,
     pub config_file: Option<String>,
+    pub watch_interval: Duration,
+    pub save_config: Option<String>,
+    /// Arguments of the `run` subcommand, applied on top of file and environment
+    pub run_matches: Option<ArgMatches>,
+    pub merge: Option<MergeArgs>,
 }
 
 /// Error type for configuration operations
@@ -74,6 +103,7 @@ pub enum ConfigError {
     FileNotFound(String),
     ParseError(String),
     ValidationError(String),
+    SerializeError(String),
     IoError(std::io::Error),
 }
 
@@ -83,6 +113,7 @@ impl std::fmt::Display for ConfigError {
             ConfigError::FileNotFound(path) => write!(f, "Configuration file not found: {}", path),
             ConfigError::ParseError(msg) => write!(f, "Parse error: {}", msg),
             ConfigError::ValidationError(msg) => write!(f, "Validation error: {}", msg),
+            ConfigError::SerializeError(msg) => write!(f, "Serialization error: {}", msg),
             ConfigError::IoError(err) => write!(f, "IO error: {}", err),
         }
     }
@@ -135,11 +166,53 @@ fn load_config_from_file<P: AsRef<Path>>(file_path: P) -> Result<AppConfig, Conf
     }
 }
 
+/// Render configuration as pretty-printed TOML
+fn config_to_toml_string(config: &AppConfig) -> Result<String, ConfigError> {
+    toml::to_string_pretty(config)
+        .map_err(|e| ConfigError::SerializeError(format!("TOML serialize error: {}", e)))
+}
+
+/// Save configuration as TOML
+/// The file is replaced atomically so a reader never sees it half-written
+fn save_config_as_toml(config: &AppConfig, path: &Path) -> Result<(), ConfigError> {
+    let contents = config_to_toml_string(config)?;
+    write_atomic(path, contents.as_bytes())?;
+    Ok(())
+}
+
+/// Save configuration in the format matching the file extension
+/// Unknown extensions fall back to TOML
+fn save_config_to_file(config: &AppConfig, path: &Path) -> Result<(), ConfigError> {
+    let contents = match detect_format_from_extension(path) {
+        Some(ConfigFormat::Json) => serde_json::to_string_pretty(config)
+            .map(|json| json + "\n")
+            .map_err(|e| ConfigError::SerializeError(format!("JSON serialize error: {}", e)))?,
+        Some(ConfigFormat::Yaml) => serde_yaml::to_string(config)
+            .map_err(|e| ConfigError::SerializeError(format!("YAML serialize error: {}", e)))?,
+        Some(ConfigFormat::Toml) | None => config_to_toml_string(config)?,
+    };
+    write_atomic(path, contents.as_bytes())?;
+    Ok(())
+}
+
+/// Write the resolved configuration to the `--save-config` path, if one was given
+fn save_requested_config(cli_args: &CliArgs, config: &AppConfig) {
+    if let Some(path) = &cli_args.save_config {
+        match save_config_as_toml(config, Path::new(path)) {
+            Ok(()) => println!("💾 Saved configuration to {}", path),
+            Err(e) => {
+                eprintln!("❌ Failed to save configuration: {}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+}
+
 /// Load configuration from environment variables
 /// Looks for variables with APP_ prefix (e.g., APP_SERVER_HOST, APP_DATABASE_PORT)
 /// Merges with existing config if provided
 fn load_config_from_env(existing_config: Option<AppConfig>) -> Result<AppConfig, ConfigError> {
-    let mut config = existing_config.unwrap_or_else(create_default_config);
+    let mut config = existing_config.unwrap_or_default();
 
     // Server configuration
     if let Ok(host) = env::var("APP_SERVER_HOST") {
@@ -185,28 +258,32 @@ fn load_config_from_env(existing_config: Option<AppConfig>) -> Result<AppConfig,
     }
 
     // Feature flags
-    for (key, _) in env::vars() {
-        if key.starts_with("APP_FEATURES_") {
-            let feature_name = key.strip_prefix("APP_FEATURES_").unwrap().to_lowercase();
-            if let Ok(value_str) = env::var(&key) {
-                if let Ok(value) = value_str.parse::<bool>() {
-                    config.features.insert(feature_name, value);
-                }
-            }
+    for (key, value_str) in env::vars() {
+        if let Some(feature_name) = key.strip_prefix("APP_FEATURES_")
+            && let Ok(value) = value_str.parse::<bool>()
+        {
+            config.features.insert(feature_name.to_lowercase(), value);
         }
     }
 
     Ok(config)
 }
 
-/// Load configuration from command line arguments
+/// Parse command line arguments
 /// Uses clap to define and parse CLI arguments
-/// Highest priority - overrides file and env configs
-fn load_config_from_args() -> Result<(AppConfig, CliArgs), ConfigError> {
+/// The `run` overrides are applied last by `load_config` (highest priority)
+fn load_config_from_args() -> CliArgs {
     let matches = Command::new("Config Reader")
         .version("1.0")
         .author("Rust Config Reader")
         .about("Multi-source configuration loader")
+        .arg(
+            Arg::new("save-config")
+                .long("save-config")
+                .value_name("PATH")
+                .global(true)
+                .help("Write the resolved configuration to a TOML file")
+        )
         .subcommand(
             Command::new("info")
                 .about("Display current configuration information")
@@ -233,6 +310,62 @@ fn load_config_from_args() -> Result<(AppConfig, CliArgs), ConfigError> {
             Command::new("defaults")
                 .about("Show default configuration values")
         )
+        .subcommand(
+            Command::new("schema")
+                .about("Print the JSON Schema of the configuration file")
+        )
+        .subcommand(
+            Command::new("merge")
+                .about("Merge an overlay file into a base configuration file")
+                .arg(
+                    Arg::new("base")
+                        .long("base")
+                        .value_name("FILE")
+                        .required(true)
+                        .help("Base configuration file")
+                )
+                .arg(
+                    Arg::new("overlay")
+                        .long("overlay")
+                        .value_name("FILE")
+                        .required(true)
+                        .help("Configuration file applied on top of the base")
+                )
+                .arg(
+                    Arg::new("output")
+                        .long("output")
+                        .short('o')
+                        .value_name("FILE")
+                        .help("Where to write the result, format taken from the extension (default: stdout as TOML)")
+                )
+                .arg(
+                    Arg::new("strategy")
+                        .long("strategy")
+                        .value_name("STRATEGY")
+                        .value_parser(["overlay", "additive"])
+                        .default_value("overlay")
+                        .help("overlay: overlay values win; additive: also keep features enabled in either file")
+                )
+        )
+        .subcommand(
+            Command::new("watch")
+                .about("Print the configuration file again each time it changes")
+                .arg(
+                    Arg::new("config")
+                        .long("config")
+                        .short('c')
+                        .value_name("FILE")
+                        .help("Configuration file to watch")
+                )
+                .arg(
+                    Arg::new("interval")
+                        .long("interval")
+                        .value_name("MS")
+                        .default_value("500")
+                        .value_parser(clap::value_parser!(u64).range(1..))
+                        .help("How often to check the file, in milliseconds")
+                )
+        )
         .subcommand(
             Command::new("run")
                 .about("Run application with configuration")
@@ -312,13 +445,19 @@ fn load_config_from_args() -> Result<(AppConfig, CliArgs), ConfigError> {
         )
         .get_matches();
 
-    let cli_args = CliArgs {
-        command: if let Some(_) = matches.subcommand_matches("info") {
+    CliArgs {
+        command: if matches.subcommand_matches("info").is_some() {
             CliCommand::Info
-        } else if let Some(_) = matches.subcommand_matches("validate") {
+        } else if matches.subcommand_matches("validate").is_some() {
             CliCommand::Validate
-        } else if let Some(_) = matches.subcommand_matches("defaults") {
+        } else if matches.subcommand_matches("defaults").is_some() {
             CliCommand::Defaults
+        } else if matches.subcommand_matches("watch").is_some() {
+            CliCommand::Watch
+        } else if matches.subcommand_matches("schema").is_some() {
+            CliCommand::Schema
+        } else if matches.subcommand_matches("merge").is_some() {
+            CliCommand::Merge
         } else {
             CliCommand::Run
         },
@@ -327,64 +466,95 @@ fn load_config_from_args() -> Result<(AppConfig, CliArgs), ConfigError> {
             .or_else(|| matches.subcommand_matches("info")
                 .and_then(|m| m.get_one::<String>("config").cloned()))
             .or_else(|| matches.subcommand_matches("validate")
+                .and_then(|m| m.get_one::<String>("config").cloned()))
+            .or_else(|| matches.subcommand_matches("watch")
                 .and_then(|m| m.get_one::<String>("config").cloned())),
-    };
-
-    let mut config = create_default_config();
+        save_config: matches.subcommand()
+            .and_then(|(_, m)| m.get_one::<String>("save-config").cloned())
+            .or_else(|| matches.get_one::<String>("save-config").cloned()),
+        watch_interval: matches
+            .subcommand_matches("watch")
+            .and_then(|m| m.get_one::<u64>("interval").copied())
+            .map(Duration::from_millis)
+            .unwrap_or_default(),
+        run_matches: matches.subcommand_matches("run").cloned(),
+        merge: matches.subcommand_matches("merge").map(|m| MergeArgs {
+            base: m.get_one::<String>("base").cloned().unwrap_or_default(),
+            overlay: m.get_one::<String>("overlay").cloned().unwrap_or_default(),
+            output: m.get_one::<String>("output").cloned(),
+            strategy: match m.get_one::<String>("strategy").map(String::as_str) {
+                Some("additive") => MergeStrategy::Additive,
+                _ => MergeStrategy::Overlay,
+            },
+        }),
+    }
+}
 
-    // Only parse run command arguments
-    if let Some(run_matches) = matches.subcommand_matches("run") {
-        // Server configuration
-        if let Some(host) = run_matches.get_one::<String>("server-host") {
-            config.server.host = host.clone();
-        }
-        if let Some(port_str) = run_matches.get_one::<String>("server-port") {
-            config.server.port = port_str.parse()
-                .map_err(|_| ConfigError::ParseError("Invalid server port".to_string()))?;
-        }
-        if let Some(workers_str) = run_matches.get_one::<String>("server-workers") {
-            config.server.workers = Some(workers_str.parse()
-                .map_err(|_| ConfigError::ParseError("Invalid server workers".to_string()))?);
-        }
+/// Apply the `run` command line overrides to a configuration
+/// Only arguments that were actually given replace existing values
+fn apply_cli_overrides(
+    config: &mut AppConfig,
+    run_matches: &ArgMatches,
+) -> Result<(), ConfigError> {
+    // Server configuration
+    if let Some(host) = run_matches.get_one::<String>("server-host") {
+        config.server.host = host.clone();
+    }
+    if let Some(port_str) = run_matches.get_one::<String>("server-port") {
+        config.server.port = port_str
+            .parse()
+            .map_err(|_| ConfigError::ParseError("Invalid server port".to_string()))?;
+    }
+    if let Some(workers_str) = run_matches.get_one::<String>("server-workers") {
+        config.server.workers = Some(
+            workers_str
+                .parse()
+                .map_err(|_| ConfigError::ParseError("Invalid server workers".to_string()))?,
+        );
+    }
 
-        // Database configuration
-        if let Some(host) = run_matches.get_one::<String>("database-host") {
-            config.database.host = host.clone();
-        }
-        if let Some(port_str) = run_matches.get_one::<String>("database-port") {
-            config.database.port = port_str.parse()
-                .map_err(|_| ConfigError::ParseError("Invalid database port".to_string()))?;
-        }
-        if let Some(username) = run_matches.get_one::<String>("database-username") {
-            config.database.username = username.clone();
-        }
-        if let Some(password) = run_matches.get_one::<String>("database-password") {
-            config.database.password = password.clone();
-        }
-        if let Some(database) = run_matches.get_one::<String>("database-name") {
-            config.database.database = database.clone();
-        }
-        if let Some(max_conn_str) = run_matches.get_one::<String>("database-max-connections") {
-            config.database.max_connections = Some(max_conn_str.parse()
-                .map_err(|_| ConfigError::ParseError("Invalid max connections".to_string()))?);
-        }
+    // Database configuration
+    if let Some(host) = run_matches.get_one::<String>("database-host") {
+        config.database.host = host.clone();
+    }
+    if let Some(port_str) = run_matches.get_one::<String>("database-port") {
+        config.database.port = port_str
+            .parse()
+            .map_err(|_| ConfigError::ParseError("Invalid database port".to_string()))?;
+    }
+    if let Some(username) = run_matches.get_one::<String>("database-username") {
+        config.database.username = username.clone();
+    }
+    if let Some(password) = run_matches.get_one::<String>("database-password") {
+        config.database.password = password.clone();
+    }
+    if let Some(database) = run_matches.get_one::<String>("database-name") {
+        config.database.database = database.clone();
+    }
+    if let Some(max_conn_str) = run_matches.get_one::<String>("database-max-connections") {
+        config.database.max_connections = Some(
+            max_conn_str
+                .parse()
+                .map_err(|_| ConfigError::ParseError("Invalid max connections".to_string()))?,
+        );
+    }
 
-        // Logging configuration
-        if let Some(level) = run_matches.get_one::<String>("logging-level") {
-            config.logging.level = level.clone();
-        }
-        if let Some(file) = run_matches.get_one::<String>("logging-file") {
-            config.logging.file = Some(file.clone());
-        }
+    // Logging configuration
+    if let Some(level) = run_matches.get_one::<String>("logging-level") {
+        config.logging.level = level.clone();
+    }
+    if let Some(file) = run_matches.get_one::<String>("logging-file") {
+        config.logging.file = Some(file.clone());
     }
 
-    Ok((config, cli_args))
+    Ok(())
 }
 
 /// Merge multiple configuration sources with priority order
 /// Priority: CLI args > Environment variables > Config file
 /// Later sources override earlier ones for conflicting keys
-fn merge_configs(base: AppConfig, overrides: AppConfig) -> AppConfig {
+/// With `MergeStrategy::Additive` a feature flag set in either source stays set
+fn merge_configs(base: AppConfig, overrides: AppConfig, strategy: MergeStrategy) -> AppConfig {
     let mut merged = base;
 
     // Merge server config
@@ -410,14 +580,64 @@ fn merge_configs(base: AppConfig, overrides: AppConfig) -> AppConfig {
         merged.logging.file = overrides.logging.file;
     }
 
-    // Merge features (overrides take precedence)
+    // Merge features (overrides take precedence unless additive)
     for (key, value) in overrides.features {
-        merged.features.insert(key, value);
+        let enabled = merged.features.entry(key).or_insert(value);
+        if strategy == MergeStrategy::Overlay {
+            *enabled = value;
+        } else {
+            *enabled |= value;
+        }
     }
 
     merged
 }
 
+/// How long a changed file must stay unchanged before it is reloaded,
+/// so an editor's burst of writes is read once and never half-written
+const RELOAD_SETTLE_DELAY: Duration = Duration::from_millis(100);
+
+/// Watch a config file for changes by polling its modification time
+/// Each change is parsed on a background thread and sent on the returned channel,
+/// parse errors included, so a bad edit doesn't stop the watcher
+/// The thread stops once the receiver is dropped
+fn watch_config_file(path: &Path, interval: Duration) -> Receiver<Result<AppConfig, ConfigError>> {
+    let (sender, receiver) = mpsc::channel();
+    let path = path.to_path_buf();
+    let modified_time = |path: &Path| -> Option<SystemTime> {
+        fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+    };
+
+    thread::spawn(move || {
+        let mut last_seen = modified_time(&path);
+        loop {
+            thread::sleep(interval);
+            let mut current = modified_time(&path);
+            if current == last_seen {
+                continue;
+            }
+
+            loop {
+                thread::sleep(RELOAD_SETTLE_DELAY);
+                let latest = modified_time(&path);
+                if latest == current {
+                    break;
+                }
+                current = latest;
+            }
+            last_seen = current;
+
+            if sender.send(load_config_from_file(&path)).is_err() {
+                break;
+            }
+        }
+    });
+
+    receiver
+}
+
 /// Validate the final configuration
 /// Checks for required fields, valid ranges, and logical consistency
 fn validate_config(config: &AppConfig) -> Result<(), ConfigError> {
@@ -428,10 +648,12 @@ fn validate_config(config: &AppConfig) -> Result<(), ConfigError> {
     if config.server.port == 0 {
         return Err(ConfigError::ValidationError("Server port must be greater than 0".to_string()));
     }
-    if let Some(workers) = config.server.workers {
-        if workers == 0 {
-            return Err(ConfigError::ValidationError("Server workers must be greater than 0".to_string()));
-        }
+    if let Some(workers) = config.server.workers
+        && workers == 0
+    {
+        return Err(ConfigError::ValidationError(
+            "Server workers must be greater than 0".to_string(),
+        ));
     }
 
     // Validate database configuration
@@ -447,10 +669,12 @@ fn validate_config(config: &AppConfig) -> Result<(), ConfigError> {
     if config.database.database.is_empty() {
         return Err(ConfigError::ValidationError("Database name cannot be empty".to_string()));
     }
-    if let Some(max_conn) = config.database.max_connections {
-        if max_conn == 0 {
-            return Err(ConfigError::ValidationError("Database max connections must be greater than 0".to_string()));
-        }
+    if let Some(max_conn) = config.database.max_connections
+        && max_conn == 0
+    {
+        return Err(ConfigError::ValidationError(
+            "Database max connections must be greater than 0".to_string(),
+        ));
     }
 
     // Validate logging configuration
@@ -467,6 +691,104 @@ fn validate_config(config: &AppConfig) -> Result<(), ConfigError> {
     Ok(())
 }
 
+/// JSON Schema (draft 2020-12) describing the configuration file
+/// Written by hand to mirror `AppConfig` and the rules of `validate_config`
+/// Keep it in sync when fields or checks change
+fn generate_json_schema() -> serde_json::Value {
+    serde_json::json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "AppConfig",
+        "description": "Configuration loaded by config_reader from TOML, JSON or YAML",
+        "type": "object",
+        "required": ["server", "database", "logging", "features"],
+        "properties": {
+            "server": {
+                "description": "Server configuration section",
+                "type": "object",
+                "required": ["host", "port"],
+                "properties": {
+                    "host": {
+                        "description": "Address the server listens on",
+                        "type": "string",
+                        "minLength": 1
+                    },
+                    "port": {
+                        "description": "Port the server listens on",
+                        "type": "integer",
+                        "minimum": 1,
+                        "maximum": u16::MAX
+                    },
+                    "workers": {
+                        "description": "Number of worker threads",
+                        "type": ["integer", "null"],
+                        "minimum": 1,
+                        "maximum": u32::MAX
+                    }
+                }
+            },
+            "database": {
+                "description": "Database configuration section",
+                "type": "object",
+                "required": ["host", "port", "username", "database"],
+                "properties": {
+                    "host": {
+                        "description": "Database host address",
+                        "type": "string",
+                        "minLength": 1
+                    },
+                    "port": {
+                        "description": "Database port",
+                        "type": "integer",
+                        "minimum": 1,
+                        "maximum": u16::MAX
+                    },
+                    "username": {
+                        "description": "User to connect as",
+                        "type": "string",
+                        "minLength": 1
+                    },
+                    "password": {
+                        "description": "Password of the user, empty if none",
+                        "type": "string"
+                    },
+                    "database": {
+                        "description": "Name of the database to use",
+                        "type": "string",
+                        "minLength": 1
+                    },
+                    "max_connections": {
+                        "description": "Upper bound of the connection pool",
+                        "type": ["integer", "null"],
+                        "minimum": 1,
+                        "maximum": u32::MAX
+                    }
+                }
+            },
+            "logging": {
+                "description": "Logging configuration section",
+                "type": "object",
+                "required": ["level"],
+                "properties": {
+                    "level": {
+                        "description": "Minimum level of messages to log",
+                        "type": "string",
+                        "enum": ["debug", "info", "warn", "error"]
+                    },
+                    "file": {
+                        "description": "File to write logs to",
+                        "type": ["string", "null"]
+                    }
+                }
+            },
+            "features": {
+                "description": "Feature flags by name",
+                "type": "object",
+                "additionalProperties": { "type": "boolean" }
+            }
+        }
+    })
+}
+
 /// Determine config file format from file extension
 fn detect_format_from_extension(file_path: &Path) -> Option<ConfigFormat> {
     file_path
@@ -480,48 +802,70 @@ fn detect_format_from_extension(file_path: &Path) -> Option<ConfigFormat> {
         })
 }
 
-/// Create default configuration with sensible defaults
-fn create_default_config() -> AppConfig {
-    let mut features = HashMap::new();
-    features.insert("debug_mode".to_string(), false);
-    features.insert("metrics".to_string(), true);
-    features.insert("cache".to_string(), true);
+/// Default configuration with sensible defaults
+/// Sub-configs use their own `Default` impls so partial overrides work
+/// with struct update syntax (`..Default::default()`)
+impl Default for AppConfig {
+    fn default() -> Self {
+        let mut features = HashMap::new();
+        features.insert("debug_mode".to_string(), false);
+        features.insert("metrics".to_string(), true);
+        features.insert("cache".to_string(), true);
+
+        AppConfig {
+            server: ServerConfig::default(),
+            database: DatabaseConfig::default(),
+            logging: LoggingConfig::default(),
+            features,
+        }
+    }
+}
 
-    AppConfig {
-        server: ServerConfig {
+impl Default for ServerConfig {
+    fn default() -> Self {
+        ServerConfig {
             host: "127.0.0.1".to_string(),
             port: 8080,
             workers: Some(4),
-        },
-        database: DatabaseConfig {
+        }
+    }
+}
+
+impl Default for DatabaseConfig {
+    fn default() -> Self {
+        DatabaseConfig {
             host: "localhost".to_string(),
             port: 5432,
             username: "postgres".to_string(),
             password: "".to_string(),
             database: "myapp".to_string(),
             max_connections: Some(10),
-        },
-        logging: LoggingConfig {
+        }
+    }
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        LoggingConfig {
             level: "info".to_string(),
             file: Some("app.log".to_string()),
-        },
-        features,
+        }
     }
 }
 
 /// Main configuration loading function
 /// Orchestrates loading from all sources in priority order
 /// Priority: CLI args > Environment > Config file > Defaults
-fn load_config(cli_args: &CliArgs, cli_overrides: AppConfig) -> Result<AppConfig, ConfigError> {
+fn load_config(cli_args: &CliArgs) -> Result<AppConfig, ConfigError> {
     // Start with defaults
-    let mut config = create_default_config();
+    let mut config = AppConfig::default();
 
     // Load from config file if specified or default exists
     let config_file_path = cli_args.config_file.as_deref().unwrap_or("config.toml");
     if Path::new(config_file_path).exists() {
         match load_config_from_file(config_file_path) {
             Ok(file_config) => {
-                config = merge_configs(config, file_config);
+                config = merge_configs(config, file_config, MergeStrategy::Overlay);
             }
             Err(ConfigError::FileNotFound(_)) => {
                 // Config file not found, continue with defaults
@@ -534,7 +878,9 @@ fn load_config(cli_args: &CliArgs, cli_overrides: AppConfig) -> Result<AppConfig
     config = load_config_from_env(Some(config))?;
 
     // Apply CLI overrides (highest priority)
-    config = merge_configs(config, cli_overrides);
+    if let Some(run_matches) = &cli_args.run_matches {
+        apply_cli_overrides(&mut config, run_matches)?;
+    }
 
     // Validate final configuration
     validate_config(&config)?;
@@ -579,19 +925,22 @@ fn print_config(config: &AppConfig) {
 /// Main application entry point
 /// Demonstrates configuration loading and usage
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let (cli_config, cli_args) = load_config_from_args()?;
+    let cli_args = load_config_from_args();
 
     match cli_args.command {
         CliCommand::Defaults => {
             println!("📋 Default Configuration:");
-            print_config(&create_default_config());
+            let config = AppConfig::default();
+            print_config(&config);
+            save_requested_config(&cli_args, &config);
         }
 
         CliCommand::Info => {
-            match load_config(&cli_args, cli_config.clone()) {
+            match load_config(&cli_args) {
                 Ok(config) => {
                     println!("ℹ️  Configuration Information:");
                     print_config(&config);
+                    save_requested_config(&cli_args, &config);
                 }
                 Err(e) => {
                     eprintln!("❌ Failed to load configuration: {}", e);
@@ -601,11 +950,12 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
 
         CliCommand::Validate => {
-            match load_config(&cli_args, cli_config.clone()) {
+            match load_config(&cli_args) {
                 Ok(config) => {
                     println!("✅ Configuration is valid!");
                     println!("📄 Loaded from: {}", cli_args.config_file.as_deref().unwrap_or("defaults"));
                     println!("🔧 Sources merged: defaults + file + environment + CLI");
+                    save_requested_config(&cli_args, &config);
                 }
                 Err(e) => {
                     eprintln!("❌ Configuration validation failed: {}", e);
@@ -614,8 +964,67 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             }
         }
 
+        CliCommand::Schema => {
+            println!("{}", serde_json::to_string_pretty(&generate_json_schema())?);
+        }
+
+        CliCommand::Merge => {
+            let merge = cli_args
+                .merge
+                .as_ref()
+                .expect("merge arguments are parsed with the merge command");
+            let merged = load_config_from_file(&merge.base)
+                .and_then(|base| {
+                    Ok(merge_configs(
+                        base,
+                        load_config_from_file(&merge.overlay)?,
+                        merge.strategy,
+                    ))
+                })
+                .and_then(|config| validate_config(&config).map(|()| config));
+            let config = merged.unwrap_or_else(|e| {
+                eprintln!("❌ Failed to merge configuration: {}", e);
+                std::process::exit(1);
+            });
+
+            match &merge.output {
+                Some(output) => {
+                    if let Err(e) = save_config_to_file(&config, Path::new(output)) {
+                        eprintln!("❌ Failed to save configuration: {}", e);
+                        std::process::exit(1);
+                    }
+                    println!(
+                        "🔀 Merged {} and {} into {}",
+                        merge.base, merge.overlay, output
+                    );
+                }
+                None => print!("{}", config_to_toml_string(&config)?),
+            }
+            save_requested_config(&cli_args, &config);
+        }
+
+        CliCommand::Watch => {
+            let config_file = cli_args.config_file.as_deref().unwrap_or("config.toml");
+            let config = load_config_from_file(config_file).unwrap_or_else(|e| {
+                eprintln!("❌ Failed to load configuration: {}", e);
+                std::process::exit(1);
+            });
+            print_config(&config);
+            println!("👀 Watching {} for changes (Ctrl+C to stop)", config_file);
+
+            for reloaded in watch_config_file(Path::new(config_file), cli_args.watch_interval) {
+                match reloaded {
+                    Ok(config) => {
+                        println!("🔄 Configuration reloaded:");
+                        print_config(&config);
+                    }
+                    Err(e) => eprintln!("❌ Reload failed: {}", e),
+                }
+            }
+        }
+
         CliCommand::Run => {
-            match load_config(&cli_args, cli_config.clone()) {
+            match load_config(&cli_args) {
                 Ok(config) => {
                     println!("🚀 Starting application with configuration:");
                     print_config(&config);
@@ -635,6 +1044,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                         println!("   Debug mode: ENABLED");
                     }
 
+                    save_requested_config(&cli_args, &config);
+
                     println!("\n✅ Application ready!");
                 }
                 Err(e) => {
@@ -0,0 +1,138 @@
+//! The `--float` variant: the secret is any real number in the range, and a
+//! guess within the tolerance of it wins.
+
+use colored::Colorize;
+use rand::Rng;
+use rand::rngs::StdRng;
+
+use crate::{guesses, play_again, read_line};
+
+/// Misses within this many tolerances of the secret count as close
+const CLOSE_FACTOR: f64 = 10.0;
+
+/// How a guess compares to the secret
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum FloatGuessResult {
+    Low,
+    High,
+    /// A miss within `CLOSE_FACTOR` tolerances, with the guess minus the secret,
+    /// so negative when the guess is too small
+    Close(f64),
+    Win,
+}
+
+/// A guess at most `tolerance` away from the secret wins
+pub fn check_float_guess(secret: f64, guess: f64, tolerance: f64) -> FloatGuessResult {
+    let distance = guess - secret;
+    if distance.abs() <= tolerance {
+        FloatGuessResult::Win
+    } else if distance.abs() <= tolerance * CLOSE_FACTOR {
+        FloatGuessResult::Close(distance)
+    } else if guess < secret {
+        FloatGuessResult::Low
+    } else {
+        FloatGuessResult::High
+    }
+}
+
+/// Plays one game, returning whether the player quit
+fn play_float_round(
+    rng: &mut StdRng,
+    min: f64,
+    max: f64,
+    tolerance: f64,
+    attempts: Option<u32>,
+) -> bool {
+    println!(
+        "Guess the number between {} and {}, to within {}!",
+        min, max, tolerance
+    );
+
+    let secret_number = rng.gen_range(min..=max);
+    let mut guesses_left = attempts;
+    let mut guess_count = 0;
+
+    loop {
+        match guesses_left {
+            Some(0) => {
+                println!("Out of guesses! The number was {}.", secret_number);
+                return false;
+            }
+            Some(1) => println!("1 guess left."),
+            Some(n) => println!("{} guesses left.", n),
+            None => {}
+        }
+        println!("Please input your guess (or q to quit):");
+
+        let Some(input) = read_line() else {
+            println!("No more input. The number was {}.", secret_number);
+            return false;
+        };
+        if matches!(input.trim().to_lowercase().as_str(), "q" | "quit") {
+            println!("The number was {}. Goodbye!", secret_number);
+            return true;
+        }
+
+        // Typos, "NaN" and "inf" included, don't use up a guess
+        let guess = match input.trim().parse::<f64>() {
+            Ok(guess) if guess.is_finite() => guess,
+            _ => {
+                println!("Please type a number!");
+                continue;
+            }
+        };
+        guess_count += 1;
+        if let Some(n) = guesses_left.as_mut() {
+            *n -= 1;
+        }
+
+        // Distance as a percentage of the range
+        let off_by = |distance: f64| distance * 100.0 / (max - min);
+        match check_float_guess(secret_number, guess, tolerance) {
+            FloatGuessResult::Low => {
+                println!(
+                    "{} Off by {:.1}% of the range.",
+                    "Too small!".blue(),
+                    off_by(secret_number - guess)
+                )
+            }
+            FloatGuessResult::High => {
+                println!(
+                    "{} Off by {:.1}% of the range.",
+                    "Too big!".red(),
+                    off_by(guess - secret_number)
+                )
+            }
+            FloatGuessResult::Close(distance) => {
+                let direction = if distance < 0.0 {
+                    "Too small!".blue()
+                } else {
+                    "Too big!".red()
+                };
+                println!(
+                    "{} Close! Off by {:.1}% of the range.",
+                    direction,
+                    off_by(distance.abs())
+                )
+            }
+            FloatGuessResult::Win => {
+                let message = format!(
+                    "You got it in {}! The number was {}.",
+                    guesses(guess_count),
+                    secret_number
+                );
+                println!("{}", message.green());
+                return false;
+            }
+        }
+    }
+}
+
+/// Plays rounds until the player stops
+pub fn guess_float(rng: &mut StdRng, min: f64, max: f64, tolerance: f64, attempts: Option<u32>) {
+    loop {
+        if play_float_round(rng, min, max, tolerance, attempts) || !play_again() {
+            break;
+        }
+    }
+}
@@ -0,0 +1,45 @@
+//! Efficiency of every game where the number was found, kept in `game_stats.json` in the working directory.
+
+use std::fs;
+use std::io;
+
+use serde::{Deserialize, Serialize};
+
+const GAME_STATS_PATH: &str = "game_stats.json";
+
+#[derive(Default, Serialize, Deserialize)]
+pub struct GameStats {
+    /// Optimal guesses as a percentage of the guesses used, one per game where the number was found
+    efficiency: Vec<f64>,
+}
+
+impl GameStats {
+    /// The saved stats; a missing or unreadable file starts afresh
+    pub fn load() -> GameStats {
+        match fs::read_to_string(GAME_STATS_PATH) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                eprintln!("Ignoring corrupt {}: {}", GAME_STATS_PATH, e);
+                GameStats::default()
+            }),
+            Err(_) => GameStats::default(),
+        }
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(GAME_STATS_PATH, json)
+    }
+
+    pub fn record(&mut self, efficiency: f64) {
+        self.efficiency.push(efficiency);
+    }
+
+    pub fn games(&self) -> usize {
+        self.efficiency.len()
+    }
+
+    pub fn average_efficiency(&self) -> Option<f64> {
+        (!self.efficiency.is_empty())
+            .then(|| self.efficiency.iter().sum::<f64>() / self.efficiency.len() as f64)
+    }
+}
@@ -0,0 +1,71 @@
+//! Best results, kept in `highscores.json` in the working directory.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+
+use serde::{Deserialize, Serialize};
+
+const HIGHSCORES_PATH: &str = "highscores.json";
+
+/// Fewest guesses needed to win and highest score, by range and attempt limit such as `"1-100/7"`
+#[derive(Default, Serialize, Deserialize)]
+pub struct HighScores {
+    best: BTreeMap<String, u32>,
+    /// Missing from files saved before scores existed
+    #[serde(default)]
+    best_score: BTreeMap<String, u32>,
+}
+
+impl HighScores {
+    /// The saved scores; a missing or unreadable file starts a fresh table
+    pub fn load() -> HighScores {
+        match fs::read_to_string(HIGHSCORES_PATH) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                eprintln!("Ignoring corrupt {}: {}", HIGHSCORES_PATH, e);
+                HighScores::default()
+            }),
+            Err(_) => HighScores::default(),
+        }
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(HIGHSCORES_PATH, json)
+    }
+
+    pub fn best(&self, key: &str) -> Option<u32> {
+        self.best.get(key).copied()
+    }
+
+    /// Records `guesses` if it beats the best for `key`, returning whether it did
+    pub fn record(&mut self, key: &str, guesses: u32) -> bool {
+        if self.best(key).is_some_and(|best| best <= guesses) {
+            return false;
+        }
+        self.best.insert(key.to_string(), guesses);
+        true
+    }
+
+    pub fn best_score(&self, key: &str) -> Option<u32> {
+        self.best_score.get(key).copied()
+    }
+
+    /// Records `score` if it beats the best score for `key`, returning whether it did
+    pub fn record_score(&mut self, key: &str, score: u32) -> bool {
+        if self.best_score(key).is_some_and(|best| best >= score) {
+            return false;
+        }
+        self.best_score.insert(key.to_string(), score);
+        true
+    }
+}
+
+/// Key of the scores for a range and attempt limit, e.g. `"1-100/7"`.
+/// Unlimited games keep the plain `"1-100"` key.
+pub fn range_key(min: u32, max: u32, attempts: Option<u32>) -> String {
+    match attempts {
+        Some(attempts) => format!("{}-{}/{}", min, max, attempts),
+        None => format!("{}-{}", min, max),
+    }
+}
@@ -1,31 +1,687 @@
+use clap::{Parser, ValueEnum};
+use colored::Colorize;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::cmp::Ordering;
 use std::io;
+use std::time::{Duration, Instant};
 
-fn guess_number() {
-    println!("Guess the number between 1 and 100!");
+mod float_game;
+mod game_stats;
+mod highscores;
 
-    let secret_number = rand::thread_rng().gen_range(1..=100);
+use game_stats::GameStats;
+use highscores::HighScores;
+
+/// Guess the secret number, with a hint after every try
+#[derive(Parser)]
+#[command(name = "guess_game")]
+struct Args {
+    /// Smallest number the secret can be (default 1, or as set by --difficulty)
+    #[arg(long, value_parser = parse_bound, allow_negative_numbers = true)]
+    min: Option<f64>,
+
+    /// Largest number the secret can be (default 100, or as set by --difficulty)
+    #[arg(long, value_parser = parse_bound, allow_negative_numbers = true)]
+    max: Option<f64>,
+
+    /// Number of guesses before the game is lost (default unlimited, or as set by --difficulty)
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    attempts: Option<u32>,
+
+    /// Preset range and number of guesses; --min, --max and --attempts override it
+    #[arg(long, value_enum)]
+    difficulty: Option<Difficulty>,
+
+    /// You think of a number and the computer guesses it
+    #[arg(long, conflicts_with = "attempts")]
+    reverse: bool,
+
+    /// Also say whether each guess is warmer or colder than the one before
+    #[arg(long)]
+    hints: bool,
+
+    /// After each miss, draw what's left of the range and where a binary search would guess next
+    #[arg(long)]
+    coach: bool,
+
+    /// A win that takes more than the optimal number of guesses counts as a loss
+    #[arg(long)]
+    require_optimal: bool,
+
+    /// Points a win loses per guess (default as set by --difficulty, else 50)
+    #[arg(long, value_name = "POINTS")]
+    guess_penalty: Option<u32>,
+
+    /// Points a win loses per second taken (default as set by --difficulty, else 5)
+    #[arg(long, value_name = "POINTS")]
+    second_penalty: Option<u32>,
+
+    /// Play N rounds in a row and finish with a scoreboard; winning more than half wins the tournament
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..), conflicts_with = "reverse")]
+    tournament: Option<u32>,
+
+    /// Guess a real number instead, between --min and --max (default 0 and 1)
+    #[arg(long, conflicts_with_all = ["difficulty", "reverse", "coach", "hints", "require_optimal", "tournament"])]
+    float: bool,
+
+    /// How far off a --float guess may be and still win
+    #[arg(long, value_name = "F64", default_value_t = 0.01, value_parser = parse_tolerance, requires = "float")]
+    tolerance: f64,
+
+    /// Pick the secret numbers reproducibly: the same seed and range give the same game
+    #[arg(long, value_name = "N")]
+    seed: Option<u64>,
+}
+
+/// Any finite number; only --float takes fractions and negatives
+fn parse_bound(value: &str) -> Result<f64, String> {
+    match value.parse::<f64>() {
+        Ok(bound) if bound.is_finite() => Ok(bound),
+        _ => Err(format!("expected a number, got '{}'", value)),
+    }
+}
+
+/// A --min or --max bound of the whole-number game
+fn whole_bound(flag: &str, bound: f64) -> u32 {
+    if bound.fract() == 0.0 && (0.0..=f64::from(u32::MAX)).contains(&bound) {
+        bound as u32
+    } else {
+        eprintln!(
+            "{} ({}) must be a whole number from 0 to {} unless --float is set",
+            flag,
+            bound,
+            u32::MAX
+        );
+        std::process::exit(1);
+    }
+}
+
+fn parse_tolerance(value: &str) -> Result<f64, String> {
+    match value.parse::<f64>() {
+        Ok(tolerance) if tolerance.is_finite() && tolerance >= 0.0 => Ok(tolerance),
+        _ => Err(format!("expected a number of at least 0, got '{}'", value)),
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Difficulty {
+    /// 1 to 50 in 10 guesses
+    Easy,
+    /// 1 to 100 in 7 guesses
+    Medium,
+    /// 1 to 1000 in 10 guesses
+    Hard,
+}
+
+impl Difficulty {
+    /// Range and number of guesses of the preset
+    fn settings(self) -> (u32, u32, u32) {
+        match self {
+            Difficulty::Easy => (1, 50, 10),
+            Difficulty::Medium => (1, 100, 7),
+            Difficulty::Hard => (1, 1000, 10),
+        }
+    }
+
+    /// Penalties of the preset; wider ranges need more guesses, so each costs less
+    fn scoring(self) -> Scoring {
+        match self {
+            Difficulty::Easy => Scoring {
+                guess_penalty: 100,
+                second_penalty: 10,
+            },
+            Difficulty::Medium => DEFAULT_SCORING,
+            Difficulty::Hard => Scoring {
+                guess_penalty: 25,
+                second_penalty: 2,
+            },
+        }
+    }
+}
+
+/// Points a win starts from before the penalties
+const BASE_SCORE: u32 = 1000;
+
+const DEFAULT_SCORING: Scoring = Scoring {
+    guess_penalty: 50,
+    second_penalty: 5,
+};
+
+/// How many points each guess and each second of a round cost
+#[derive(Clone, Copy)]
+struct Scoring {
+    guess_penalty: u32,
+    second_penalty: u32,
+}
+
+impl Scoring {
+    /// Score of a win, never below zero
+    fn score(self, guesses: u32, elapsed: Duration) -> u32 {
+        let seconds = u32::try_from(elapsed.as_secs()).unwrap_or(u32::MAX);
+        let penalty = guesses
+            .saturating_mul(self.guess_penalty)
+            .saturating_add(seconds.saturating_mul(self.second_penalty));
+        BASE_SCORE.saturating_sub(penalty)
+    }
+}
+
+/// How a guess compares to the secret
+#[derive(Clone, Copy)]
+enum GuessResult {
+    TooLow,
+    TooHigh,
+    Correct,
+}
+
+fn evaluate_guess(guess: u32, secret: u32) -> GuessResult {
+    match guess.cmp(&secret) {
+        Ordering::Less => GuessResult::TooLow,
+        Ordering::Greater => GuessResult::TooHigh,
+        Ordering::Equal => GuessResult::Correct,
+    }
+}
+
+/// Optional feedback during a round
+#[derive(Clone, Copy)]
+struct RoundOptions {
+    hints: bool,
+    coach: bool,
+    require_optimal: bool,
+    scoring: Scoring,
+}
+
+/// Outcome of one round
+#[derive(Clone, Copy)]
+struct RoundResult {
+    guesses: u32,
+    won: bool,
+    /// Points for a win, 0 for a loss
+    score: u32,
+    /// The player typed `quit` and wants no more rounds
+    quit: bool,
+}
+
+struct TournamentResult {
+    rounds: Vec<RoundResult>,
+    /// Who won more than half of the rounds, if anyone
+    winner: Option<String>,
+}
+
+impl TournamentResult {
+    fn total_guesses(&self) -> u32 {
+        self.rounds.iter().map(|round| round.guesses).sum()
+    }
+
+    /// Number (counting from 1) and guesses of the won round with the fewest guesses
+    fn best_round(&self) -> Option<(usize, u32)> {
+        self.rounds
+            .iter()
+            .enumerate()
+            .filter(|(_, round)| round.won)
+            .min_by_key(|(_, round)| round.guesses)
+            .map(|(i, round)| (i + 1, round.guesses))
+    }
+}
+
+/// Whether `guess` is closer to the secret than `previous` was
+fn temperature(previous: u32, guess: u32, secret: u32) -> &'static str {
+    match guess.abs_diff(secret).cmp(&previous.abs_diff(secret)) {
+        Ordering::Less => "Warmer.",
+        Ordering::Greater => "Colder.",
+        Ordering::Equal => "Same distance as before.",
+    }
+}
+
+/// Guesses a binary search needs for the range: ceil(log2(range size))
+fn theoretical_minimum_guesses(range_min: u32, range_max: u32) -> u32 {
+    let size = u64::from(range_max - range_min) + 1;
+    size.next_power_of_two().trailing_zeros()
+}
+
+/// Compares a win with the optimal number of guesses and adds it to the game stats,
+/// returning whether it was optimal
+fn report_efficiency(min: u32, max: u32, used: u32) -> bool {
+    let optimal = theoretical_minimum_guesses(min, max);
+    // A lucky guess can beat the optimum, that's still 100%
+    let efficiency = (f64::from(optimal) * 100.0 / f64::from(used)).min(100.0);
+    println!(
+        "You used {} vs optimal {} (efficiency: {:.1}%)",
+        guesses(used),
+        optimal,
+        efficiency
+    );
+
+    let mut stats = GameStats::load();
+    stats.record(efficiency);
+    if let Some(average) = stats.average_efficiency() {
+        println!(
+            "Average efficiency over {} games: {:.1}%",
+            stats.games(),
+            average
+        );
+    }
+    if let Err(e) = stats.save() {
+        eprintln!("Could not save game stats: {}", e);
+    }
+    used <= optimal
+}
+
+/// Width of the `--coach` number line
+const NUMBER_LINE_WIDTH: usize = 60;
+
+/// The range `min..=max` squeezed into `width` characters: `<` and `>` mark
+/// numbers already ruled out below `lo` and above `hi`, `?` the midpoint `mid`
+/// and `*` the guess, which wins when they share a position.
+fn draw_number_line(
+    min: u32,
+    max: u32,
+    lo: u32,
+    hi: u32,
+    mid: u32,
+    guess: u32,
+    width: usize,
+) -> String {
+    let span = u64::from(max - min);
+    let position =
+        |n: u32| (u64::from(n.clamp(min, max) - min) * (width as u64 - 1) / span) as usize;
+    let mut line: Vec<char> = (0..width)
+        .map(|i| {
+            // The smallest number drawn at position i
+            let n = min + (i as u64 * span).div_ceil(width as u64 - 1) as u32;
+            if n < lo {
+                '<'
+            } else if n > hi {
+                '>'
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    line[position(mid)] = '?';
+    line[position(guess)] = '*';
+    line.into_iter().collect()
+}
+
+/// "1 guess", "2 guesses"
+fn guesses(count: u32) -> String {
+    format!("{} {}", count, if count == 1 { "guess" } else { "guesses" })
+}
+
+/// Lists the guesses of a finished round, e.g. `Your 2 guesses: 50 (too big), 25 (correct)`
+fn print_history(history: &[(u32, GuessResult)]) {
+    if history.is_empty() {
+        return;
+    }
+    let list: Vec<String> = history
+        .iter()
+        .map(|(guess, result)| {
+            let hint = match result {
+                GuessResult::TooLow => "too small",
+                GuessResult::TooHigh => "too big",
+                GuessResult::Correct => "correct",
+            };
+            format!("{} ({})", guess, hint)
+        })
+        .collect();
+    println!(
+        "Your {}: {}",
+        guesses(history.len() as u32),
+        list.join(", ")
+    );
+}
+
+/// A line of input, or `None` once stdin is exhausted
+fn read_line() -> Option<String> {
+    let mut input = String::new();
+    let bytes_read = io::stdin()
+        .read_line(&mut input)
+        .expect("Failed to read line");
+    (bytes_read > 0).then_some(input)
+}
+
+/// Plays one game
+fn play_round(
+    rng: &mut StdRng,
+    min: u32,
+    max: u32,
+    attempts: Option<u32>,
+    options: RoundOptions,
+) -> RoundResult {
+    println!("Guess the number between {} and {}!", min, max);
+
+    let secret_number = rng.gen_range(min..=max);
+    let start = Instant::now();
+    let mut guesses_left = attempts;
+    let mut history = Vec::new();
+    // What the hints so far leave possible, for the coach
+    let (mut lo, mut hi) = (min, max);
+
+    loop {
+        match guesses_left {
+            Some(0) => {
+                println!("Out of guesses! The number was {}.", secret_number);
+                print_history(&history);
+                return RoundResult {
+                    guesses: history.len() as u32,
+                    won: false,
+                    score: 0,
+                    quit: false,
+                };
+            }
+            Some(1) => println!("1 guess left."),
+            Some(n) => println!("{} guesses left.", n),
+            None => {}
+        }
+        println!("Please input your guess (or q to quit):");
+
+        let Some(input) = read_line() else {
+            println!("No more input. The number was {}.", secret_number);
+            print_history(&history);
+            return RoundResult {
+                guesses: history.len() as u32,
+                won: false,
+                score: 0,
+                quit: false,
+            };
+        };
+
+        if matches!(input.trim().to_lowercase().as_str(), "q" | "quit") {
+            println!("The number was {}. Goodbye!", secret_number);
+            print_history(&history);
+            return RoundResult {
+                guesses: history.len() as u32,
+                won: false,
+                score: 0,
+                quit: true,
+            };
+        }
+
+        // Typos don't use up a guess
+        let guess = match input.trim().parse::<u32>() {
+            Ok(guess) => guess,
+            Err(_) => {
+                println!("Please type a number!");
+                continue;
+            }
+        };
+        if let Some(n) = guesses_left.as_mut() {
+            *n -= 1;
+        }
+
+        let result = evaluate_guess(guess, secret_number);
+        let previous = history.last().map(|&(previous, _)| previous);
+        history.push((guess, result));
+        let direction = match result {
+            GuessResult::TooLow => "Too small!".blue(),
+            GuessResult::TooHigh => "Too big!".red(),
+            GuessResult::Correct => {
+                println!(
+                    "{}",
+                    format!("You guessed it! The number was {}.", secret_number).green()
+                );
+                print_history(&history);
+                let used = history.len() as u32;
+                let optimal = report_efficiency(min, max, used);
+                if options.require_optimal && !optimal {
+                    println!("That's more than optimal, so it counts as a loss.");
+                    return RoundResult {
+                        guesses: used,
+                        won: false,
+                        score: 0,
+                        quit: false,
+                    };
+                }
+                let score = options.scoring.score(used, start.elapsed());
+                println!("Score: {}", score);
+                return RoundResult {
+                    guesses: used,
+                    won: true,
+                    score,
+                    quit: false,
+                };
+            }
+        };
+        // The first guess has nothing to compare against
+        match previous.filter(|_| options.hints) {
+            Some(previous) => println!(
+                "{} {}",
+                direction,
+                temperature(previous, guess, secret_number)
+            ),
+            None => println!("{}", direction),
+        }
+
+        if options.coach {
+            match result {
+                GuessResult::TooLow => lo = lo.max(guess + 1),
+                _ => hi = hi.min(guess.saturating_sub(1)),
+            }
+            let mid = lo + (hi - lo) / 2;
+            println!(
+                "Coach: it's between {} and {}, the best next guess is {}.",
+                lo, hi, mid
+            );
+            println!(
+                "[{}]",
+                draw_number_line(min, max, lo, hi, mid, guess, NUMBER_LINE_WIDTH)
+            );
+        }
+    }
+}
+
+/// The computer bisects the range, reading h(igher), l(ower) or c(orrect) after
+/// each guess. Returns the number of guesses, or `None` if it caught a cheat or
+/// ran out of input.
+fn computer_guesses(min: u32, max: u32) -> Option<u32> {
+    println!(
+        "Think of a number between {} and {}, and I'll guess it!",
+        min, max
+    );
+    println!("Answer h if your number is higher, l if it is lower and c if I got it.");
+
+    // Signed, so the bounds can cross when the answers contradict each other
+    let (mut low, mut high) = (i64::from(min), i64::from(max));
+    let mut guesses = 0;
+
+    while low <= high {
+        let guess = low + (high - low) / 2;
+        guesses += 1;
+        println!("Is it {}?", guess);
+
+        loop {
+            let Some(answer) = read_line() else {
+                println!("No more input. Goodbye!");
+                return None;
+            };
+            match answer.trim().to_lowercase().as_str() {
+                "h" | "higher" => low = guess + 1,
+                "l" | "lower" => high = guess - 1,
+                "c" | "correct" => {
+                    println!("Got it in {} guesses!", guesses);
+                    return Some(guesses);
+                }
+                _ => {
+                    println!("Please answer h, l or c.");
+                    continue;
+                }
+            }
+            break;
+        }
+    }
+
+    println!("Your answers contradict each other, no number fits them all. Cheater!");
+    None
+}
+
+/// Asks whether to play another round; anything but yes ends the game
+fn play_again() -> bool {
+    println!("Play again? (y/n)");
+    read_line().is_some_and(|answer| matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+/// Plays rounds until the player stops, keeping high scores per range and attempt limit
+fn guess_number(
+    rng: &mut StdRng,
+    min: u32,
+    max: u32,
+    attempts: Option<u32>,
+    options: RoundOptions,
+) {
+    let key = highscores::range_key(min, max, attempts);
+    let mut scores = HighScores::load();
 
     loop {
-        println!("Please input your guess:");
+        match scores.best(&key) {
+            Some(best) => println!("High score for {} to {}: {} guesses", min, max, best),
+            None => println!("No high score for {} to {} yet.", min, max),
+        }
+        if let Some(score) = scores.best_score(&key) {
+            println!("Best score for {} to {}: {}", min, max, score);
+        }
 
-        let mut input = String::new();
-        io::stdin()
-            .read_line(&mut input)
-            .expect("Failed to read line");
+        let round = play_round(rng, min, max, attempts, options);
+        record_high_score(&mut scores, &key, round);
 
-        let guess = input.trim().parse::<u32>().expect("Please type a number!");
+        if round.quit || !play_again() {
+            break;
+        }
+    }
+}
 
-        if guess < secret_number {
-            println!("Too small!");
-        } else if guess > secret_number {
-            println!("Too big!");
-        } else {
-            println!("You guessed it! The number was {}.", secret_number);
+/// Saves a won round that beats the fewest guesses or the best score of its range
+fn record_high_score(scores: &mut HighScores, key: &str, round: RoundResult) {
+    if !round.won {
+        return;
+    }
+    let fewest_guesses = scores.record(key, round.guesses);
+    if fewest_guesses {
+        println!("New high score: {} guesses!", round.guesses);
+    }
+    let best_score = scores.record_score(key, round.score);
+    if best_score {
+        println!("New best score: {}!", round.score);
+    }
+    if (fewest_guesses || best_score)
+        && let Err(e) = scores.save()
+    {
+        eprintln!("Could not save high scores: {}", e);
+    }
+}
+
+/// Plays `rounds` rounds back to back, keeping high scores as usual. Quitting
+/// ends the tournament early, the rounds not played count as not won.
+fn play_tournament(
+    rng: &mut StdRng,
+    min: u32,
+    max: u32,
+    attempts: Option<u32>,
+    options: RoundOptions,
+    rounds: u32,
+) -> TournamentResult {
+    let key = highscores::range_key(min, max, attempts);
+    let mut scores = HighScores::load();
+    let mut results = Vec::new();
+
+    for number in 1..=rounds {
+        println!("Round {} of {}", number, rounds);
+        let round = play_round(rng, min, max, attempts, options);
+        record_high_score(&mut scores, &key, round);
+        results.push(round);
+        if round.quit {
             break;
         }
     }
+
+    let wins = results.iter().filter(|round| round.won).count() as u32;
+    TournamentResult {
+        rounds: results,
+        winner: (wins * 2 > rounds).then(|| "You".to_string()),
+    }
+}
+
+fn print_scoreboard(result: &TournamentResult) {
+    println!("Tournament results:");
+    for (i, round) in result.rounds.iter().enumerate() {
+        let outcome = if round.won { "won in" } else { "lost after" };
+        println!("  Round {}: {} {}", i + 1, outcome, guesses(round.guesses));
+    }
+    let wins = result.rounds.iter().filter(|round| round.won).count();
+    println!(
+        "Won {} of {} rounds with {} in total.",
+        wins,
+        result.rounds.len(),
+        guesses(result.total_guesses())
+    );
+    match result.best_round() {
+        Some((number, count)) => println!("Best round: round {} in {}", number, guesses(count)),
+        None => println!("No round was won."),
+    }
+    match &result.winner {
+        Some(winner) => println!("{} won the tournament!", winner),
+        None => println!("Nobody won the tournament."),
+    }
 }
 
 fn main() {
-    guess_number();
-}
\ No newline at end of file
+    let args = Args::parse();
+    let mut rng = match args.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+
+    if args.float {
+        let min = args.min.unwrap_or(0.0);
+        let max = args.max.unwrap_or(1.0);
+        if min >= max {
+            eprintln!("--min ({}) must be less than --max ({})", min, max);
+            std::process::exit(1);
+        }
+        float_game::guess_float(&mut rng, min, max, args.tolerance, args.attempts);
+        return;
+    }
+
+    let (preset_min, preset_max, preset_attempts) = match args.difficulty {
+        Some(difficulty) => {
+            let (min, max, attempts) = difficulty.settings();
+            (min, max, Some(attempts))
+        }
+        None => (1, 100, None),
+    };
+    let min = args.min.map_or(preset_min, |min| whole_bound("--min", min));
+    let max = args.max.map_or(preset_max, |max| whole_bound("--max", max));
+    if min >= max {
+        eprintln!("--min ({}) must be less than --max ({})", min, max);
+        std::process::exit(1);
+    }
+
+    if args.reverse {
+        loop {
+            computer_guesses(min, max);
+            if !play_again() {
+                break;
+            }
+        }
+        return;
+    }
+
+    let attempts = args.attempts.or(preset_attempts);
+    let options = RoundOptions {
+        hints: args.hints,
+        coach: args.coach,
+        require_optimal: args.require_optimal,
+        scoring: {
+            let preset = args.difficulty.map_or(DEFAULT_SCORING, Difficulty::scoring);
+            Scoring {
+                guess_penalty: args.guess_penalty.unwrap_or(preset.guess_penalty),
+                second_penalty: args.second_penalty.unwrap_or(preset.second_penalty),
+            }
+        },
+    };
+    match args.tournament {
+        Some(rounds) => print_scoreboard(&play_tournament(
+            &mut rng, min, max, attempts, options, rounds,
+        )),
+        None => guess_number(&mut rng, min, max, attempts, options),
+    }
+}
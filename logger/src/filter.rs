@@ -0,0 +1,219 @@
+//! The expression language of `read --filter`, e.g.
+//! `level:warn AND message:database`, `NOT level:debug` or
+//! `(message:auth OR message:login) AND timestamp:2024-05`.
+//!
+//! Grammar, from loosest to tightest binding:
+//!
+//! ```text
+//! expr    := and ("OR" and)*
+//! and     := not ("AND" not)*
+//! not     := "NOT" not | primary
+//! primary := "(" expr ")" | field ":" value
+//! ```
+//!
+//! Keywords are case-insensitive. Values are bare words or double-quoted strings.
+
+use crate::{LogEntry, LogLevel};
+
+#[derive(Clone)]
+pub enum FilterExpr {
+    /// Exact level, e.g. `level:error`
+    Level(LogLevel),
+    /// Case-insensitive substring of the message
+    Message(String),
+    /// Prefix of the `YYYY-MM-DD HH:MM:SS` timestamp, e.g. `timestamp:2024-05-01`
+    Timestamp(String),
+    Not(Box<FilterExpr>),
+    And(Box<FilterExpr>, Box<FilterExpr>),
+    Or(Box<FilterExpr>, Box<FilterExpr>),
+}
+
+impl FilterExpr {
+    pub fn matches(&self, entry: &LogEntry) -> bool {
+        match self {
+            FilterExpr::Level(level) => entry.level == *level,
+            FilterExpr::Message(text) => entry.message.to_lowercase().contains(text),
+            FilterExpr::Timestamp(prefix) => entry
+                .timestamp
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string()
+                .starts_with(prefix.as_str()),
+            FilterExpr::Not(inner) => !inner.matches(entry),
+            FilterExpr::And(left, right) => left.matches(entry) && right.matches(entry),
+            FilterExpr::Or(left, right) => left.matches(entry) || right.matches(entry),
+        }
+    }
+}
+
+#[derive(PartialEq)]
+enum Token {
+    Word(String),
+    Quoted(String),
+    Colon,
+    Open,
+    Close,
+}
+
+impl Token {
+    fn describe(&self) -> String {
+        match self {
+            Token::Word(word) => format!("'{}'", word),
+            Token::Quoted(text) => format!("\"{}\"", text),
+            Token::Colon => "':'".to_string(),
+            Token::Open => "'('".to_string(),
+            Token::Close => "')'".to_string(),
+        }
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self, Token::Word(word) if word.eq_ignore_ascii_case(keyword))
+    }
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            ':' | '(' | ')' => {
+                chars.next();
+                tokens.push(match c {
+                    ':' => Token::Colon,
+                    '(' => Token::Open,
+                    _ => Token::Close,
+                });
+            }
+            '"' => {
+                chars.next();
+                let mut text = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => text.extend(chars.next()),
+                        Some(c) => text.push(c),
+                        None => return Err("unterminated string literal".to_string()),
+                    }
+                }
+                tokens.push(Token::Quoted(text));
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || matches!(c, ':' | '(' | ')' | '"') {
+                        break;
+                    }
+                    word.push(c);
+                    chars.next();
+                }
+                tokens.push(Token::Word(word));
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<&Token> {
+        let token = self.tokens.get(self.position);
+        self.position += 1;
+        token
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let found = self.peek().is_some_and(|token| token.is_keyword(keyword));
+        if found {
+            self.position += 1;
+        }
+        found
+    }
+
+    fn parse_or(&mut self) -> Result<FilterExpr, String> {
+        let mut expr = self.parse_and()?;
+        while self.eat_keyword("OR") {
+            expr = FilterExpr::Or(Box::new(expr), Box::new(self.parse_and()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_and(&mut self) -> Result<FilterExpr, String> {
+        let mut expr = self.parse_not()?;
+        while self.eat_keyword("AND") {
+            expr = FilterExpr::And(Box::new(expr), Box::new(self.parse_not()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_not(&mut self) -> Result<FilterExpr, String> {
+        if self.eat_keyword("NOT") {
+            return Ok(FilterExpr::Not(Box::new(self.parse_not()?)));
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<FilterExpr, String> {
+        let field = match self.next() {
+            Some(Token::Open) => {
+                let expr = self.parse_or()?;
+                return match self.next() {
+                    Some(Token::Close) => Ok(expr),
+                    Some(token) => Err(format!("expected ')' but found {}", token.describe())),
+                    None => Err("missing ')'".to_string()),
+                };
+            }
+            Some(Token::Word(field)) => field.to_lowercase(),
+            Some(token) => {
+                return Err(format!(
+                    "expected a condition but found {}",
+                    token.describe()
+                ))
+            }
+            None => return Err("expected a condition such as level:error".to_string()),
+        };
+
+        if self.next() != Some(&Token::Colon) {
+            return Err(format!("expected ':' after '{}'", field));
+        }
+        let value = match self.next() {
+            Some(Token::Word(value) | Token::Quoted(value)) => value.clone(),
+            _ => return Err(format!("expected a value after '{}:'", field)),
+        };
+
+        match field.as_str() {
+            "level" => LogLevel::from_name(&value)
+                .map(FilterExpr::Level)
+                .ok_or_else(|| format!("unknown level '{}'", value)),
+            "message" => Ok(FilterExpr::Message(value.to_lowercase())),
+            "timestamp" => Ok(FilterExpr::Timestamp(value)),
+            _ => Err(format!(
+                "unknown field '{}' (use level, message or timestamp)",
+                field
+            )),
+        }
+    }
+}
+
+/// Parses a filter expression, for use as a clap value parser
+pub fn parse(input: &str) -> Result<FilterExpr, String> {
+    let mut parser = Parser {
+        tokens: tokenize(input)?,
+        position: 0,
+    };
+    let expr = parser.parse_or()?;
+    match parser.peek() {
+        None => Ok(expr),
+        Some(token) => Err(format!("unexpected {}", token.describe())),
+    }
+}
@@ -1,20 +1,52 @@
+use chrono::{DateTime, Utc};
+use clap::{Parser, Subcommand};
+use colored::*;
+use common::file_utils::{open_append_create, read_to_string_or_empty, write_atomic};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use serde::{Deserialize, Serialize};
-use serde_json;
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
 use std::fs::{self, File};
 use std::io;
 use std::io::{Read, Write};
-use chrono::{DateTime, Utc};
-use colored::*;
-use clap::{Parser, Subcommand};
-use std::time::Instant;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{mpsc, Mutex, OnceLock};
 use std::thread;
-use std::sync::mpsc;
-use tiny_http::{Server, Response};
+use std::time::Duration;
+use std::time::Instant;
+use syslog::{Facility, Formatter3164, LoggerBackend};
+use tiny_http::{Response, Server};
+
+mod filter;
+
+use filter::FilterExpr;
 
 #[derive(Parser)]
 #[command(name = "logger")]
 #[command(about = "A simple logging utility with timestamps")]
 struct Cli {
+    /// POST a JSON notification to this URL whenever an ERROR entry is written
+    #[arg(long, env = "LOGGER_WEBHOOK_URL", global = true)]
+    webhook_url: Option<String>,
+    /// Keep each level in its own log_<level>.json file (used by write, read and stats)
+    #[arg(long, global = true)]
+    split_by_level: bool,
+    /// How read and the txt export show each entry. Placeholders: {timestamp}, {level},
+    /// {message}, {id} (the trace ID, if any) and {hostname}; {message} is required
+    #[arg(long, value_name = "TEMPLATE", global = true, value_parser = parse_template)]
+    output_format: Option<String>,
+    /// Also send every written entry to the local syslog daemon
+    #[arg(long, global = true)]
+    syslog: bool,
+    /// Syslog facility, e.g. user, daemon or local0
+    #[arg(long, value_name = "FACILITY", global = true, default_value = "user", value_parser = parse_facility)]
+    syslog_facility: Facility,
+    /// After each rotation, delete the oldest log_backup_*.json files until all of them
+    /// together take at most this much space, e.g. 4096, 512K, 50M or 1G
+    #[arg(long, value_name = "BYTES", global = true, value_parser = parse_size)]
+    max_total_backups_size: Option<u64>,
     #[command(subcommand)]
     command: Option<Commands>,
 }
@@ -27,7 +59,23 @@ enum Commands {
         #[arg(short, long, default_value = "info")]
         level: String,
         /// Log message
-        message: String,
+        #[arg(required_unless_present = "stdin")]
+        message: Option<String>,
+        /// Write one entry per line of stdin instead of a single message
+        #[arg(long, conflicts_with = "message")]
+        stdin: bool,
+        /// Trace this entry belongs to (32 lowercase hex characters)
+        #[arg(long, value_parser = parse_trace_id)]
+        trace_id: Option<String>,
+        /// Span this entry belongs to (16 lowercase hex characters)
+        #[arg(long, value_parser = parse_span_id, requires = "trace_id")]
+        span_id: Option<String>,
+        /// Only write the entry with probability 1/N; counts are kept in log_sampling.json
+        #[arg(long, value_name = "N", value_parser = clap::value_parser!(u64).range(1..))]
+        sample_rate: Option<u64>,
+        /// Make sampling decisions reproducible
+        #[arg(long, value_name = "S", requires = "sample_rate")]
+        sample_seed: Option<u64>,
     },
     /// Read logs with optional filtering
     Read {
@@ -37,123 +85,392 @@ enum Commands {
         /// Search for keyword
         #[arg(short, long)]
         search: Option<String>,
+        /// Show N entries before each search match
+        #[arg(short = 'B', long, default_value = "0", requires = "search")]
+        before: usize,
+        /// Show N entries after each search match
+        #[arg(short = 'A', long, default_value = "0", requires = "search")]
+        after: usize,
+        /// Only show entries belonging to this trace
+        #[arg(long, value_parser = parse_trace_id)]
+        trace_id: Option<String>,
+        /// Only show entries matching an expression, e.g. "level:warn AND NOT message:retry".
+        /// Fields: level, message, timestamp; operators: AND, OR, NOT and parentheses.
+        #[arg(long, value_name = "EXPR", value_parser = filter::parse)]
+        filter: Option<FilterExpr>,
     },
-    /// Show log statistics
-    Stats,
-    /// Export logs to file
-    Export {
-        /// Export format (csv, txt)
-        #[arg(short, long, default_value = "csv")]
-        format: String,
-    },
-}
-
-const LOG_FILE_PATH: &str = "log.json";
-const MAX_LOG_SIZE: u64 = 1024 * 1024; // 1MB
-
-#[derive(Parser)]
-#[command(name = "logger")]
-#[command(about = "A simple logging utility with timestamps")]
-struct Cli {
-    #[command(subcommand)]
-    command: Option<Commands>,
-}
-
-#[derive(Subcommand)]
-enum Commands {
-    /// Write a log message
-    Write {
-        /// Log level (info, warn, error, debug)
-        #[arg(short, long, default_value = "info")]
-        level: String,
-        /// Log message
-        message: String,
-    },
-    /// Read logs with optional filtering
-    Read {
-        /// Filter by log level
+    /// Print how many entries match; exits 0 if any do, 1 if none do and 2 on errors
+    Count {
+        /// Only count this level
         #[arg(short, long)]
         level: Option<String>,
-        /// Search for keyword
+        /// Only count entries whose message contains this keyword
         #[arg(short, long)]
         search: Option<String>,
+        /// Only count entries at or after this time: an age such as 30m, 1h, 2d or 1w,
+        /// or a UTC date such as 2024-05-01 or "2024-05-01 12:00:00"
+        #[arg(long, value_name = "TIME", value_parser = parse_time_bound)]
+        since: Option<DateTime<Utc>>,
+        /// Only count entries at or before this time, in the same forms as --since
+        #[arg(long, value_name = "TIME", value_parser = parse_time_bound)]
+        until: Option<DateTime<Utc>>,
     },
     /// Show log statistics
-    Stats,
+    Stats {
+        /// Print the statistics in the Prometheus text exposition format
+        #[arg(long)]
+        prometheus: bool,
+        /// Serve the Prometheus metrics at http://127.0.0.1:<PORT>/metrics instead
+        #[arg(long, value_name = "PORT", conflicts_with = "prometheus")]
+        metrics_port: Option<u16>,
+    },
     /// Export logs to file
     Export {
         /// Export format (csv, txt)
         #[arg(short, long, default_value = "csv")]
         format: String,
     },
+    /// Archive logs older than the given number of days
+    Archive {
+        /// Age in days after which logs are archived
+        #[arg(short, long, default_value = "30")]
+        days: i64,
+    },
+    /// Validate log entries in parallel across all CPU cores
+    Process,
+    /// Report log lines that are not valid entries
+    Validate {
+        /// Remove the corrupt lines from the log file
+        #[arg(long)]
+        fix: bool,
+    },
+    /// Store the SHA-256 of each log file in <file>.sha256 and report files changed since
+    /// the last checksum. Writes by logger keep the stored checksum up to date
+    Checksum {
+        /// Only compare against the stored checksum; exit 1 if a file changed
+        #[arg(long)]
+        verify: bool,
+    },
+    /// Add the entries of another JSON-lines log file and sort the log by timestamp.
+    /// A log with corrupt lines is only appended to, keeping those lines
+    Import {
+        /// File to import, in the same format as log.json
+        file: String,
+        /// Skip entries whose timestamp and message are already in the log
+        #[arg(long)]
+        deduplicate: bool,
+    },
+    /// Print the entries again with the delays they were originally written with
+    Replay {
+        /// Playback speed from 0.01 to 1000, e.g. 2.0 for twice as fast
+        #[arg(long, default_value = "1.0", value_parser = parse_speed)]
+        speed: f32,
+        /// Start at this time, in the same forms as count --since
+        #[arg(long, value_name = "TIME", value_parser = parse_time_bound)]
+        from: Option<DateTime<Utc>>,
+        /// Stop at this time, in the same forms as count --since
+        #[arg(long, value_name = "TIME", value_parser = parse_time_bound)]
+        to: Option<DateTime<Utc>>,
+    },
+    /// Show read/parse performance metrics for the log file
+    Perf,
+    /// Start the web interface
+    Serve {
+        /// Port to listen on
+        #[arg(short, long, default_value = "8080")]
+        port: u16,
+    },
 }
 
+const LOG_FILE_PATH: &str = "log.json";
+const MAX_LOG_SIZE: u64 = 1024 * 1024; // 1MB
+const SAMPLING_STATS_PATH: &str = "log_sampling.json";
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(5);
+const DEFAULT_OUTPUT_FORMAT: &str = "[{timestamp}] [{level}] {message}";
+
+static WEBHOOK_URL: OnceLock<String> = OnceLock::new();
+static SPLIT_BY_LEVEL: AtomicBool = AtomicBool::new(false);
+static OUTPUT_FORMAT: OnceLock<String> = OnceLock::new();
+static MAX_TOTAL_BACKUPS_SIZE: OnceLock<u64> = OnceLock::new();
+static SYSLOG: OnceLock<Mutex<syslog::Logger<LoggerBackend, Formatter3164>>> = OnceLock::new();
+/// Writes seen and skipped by `--sample-rate` in this run, added to log_sampling.json at the end
+static SAMPLED_SEEN: AtomicU64 = AtomicU64::new(0);
+static SAMPLED_SKIPPED: AtomicU64 = AtomicU64::new(0);
+static PENDING_WEBHOOKS: Mutex<Vec<thread::JoinHandle<()>>> = Mutex::new(Vec::new());
+
 fn main() {
     let cli = Cli::parse();
+    if let Some(url) = cli.webhook_url {
+        WEBHOOK_URL.set(url).expect("webhook URL is only set once");
+    }
+    SPLIT_BY_LEVEL.store(cli.split_by_level, Ordering::Relaxed);
+    if let Some(template) = cli.output_format {
+        OUTPUT_FORMAT
+            .set(template)
+            .expect("output format is only set once");
+    }
+    if cli.syslog {
+        connect_syslog(cli.syslog_facility);
+    }
+    if let Some(size) = cli.max_total_backups_size {
+        MAX_TOTAL_BACKUPS_SIZE
+            .set(size)
+            .expect("backup size cap is only set once");
+    }
 
     match cli.command {
-        Some(Commands::Write { level, message }) => {
-            let log_level = match level.to_lowercase().as_str() {
-                "info" => LogLevel::INFO,
-                "warn" => LogLevel::WARN,
-                "error" => LogLevel::ERROR,
-                "debug" => LogLevel::DEBUG,
-                _ => {
-                    eprintln!("Invalid log level: {}", level);
-                    std::process::exit(1);
+        Some(Commands::Write {
+            level,
+            message,
+            stdin: _,
+            trace_id,
+            span_id,
+            sample_rate,
+            sample_seed,
+        }) => {
+            let Some(log_level) = LogLevel::from_name(&level) else {
+                eprintln!("Invalid log level: {}", level);
+                std::process::exit(1);
+            };
+            // One generator per run, so a seed gives the same decisions wherever it runs
+            let mut rng = match sample_seed {
+                Some(seed) => StdRng::seed_from_u64(seed),
+                None => StdRng::from_entropy(),
+            };
+            let mut write = |message: &str| {
+                if let Some(rate) = sample_rate {
+                    if !sample_write(rate, &mut rng) {
+                        println!("{} log sampled out (1 in {}).", level.to_uppercase(), rate);
+                        return;
+                    }
                 }
+                let mut log_entry = LogEntry::new(log_level, message);
+                log_entry.trace_id = trace_id.clone();
+                log_entry.span_id = span_id.clone();
+                write_log_entry(&log_entry);
+                println!("{} log written.", level.to_uppercase());
             };
-            log_message(log_level, &message);
-            println!("{} log written.", level.to_uppercase());
+            match message {
+                Some(message) => write(&message),
+                None => {
+                    for line in io::stdin().lines() {
+                        write(&line.expect("Failed to read line"));
+                    }
+                }
+            }
+            save_sampling_stats();
         }
-        Some(Commands::Read { level, search }) => {
+        Some(Commands::Read {
+            level,
+            search,
+            before,
+            after,
+            trace_id,
+            filter,
+        }) => {
+            let log_level = level.map(|level_str| {
+                LogLevel::from_name(&level_str).unwrap_or_else(|| {
+                    eprintln!("Invalid log level: {}", level_str);
+                    std::process::exit(1);
+                })
+            });
+            let filter = LogFilter {
+                level: log_level,
+                trace_id,
+                expression: filter,
+                ..Default::default()
+            };
             if let Some(keyword) = search {
-                search_logs(&keyword);
-            } else if let Some(level_str) = level {
-                let log_level = match level_str.to_lowercase().as_str() {
-                    "info" => Some(LogLevel::INFO),
-                    "warn" => Some(LogLevel::WARN),
-                    "error" => Some(LogLevel::ERROR),
-                    "debug" => Some(LogLevel::DEBUG),
-                    _ => {
-                        eprintln!("Invalid log level: {}", level_str);
+                search_logs(&keyword, &filter, before, after);
+            } else {
+                read_logs_filtered(&filter);
+            }
+        }
+        Some(Commands::Count {
+            level,
+            search,
+            since,
+            until,
+        }) => {
+            let log_level = level.map(|level_str| {
+                LogLevel::from_name(&level_str).unwrap_or_else(|| {
+                    eprintln!("Invalid log level: {}", level_str);
+                    std::process::exit(2);
+                })
+            });
+            let filter = LogFilter {
+                level: log_level,
+                since,
+                until,
+                ..Default::default()
+            };
+            match count_logs(&filter, search.as_deref()) {
+                Ok(count) => {
+                    println!("{}", count);
+                    std::process::exit(if count > 0 { 0 } else { 1 });
+                }
+                Err(e) => {
+                    eprintln!("Failed to read log file: {}", e);
+                    std::process::exit(2);
+                }
+            }
+        }
+        Some(Commands::Validate { fix }) => match validate_logs(fix) {
+            Ok(0) => {}
+            Ok(_) => std::process::exit(1),
+            Err(e) => {
+                eprintln!("Failed to validate logs: {}", e);
+                std::process::exit(1);
+            }
+        },
+        Some(Commands::Stats {
+            prometheus,
+            metrics_port,
+        }) => {
+            if let Some(port) = metrics_port {
+                if let Err(e) = serve_metrics(port) {
+                    eprintln!("Metrics server error: {}", e);
+                    std::process::exit(1);
+                }
+            } else if prometheus {
+                match export_prometheus_metrics() {
+                    Ok(metrics) => print!("{}", metrics),
+                    Err(e) => {
+                        eprintln!("Failed to read log file: {}", e);
                         std::process::exit(1);
                     }
-                };
-                read_logs_filtered(log_level);
+                }
             } else {
-                read_logs_filtered(None);
+                show_log_statistics();
             }
         }
-        Some(Commands::Stats) => {
-            show_log_statistics();
-        }
         Some(Commands::Export { format }) => {
             if let Err(e) = export_logs(&format) {
                 eprintln!("Failed to export logs: {}", e);
                 std::process::exit(1);
             }
         }
+        Some(Commands::Archive { days }) => {
+            if let Err(e) = archive_old_logs(days) {
+                eprintln!("Failed to archive logs: {}", e);
+                std::process::exit(1);
+            }
+        }
+        Some(Commands::Process) => {
+            if let Err(e) = process_logs_parallel() {
+                eprintln!("Failed to process logs: {}", e);
+                std::process::exit(1);
+            }
+        }
+        Some(Commands::Checksum { verify }) => match checksum_logs(verify) {
+            Ok(all_match) => {
+                if verify && !all_match {
+                    std::process::exit(1);
+                }
+            }
+            Err(e) => {
+                eprintln!("Failed to checksum logs: {}", e);
+                std::process::exit(2);
+            }
+        },
+        Some(Commands::Import { file, deduplicate }) => {
+            if let Err(e) = import_logs(&file, deduplicate) {
+                eprintln!("Failed to import {}: {}", file, e);
+                std::process::exit(1);
+            }
+        }
+        Some(Commands::Replay { speed, from, to }) => {
+            let filter = LogFilter {
+                since: from,
+                until: to,
+                ..Default::default()
+            };
+            if let Err(e) = replay_logs(&filter, speed) {
+                eprintln!("Failed to read log file: {}", e);
+                std::process::exit(1);
+            }
+        }
+        Some(Commands::Perf) => {
+            analyze_performance();
+        }
+        Some(Commands::Serve { port }) => {
+            if let Err(e) = start_web_server(port) {
+                eprintln!("Web server error: {}", e);
+                std::process::exit(1);
+            }
+        }
         None => {
             // Interactive mode
             run_interactive_mode();
         }
     }
+
+    wait_for_webhooks();
 }
 
-fn run_interactive_mode() {
-    if let Ok(metadata) = fs::metadata(LOG_FILE_PATH) {
+/// Moves `path` aside once it grows past `MAX_LOG_SIZE`, e.g. `log.json` to
+/// `log_backup_<time>.json` or `log_error.json` to `log_error_backup_<time>.json`
+fn rotate_log_if_needed(path: &Path) -> io::Result<()> {
+    if let Ok(metadata) = fs::metadata(path) {
         if metadata.len() > MAX_LOG_SIZE {
             let timestamp = Utc::now().format("%Y%m%d_%H%M%S");
-            let backup_path = format!("log_backup_{}.json", timestamp);
-            fs::rename(LOG_FILE_PATH, backup_path)?;
-            println!("Log file rotated to: {}", backup_path);
+            let stem = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .unwrap_or("log");
+            let backup_path = path.with_file_name(format!("{}_backup_{}.json", stem, timestamp));
+            fs::rename(path, &backup_path)?;
+            refresh_checksum(path)?;
+            println!("Log file rotated to: {}", backup_path.display());
+            if let Some(&cap) = MAX_TOTAL_BACKUPS_SIZE.get() {
+                prune_backups(&backup_path, cap)?;
+            }
         }
     }
     Ok(())
 }
 
-#[derive(Serialize, Deserialize)]
+/// Deletes the oldest backups next to `backup_path` until they add up to at most
+/// `cap` bytes. Backups of every level count, e.g. `log_backup_*.json` and
+/// `log_error_backup_*.json`.
+fn prune_backups(backup_path: &Path, cap: u64) -> io::Result<()> {
+    let dir = match backup_path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let mut backups = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if name.starts_with("log")
+            && name.contains("_backup_")
+            && name.contains(".json")
+            && !name.ends_with(".sha256")
+        {
+            let metadata = entry.metadata()?;
+            backups.push((metadata.modified()?, name, metadata.len()));
+        }
+    }
+    // Oldest first; names carry the rotation time, so they break ties
+    backups.sort();
+
+    let mut total: u64 = backups.iter().map(|(_, _, size)| size).sum();
+    for (_, name, size) in backups {
+        if total <= cap {
+            break;
+        }
+        fs::remove_file(dir.join(&name))?;
+        total -= size;
+        println!(
+            "Removed old backup {} ({} bytes) to stay within {} bytes",
+            name, size, cap
+        );
+    }
+    Ok(())
+}
+
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
 enum LogLevel {
     INFO,
     WARN,
@@ -161,60 +478,523 @@ enum LogLevel {
     DEBUG,
 }
 
+impl LogLevel {
+    const ALL: [LogLevel; 4] = [
+        LogLevel::INFO,
+        LogLevel::WARN,
+        LogLevel::ERROR,
+        LogLevel::DEBUG,
+    ];
+
+    fn from_name(name: &str) -> Option<LogLevel> {
+        match name.to_lowercase().as_str() {
+            "info" => Some(LogLevel::INFO),
+            "warn" => Some(LogLevel::WARN),
+            "error" => Some(LogLevel::ERROR),
+            "debug" => Some(LogLevel::DEBUG),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            LogLevel::INFO => "info",
+            LogLevel::WARN => "warn",
+            LogLevel::ERROR => "error",
+            LogLevel::DEBUG => "debug",
+        }
+    }
+}
+
+/// The file entries of `level` are written to
+fn log_path(level: LogLevel) -> PathBuf {
+    if SPLIT_BY_LEVEL.load(Ordering::Relaxed) {
+        PathBuf::from(format!("log_{}.json", level.name()))
+    } else {
+        PathBuf::from(LOG_FILE_PATH)
+    }
+}
+
+/// Reads the file holding `level`, or every level file when `level` is `None`.
+/// Entries from several files are merged in timestamp order.
+/// Returns `None` when none of the files exist.
+fn read_log_entries(level: Option<LogLevel>) -> io::Result<Option<Vec<LogEntry>>> {
+    if !SPLIT_BY_LEVEL.load(Ordering::Relaxed) {
+        return match fs::read_to_string(LOG_FILE_PATH) {
+            Ok(contents) => Ok(Some(parse_log_entries(&contents))),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        };
+    }
+
+    let levels = match level {
+        Some(level) => vec![level],
+        None => LogLevel::ALL.to_vec(),
+    };
+    let mut contents = String::new();
+    let mut found = false;
+    for level in levels {
+        match fs::read_to_string(log_path(level)) {
+            Ok(level_contents) => {
+                contents.push_str(&level_contents);
+                found = true;
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+    }
+    if !found {
+        return Ok(None);
+    }
+    let mut entries = parse_log_entries(&contents);
+    entries.sort_by_key(|entry| entry.timestamp);
+    Ok(Some(entries))
+}
+
 #[derive(Serialize, Deserialize)]
 struct LogEntry {
     timestamp: DateTime<Utc>,
     level: LogLevel,
     message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    trace_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    span_id: Option<String>,
 }
 
-fn log_message(level: LogLevel, message: &str) {
-    if let Err(e) = rotate_log_if_needed() {
-        eprintln!("Failed to rotate log: {}", e);
+impl LogEntry {
+    fn new(level: LogLevel, message: &str) -> Self {
+        LogEntry {
+            timestamp: Utc::now(),
+            level,
+            message: message.to_string(),
+            trace_id: None,
+            span_id: None,
+        }
+    }
+}
+
+/// Conditions an entry must meet to be shown by `read`
+#[derive(Default)]
+struct LogFilter {
+    level: Option<LogLevel>,
+    trace_id: Option<String>,
+    expression: Option<FilterExpr>,
+    since: Option<DateTime<Utc>>,
+    until: Option<DateTime<Utc>>,
+}
+
+impl LogFilter {
+    fn by_level(level: LogLevel) -> Self {
+        LogFilter {
+            level: Some(level),
+            ..Default::default()
+        }
+    }
+
+    fn matches(&self, entry: &LogEntry) -> bool {
+        self.level.is_none_or(|level| entry.level == level)
+            && self
+                .trace_id
+                .as_ref()
+                .is_none_or(|trace_id| entry.trace_id.as_ref() == Some(trace_id))
+            && self
+                .expression
+                .as_ref()
+                .is_none_or(|expression| expression.matches(entry))
+            && self.since.is_none_or(|since| entry.timestamp >= since)
+            && self.until.is_none_or(|until| entry.timestamp <= until)
+    }
+}
+
+/// Parses an age such as `30m`, `1h`, `2d` or `1w` into the time that long ago,
+/// or a UTC `YYYY-MM-DD`, `YYYY-MM-DD HH:MM:SS` or RFC 3339 time
+fn parse_time_bound(input: &str) -> Result<DateTime<Utc>, String> {
+    let input = input.trim();
+    if let Some(unit) = input
+        .chars()
+        .last()
+        .filter(|unit| unit.is_ascii_alphabetic())
+    {
+        if let Ok(amount) = input[..input.len() - 1].parse::<i64>() {
+            let age = match unit {
+                's' => chrono::Duration::try_seconds(amount),
+                'm' => chrono::Duration::try_minutes(amount),
+                'h' => chrono::Duration::try_hours(amount),
+                'd' => chrono::Duration::try_days(amount),
+                'w' => chrono::Duration::try_weeks(amount),
+                _ => {
+                    return Err(format!(
+                        "unknown unit '{}' in '{}' (use s, m, h, d or w)",
+                        unit, input
+                    ))
+                }
+            };
+            return age
+                .and_then(|age| Utc::now().checked_sub_signed(age))
+                .ok_or_else(|| format!("'{}' is too far back", input));
+        }
+    }
+    if let Ok(time) = DateTime::parse_from_rfc3339(input) {
+        return Ok(time.with_timezone(&Utc));
+    }
+    if let Ok(time) = chrono::NaiveDateTime::parse_from_str(input, "%Y-%m-%d %H:%M:%S") {
+        return Ok(time.and_utc());
+    }
+    if let Ok(date) = chrono::NaiveDate::parse_from_str(input, "%Y-%m-%d") {
+        return Ok(date
+            .and_hms_opt(0, 0, 0)
+            .expect("midnight is valid")
+            .and_utc());
+    }
+    Err(format!(
+        "invalid time '{}': use an age such as 1h or a date such as 2024-05-01",
+        input
+    ))
+}
+
+/// Replay speeds outside this range are rejected; the delays they would give are
+/// either too long to wait for or too short to notice
+const REPLAY_SPEEDS: std::ops::RangeInclusive<f32> = 0.01..=1000.0;
+
+fn parse_speed(input: &str) -> Result<f32, String> {
+    match input.parse::<f32>() {
+        Ok(speed) if REPLAY_SPEEDS.contains(&speed) => Ok(speed),
+        _ => Err(format!(
+            "invalid speed '{}': use a number from {} to {}, such as 2.0",
+            input,
+            REPLAY_SPEEDS.start(),
+            REPLAY_SPEEDS.end()
+        )),
+    }
+}
+
+fn parse_template(input: &str) -> Result<String, String> {
+    if input.contains("{message}") {
+        Ok(input.to_string())
+    } else {
+        Err("the template must contain {message}".to_string())
     }
+}
+
+/// Bytes, optionally with a K, M or G suffix (powers of 1024) and a trailing B
+fn parse_size(input: &str) -> Result<u64, String> {
+    let upper = input.trim().to_uppercase();
+    let without_b = upper.strip_suffix('B').unwrap_or(&upper);
+    let (number, multiplier) = match without_b.chars().last() {
+        Some('K') => (&without_b[..without_b.len() - 1], 1024),
+        Some('M') => (&without_b[..without_b.len() - 1], 1024 * 1024),
+        Some('G') => (&without_b[..without_b.len() - 1], 1024 * 1024 * 1024),
+        _ => (without_b, 1),
+    };
+    number
+        .parse::<u64>()
+        .ok()
+        .and_then(|n| n.checked_mul(multiplier))
+        .ok_or_else(|| {
+            format!(
+                "expected a size such as 4096, 512K, 50M or 1G, got '{}'",
+                input
+            )
+        })
+}
+
+fn parse_facility(input: &str) -> Result<Facility, String> {
+    input
+        .parse()
+        .map_err(|_| format!("unknown syslog facility '{}'", input))
+}
 
-    let log_entry = LogEntry {
-        timestamp: Utc::now(),
-        level,
-        message: message.to_string(),
+/// Connects to the local syslog daemon, or warns and carries on with the log file alone
+fn connect_syslog(facility: Facility) {
+    let formatter = Formatter3164 {
+        facility,
+        hostname: None,
+        process: "logger".to_string(),
+        pid: std::process::id(),
     };
+    match syslog::unix(formatter) {
+        Ok(logger) => {
+            SYSLOG
+                .set(Mutex::new(logger))
+                .ok()
+                .expect("syslog is only connected once");
+        }
+        Err(e) => eprintln!(
+            "Warning: syslog is unavailable ({}), logging to file only.",
+            e
+        ),
+    }
+}
+
+fn send_to_syslog(log_entry: &LogEntry) {
+    let Some(logger) = SYSLOG.get() else {
+        return;
+    };
+    let message = apply_template(output_format(), log_entry);
+    let mut logger = logger.lock().unwrap();
+    let result = match log_entry.level {
+        LogLevel::INFO => logger.info(message),
+        LogLevel::WARN => logger.warning(message),
+        LogLevel::ERROR => logger.err(message),
+        LogLevel::DEBUG => logger.debug(message),
+    };
+    if let Err(e) = result {
+        eprintln!("Failed to send log entry to syslog: {}", e);
+    }
+}
+
+fn output_format() -> &'static str {
+    OUTPUT_FORMAT
+        .get()
+        .map_or(DEFAULT_OUTPUT_FORMAT, String::as_str)
+}
+
+/// Fills in the placeholders of an `--output-format` template
+fn apply_template(template: &str, entry: &LogEntry) -> String {
+    static HOSTNAME: OnceLock<String> = OnceLock::new();
+    let hostname =
+        HOSTNAME.get_or_init(|| gethostname::gethostname().to_string_lossy().into_owned());
+
+    template
+        .replace(
+            "{timestamp}",
+            &entry.timestamp.format("%Y-%m-%d %H:%M:%S").to_string(),
+        )
+        .replace("{level}", &entry.level.name().to_uppercase())
+        .replace("{id}", entry.trace_id.as_deref().unwrap_or(""))
+        .replace("{hostname}", hostname)
+        // Last, so placeholders inside the message are left alone
+        .replace("{message}", &entry.message)
+}
+
+/// Checks for an ID of `len` lowercase hex characters, as used by W3C trace context
+fn parse_hex_id(input: &str, len: usize, name: &str) -> Result<String, String> {
+    if input.len() == len && input.chars().all(|c| matches!(c, '0'..='9' | 'a'..='f')) {
+        Ok(input.to_string())
+    } else {
+        Err(format!("{} must be {} lowercase hex characters", name, len))
+    }
+}
+
+fn parse_trace_id(input: &str) -> Result<String, String> {
+    parse_hex_id(input, 32, "trace ID")
+}
+
+fn parse_span_id(input: &str) -> Result<String, String> {
+    parse_hex_id(input, 16, "span ID")
+}
+
+/// Running totals of sampled writes, kept next to the log for monitoring
+#[derive(Default, Serialize, Deserialize)]
+struct SamplingStats {
+    seen: u64,
+    skipped: u64,
+}
+
+/// Decides whether a sampled write is kept, counting the decision for this run
+fn sample_write(rate: u64, rng: &mut StdRng) -> bool {
+    let keep = rng.gen_range(0..rate) == 0;
+    SAMPLED_SEEN.fetch_add(1, Ordering::Relaxed);
+    if !keep {
+        SAMPLED_SKIPPED.fetch_add(1, Ordering::Relaxed);
+    }
+    keep
+}
+
+/// Adds the sampling counts of this run to the totals in log_sampling.json
+fn save_sampling_stats() {
+    let seen = SAMPLED_SEEN.load(Ordering::Relaxed);
+    if seen == 0 {
+        return;
+    }
+    let mut stats: SamplingStats = fs::read_to_string(SAMPLING_STATS_PATH)
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default();
+    stats.seen += seen;
+    stats.skipped += SAMPLED_SKIPPED.load(Ordering::Relaxed);
+
+    let json = serde_json::to_string(&stats).expect("Failed to serialize sampling stats");
+    if let Err(e) = write_atomic(Path::new(SAMPLING_STATS_PATH), json.as_bytes()) {
+        eprintln!("Warning: Failed to update {}: {}", SAMPLING_STATS_PATH, e);
+    }
+}
+
+fn log_message(level: LogLevel, message: &str) {
+    write_log_entry(&LogEntry::new(level, message));
+}
+
+fn write_log_entry(log_entry: &LogEntry) {
+    let path = log_path(log_entry.level);
+    if let Err(e) = rotate_log_if_needed(&path) {
+        eprintln!("Failed to rotate log: {}", e);
+    }
 
     let log_json = serde_json::to_string(&log_entry).expect("Failed to serialize log entry");
 
-    let mut file = File::options()
-        .append(true)
-        .create(true)
-        .open(LOG_FILE_PATH)
-        .expect("Failed to open log file");
+    let mut file = open_append_create(&path).expect("Failed to open log file");
 
     writeln!(file, "{}", log_json).expect("Failed to write log entry");
+    drop(file);
+    if let Err(e) = refresh_checksum(&path) {
+        eprintln!("Failed to update checksum: {}", e);
+    }
+    send_to_syslog(log_entry);
+
+    if log_entry.level == LogLevel::ERROR {
+        notify_webhook(log_entry);
+    }
+}
+
+fn checksum_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".sha256");
+    PathBuf::from(name)
+}
+
+/// SHA-256 of the file as lowercase hex; a missing file hashes like an empty one
+fn hash_log_file(path: &Path) -> io::Result<String> {
+    let mut hasher = Sha256::new();
+    match File::open(path) {
+        Ok(mut file) => {
+            io::copy(&mut file, &mut hasher)?;
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e),
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// The stored hash, from a `sha256sum`-style `<hash>  <file>` line
+fn stored_checksum(path: &Path) -> io::Result<Option<String>> {
+    match fs::read_to_string(checksum_path(path)) {
+        Ok(contents) => Ok(contents.split_whitespace().next().map(str::to_string)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+fn store_checksum(path: &Path) -> io::Result<()> {
+    let line = format!("{}  {}\n", hash_log_file(path)?, path.display());
+    write_atomic(&checksum_path(path), line.as_bytes())
+}
+
+/// Keeps the stored checksum in step with logger's own writes. Files that were
+/// never checksummed are left alone.
+fn refresh_checksum(path: &Path) -> io::Result<()> {
+    if checksum_path(path).exists() {
+        store_checksum(path)?;
+    }
+    Ok(())
+}
+
+/// Compares each log file against its stored checksum, then stores the current
+/// one unless only verifying. Returns whether every file matched.
+fn checksum_logs(verify: bool) -> io::Result<bool> {
+    let paths: Vec<PathBuf> = if SPLIT_BY_LEVEL.load(Ordering::Relaxed) {
+        LogLevel::ALL
+            .iter()
+            .map(|&level| log_path(level))
+            .filter(|path| path.exists())
+            .collect()
+    } else {
+        vec![PathBuf::from(LOG_FILE_PATH)]
+    };
+
+    let mut all_match = true;
+    for path in paths {
+        let current = hash_log_file(&path)?;
+        match stored_checksum(&path)? {
+            Some(stored) if stored == current => println!("{}: OK ({})", path.display(), current),
+            Some(_) => {
+                all_match = false;
+                println!(
+                    "{}: {} outside of logger since the last checksum",
+                    path.display(),
+                    "modified".red()
+                );
+            }
+            None if verify => {
+                all_match = false;
+                println!("{}: no stored checksum", path.display());
+            }
+            None => {}
+        }
+        if !verify {
+            store_checksum(&path)?;
+            println!(
+                "Stored checksum of {} in {}",
+                path.display(),
+                checksum_path(&path).display()
+            );
+        }
+    }
+    Ok(all_match)
+}
+
+/// Sends the entry to the configured webhook on a background thread so the write isn't held up
+fn notify_webhook(log_entry: &LogEntry) {
+    let Some(url) = WEBHOOK_URL.get() else {
+        return;
+    };
+
+    let body = serde_json::json!({
+        "level": "error",
+        "message": log_entry.message,
+        "timestamp": log_entry.timestamp.to_rfc3339(),
+    });
+
+    let handle = thread::spawn(move || {
+        let result = reqwest::blocking::Client::builder()
+            .timeout(WEBHOOK_TIMEOUT)
+            .build()
+            .and_then(|client| client.post(url).json(&body).send())
+            .and_then(|response| response.error_for_status());
+        if let Err(e) = result {
+            eprintln!("Warning: webhook notification failed: {}", e);
+        }
+    });
+    PENDING_WEBHOOKS.lock().unwrap().push(handle);
+}
+
+/// Gives in-flight webhook calls a chance to finish before the process exits
+fn wait_for_webhooks() {
+    let handles = std::mem::take(&mut *PENDING_WEBHOOKS.lock().unwrap());
+    for handle in handles {
+        let _ = handle.join();
+    }
 }
 
 fn start_web_server(port: u16) -> io::Result<()> {
-    let server = Server::http(format!("127.0.0.1:{}", port))
-        .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
-    
+    let server = Server::http(format!("127.0.0.1:{}", port)).map_err(io::Error::other)?;
+
     println!("🌐 Web interface started at http://127.0.0.1:{}", port);
     println!("Press Ctrl+C to stop the server");
-    
+
     for request in server.incoming_requests() {
         match request.url() {
             "/" => {
                 let html = generate_html_page();
-                let response = Response::from_string(html)
-                    .with_header(tiny_http::Header::from_bytes(&b"Content-Type"[..], &b"text/html"[..]).unwrap());
+                let response = Response::from_string(html).with_header(
+                    tiny_http::Header::from_bytes(&b"Content-Type"[..], &b"text/html"[..]).unwrap(),
+                );
                 request.respond(response)?;
             }
             "/api/logs" => {
                 let logs = get_logs_as_json();
-                let response = Response::from_string(logs)
-                    .with_header(tiny_http::Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..]).unwrap());
+                let response = Response::from_string(logs).with_header(
+                    tiny_http::Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..])
+                        .unwrap(),
+                );
                 request.respond(response)?;
             }
             "/api/stats" => {
                 let stats = get_stats_as_json();
-                let response = Response::from_string(stats)
-                    .with_header(tiny_http::Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..]).unwrap());
+                let response = Response::from_string(stats).with_header(
+                    tiny_http::Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..])
+                        .unwrap(),
+                );
                 request.respond(response)?;
             }
             _ => {
@@ -223,23 +1003,23 @@ fn start_web_server(port: u16) -> io::Result<()> {
             }
         }
     }
-    
+
     Ok(())
 }
 
 fn generate_html_page() -> String {
-    format!(r#"<!DOCTYPE html>
+    r#"<!DOCTYPE html>
 <html>
 <head>
     <title>Logger Web Interface</title>
     <style>
-        body {{ font-family: Arial, sans-serif; margin: 20px; }}
-        .log-entry {{ margin: 5px 0; padding: 5px; border-left: 3px solid; }}
-        .INFO {{ border-left-color: green; }}
-        .WARN {{ border-left-color: orange; }}
-        .ERROR {{ border-left-color: red; }}
-        .DEBUG {{ border-left-color: blue; }}
-        button {{ margin: 5px; padding: 10px; }}
+        body { font-family: Arial, sans-serif; margin: 20px; }
+        .log-entry { margin: 5px 0; padding: 5px; border-left: 3px solid; }
+        .INFO { border-left-color: green; }
+        .WARN { border-left-color: orange; }
+        .ERROR { border-left-color: red; }
+        .DEBUG { border-left-color: blue; }
+        button { margin: 5px; padding: 10px; }
     </style>
 </head>
 <body>
@@ -258,58 +1038,54 @@ fn generate_html_page() -> String {
     <div id="logs"></div>
     
     <script>
-        async function loadLogs() {{
+        async function loadLogs() {
             const response = await fetch('/api/logs');
             const logs = await response.json();
             displayLogs(logs);
-        }}
+        }
         
-        async function loadStats() {{
+        async function loadStats() {
             const response = await fetch('/api/stats');
             const stats = await response.json();
             displayStats(stats);
-        }}
+        }
         
-        function displayLogs(logs) {{
+        function displayLogs(logs) {
             const container = document.getElementById('logs');
             container.innerHTML = '';
-            logs.forEach(log => {{
+            logs.forEach(log => {
                 const div = document.createElement('div');
-                div.className = `log-entry ${{log.level}}`;
-                div.textContent = `[${{log.timestamp}}] [${{log.level}}] ${{log.message}}`;
+                div.className = `log-entry ${log.level}`;
+                div.textContent = `[${log.timestamp}] [${log.level}] ${log.message}`;
                 container.appendChild(div);
-            }});
-        }}
+            });
+        }
         
-        function displayStats(stats) {{
+        function displayStats(stats) {
             const container = document.getElementById('stats');
-            container.innerHTML = `<pre>${{JSON.stringify(stats, null, 2)}}</pre>`;
-        }}
+            container.innerHTML = `<pre>${JSON.stringify(stats, null, 2)}</pre>`;
+        }
         
-        function clearLogs() {{
+        function clearLogs() {
             document.getElementById('logs').innerHTML = '';
             document.getElementById('stats').innerHTML = '';
-        }}
+        }
         
         // Load logs on page load
         loadLogs();
         loadStats();
     </script>
 </body>
-</html>"#)
+</html>"#
+        .to_string()
 }
 
 fn get_logs_as_json() -> String {
-    let mut file = match File::open(LOG_FILE_PATH) {
-        Ok(file) => file,
+    let contents = match read_to_string_or_empty(Path::new(LOG_FILE_PATH)) {
+        Ok(contents) => contents,
         Err(_) => return "[]".to_string(),
     };
 
-    let mut contents = String::new();
-    if file.read_to_string(&mut contents).is_err() {
-        return "[]".to_string();
-    }
-
     let mut logs = Vec::new();
     for line in contents.lines() {
         if line.trim().is_empty() {
@@ -324,16 +1100,11 @@ fn get_logs_as_json() -> String {
 }
 
 fn get_stats_as_json() -> String {
-    let mut file = match File::open(LOG_FILE_PATH) {
-        Ok(file) => file,
+    let contents = match read_to_string_or_empty(Path::new(LOG_FILE_PATH)) {
+        Ok(contents) => contents,
         Err(_) => return "{}".to_string(),
     };
 
-    let mut contents = String::new();
-    if file.read_to_string(&mut contents).is_err() {
-        return "{}".to_string();
-    }
-
     let mut total_logs = 0;
     let mut info_count = 0;
     let mut warn_count = 0;
@@ -365,10 +1136,91 @@ fn get_stats_as_json() -> String {
 
     serde_json::to_string(&stats).unwrap_or_else(|_| "{}".to_string())
 }
+
+/// Log statistics in the Prometheus text exposition format. A missing log file
+/// counts as empty, so scrapes keep working before the first entry is written.
+fn export_prometheus_metrics() -> io::Result<String> {
+    let entries = read_log_entries(None)?.unwrap_or_default();
+
+    let paths = if SPLIT_BY_LEVEL.load(Ordering::Relaxed) {
+        LogLevel::ALL.iter().map(|&level| log_path(level)).collect()
+    } else {
+        vec![PathBuf::from(LOG_FILE_PATH)]
+    };
+    let mut file_size = 0;
+    for path in paths {
+        match fs::metadata(&path) {
+            Ok(metadata) => file_size += metadata.len(),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+    }
+
+    let mut metrics = String::new();
+    metrics.push_str("# HELP logger_total_entries_total Number of entries in the log.\n");
+    metrics.push_str("# TYPE logger_total_entries_total counter\n");
+    metrics.push_str(&format!("logger_total_entries_total {}\n", entries.len()));
+
+    metrics.push_str("# HELP logger_entries_by_level Number of entries in the log per level.\n");
+    metrics.push_str("# TYPE logger_entries_by_level gauge\n");
+    for level in LogLevel::ALL {
+        let count = entries.iter().filter(|entry| entry.level == level).count();
+        metrics.push_str(&format!(
+            "logger_entries_by_level{{level=\"{}\"}} {}\n",
+            level.name(),
+            count
+        ));
+    }
+
+    if let Some(oldest) = entries.iter().map(|entry| entry.timestamp).min() {
+        let age = (Utc::now() - oldest).num_milliseconds().max(0) as f64 / 1000.0;
+        metrics.push_str(
+            "# HELP logger_oldest_entry_age_seconds Age of the oldest entry in the log.\n",
+        );
+        metrics.push_str("# TYPE logger_oldest_entry_age_seconds gauge\n");
+        metrics.push_str(&format!("logger_oldest_entry_age_seconds {}\n", age));
+    }
+
+    metrics.push_str("# HELP logger_file_size_bytes Size of the log file(s) on disk.\n");
+    metrics.push_str("# TYPE logger_file_size_bytes gauge\n");
+    metrics.push_str(&format!("logger_file_size_bytes {}\n", file_size));
+    Ok(metrics)
+}
+
+/// Serves `export_prometheus_metrics` at `/metrics`, computed afresh for every scrape
+fn serve_metrics(port: u16) -> io::Result<()> {
+    let server = Server::http(format!("127.0.0.1:{}", port)).map_err(io::Error::other)?;
+
+    println!("📈 Serving metrics at http://127.0.0.1:{}/metrics", port);
+    println!("Press Ctrl+C to stop the server");
+
+    for request in server.incoming_requests() {
+        if request.url() != "/metrics" {
+            request.respond(Response::from_string("404 Not Found").with_status_code(404))?;
+            continue;
+        }
+        let response = match export_prometheus_metrics() {
+            Ok(metrics) => Response::from_string(metrics).with_header(
+                tiny_http::Header::from_bytes(
+                    &b"Content-Type"[..],
+                    &b"text/plain; version=0.0.4"[..],
+                )
+                .unwrap(),
+            ),
+            Err(e) => Response::from_string(format!("Failed to read log file: {}", e))
+                .with_status_code(500),
+        };
+        request.respond(response)?;
+    }
+
+    Ok(())
+}
+
+fn process_logs_parallel() -> io::Result<()> {
     let mut file = File::open(LOG_FILE_PATH)?;
     let mut contents = String::new();
     file.read_to_string(&mut contents)?;
-    
+
     if contents.trim().is_empty() {
         println!("No logs to process.");
         return Ok(());
@@ -376,67 +1228,79 @@ fn get_stats_as_json() -> String {
 
     let lines: Vec<String> = contents.lines().map(|s| s.to_string()).collect();
     let num_threads = num_cpus::get().min(lines.len());
-    
-    println!("Processing {} log lines with {} threads...", lines.len(), num_threads);
-    
+
+    println!(
+        "Processing {} log lines with {} threads...",
+        lines.len(),
+        num_threads
+    );
+
     let (tx, rx) = mpsc::channel();
-    let chunk_size = (lines.len() + num_threads - 1) / num_threads;
-    
+    let chunk_size = lines.len().div_ceil(num_threads);
+
     let mut handles = vec![];
-    
+
     for (i, chunk) in lines.chunks(chunk_size).enumerate() {
         let tx_clone = tx.clone();
         let chunk_vec = chunk.to_vec();
-        
+
         let handle = thread::spawn(move || {
             let mut processed = 0;
             let mut errors = 0;
-            
+
             for line in chunk_vec {
                 if line.trim().is_empty() {
                     continue;
                 }
-                
+
                 match serde_json::from_str::<LogEntry>(&line) {
                     Ok(_) => processed += 1,
                     Err(_) => errors += 1,
                 }
             }
-            
+
             tx_clone.send((i, processed, errors)).unwrap();
         });
-        
+
         handles.push(handle);
     }
-    
+
     // Close the original sender
     drop(tx);
-    
+
     let mut total_processed = 0;
     let mut total_errors = 0;
-    
+
     for _ in 0..handles.len() {
         let (thread_id, processed, errors) = rx.recv().unwrap();
-        println!("Thread {}: {} valid logs, {} errors", thread_id, processed, errors);
+        println!(
+            "Thread {}: {} valid logs, {} errors",
+            thread_id, processed, errors
+        );
         total_processed += processed;
         total_errors += errors;
     }
-    
+
     // Wait for all threads to complete
     for handle in handles {
         handle.join().unwrap();
     }
-    
-    println!("Parallel processing complete: {} valid logs, {} errors", total_processed, total_errors);
+
+    println!(
+        "Parallel processing complete: {} valid logs, {} errors",
+        total_processed, total_errors
+    );
     Ok(())
 }
+
+fn analyze_performance() {
     let start = Instant::now();
-    
+
     let file_size = match fs::metadata(LOG_FILE_PATH) {
         Ok(metadata) => metadata.len(),
         Err(_) => 0,
     };
-    
+
     let mut file = match File::open(LOG_FILE_PATH) {
         Ok(file) => file,
         Err(_) => {
@@ -447,14 +1311,16 @@ fn get_stats_as_json() -> String {
 
     let mut contents = String::new();
     let read_start = Instant::now();
-    file.read_to_string(&mut contents)
-        .expect("Failed to read log file");
+    if let Err(e) = file.read_to_string(&mut contents) {
+        eprintln!("Failed to read log file: {}", e);
+        return;
+    }
     let read_duration = read_start.elapsed();
 
     let line_count = contents.lines().count();
     let parse_start = Instant::now();
     let mut valid_entries = 0;
-    
+
     for line in contents.lines() {
         if line.trim().is_empty() {
             continue;
@@ -463,25 +1329,31 @@ fn get_stats_as_json() -> String {
             valid_entries += 1;
         }
     }
-    
+
     let parse_duration = parse_start.elapsed();
     let total_duration = start.elapsed();
 
     println!("🚀 Performance Metrics:");
-    println!("File size: {} bytes ({:.2} KB)", file_size, file_size as f64 / 1024.0);
+    println!(
+        "File size: {} bytes ({:.2} KB)",
+        file_size,
+        file_size as f64 / 1024.0
+    );
     println!("Total lines: {}", line_count);
     println!("Valid log entries: {}", valid_entries);
     println!("Read time: {:.2}ms", read_duration.as_millis());
     println!("Parse time: {:.2}ms", parse_duration.as_millis());
     println!("Total analysis time: {:.2}ms", total_duration.as_millis());
-    
+
     if valid_entries > 0 {
         let avg_parse_time = parse_duration.as_millis() as f64 / valid_entries as f64;
         println!("Average parse time per entry: {:.3}ms", avg_parse_time);
     }
 }
+
+fn archive_old_logs(days: i64) -> io::Result<()> {
     let cutoff_date = Utc::now() - chrono::Duration::days(days);
-    
+
     let mut file = match File::open(LOG_FILE_PATH) {
         Ok(file) => file,
         Err(_) => {
@@ -492,7 +1364,7 @@ fn get_stats_as_json() -> String {
 
     let mut contents = String::new();
     file.read_to_string(&mut contents)?;
-    
+
     if contents.trim().is_empty() {
         println!("Log file is empty.");
         return Ok(());
@@ -505,13 +1377,12 @@ fn get_stats_as_json() -> String {
         if line.trim().is_empty() {
             continue;
         }
-        let log_entry: LogEntry = serde_json::from_str(line)
-            .expect("Failed to deserialize log entry");
-        
-        if log_entry.timestamp < cutoff_date {
-            archived_logs.push(line.to_string());
-        } else {
-            current_logs.push(line.to_string());
+        // Lines that don't parse stay in the current log for `validate` to report
+        match serde_json::from_str::<LogEntry>(line) {
+            Ok(log_entry) if log_entry.timestamp < cutoff_date => {
+                archived_logs.push(line.to_string())
+            }
+            _ => current_logs.push(line.to_string()),
         }
     }
 
@@ -524,25 +1395,34 @@ fn get_stats_as_json() -> String {
     let timestamp = Utc::now().format("%Y%m%d_%H%M%S");
     let archive_filename = format!("logs_archive_{}.json", timestamp);
     let mut archive_file = File::create(&archive_filename)?;
-    
+
     for archived_log in &archived_logs {
         writeln!(archive_file, "{}", archived_log)?;
     }
 
     // Rewrite current log file with only recent logs
-    let mut current_file = File::create(LOG_FILE_PATH)?;
+    let mut current_contents = String::new();
     for current_log in &current_logs {
-        writeln!(current_file, "{}", current_log)?;
+        current_contents.push_str(current_log);
+        current_contents.push('\n');
     }
+    write_atomic(Path::new(LOG_FILE_PATH), current_contents.as_bytes())?;
+    refresh_checksum(Path::new(LOG_FILE_PATH))?;
 
-    println!("Archived {} old logs to: {}", archived_logs.len(), archive_filename);
+    println!(
+        "Archived {} old logs to: {}",
+        archived_logs.len(),
+        archive_filename
+    );
     Ok(())
 }
+
+fn export_logs(format: &str) -> io::Result<()> {
     let mut file = File::open(LOG_FILE_PATH)?;
-    
+
     let mut contents = String::new();
     file.read_to_string(&mut contents)?;
-    
+
     if contents.trim().is_empty() {
         println!("No logs to export.");
         return Ok(());
@@ -550,27 +1430,23 @@ fn get_stats_as_json() -> String {
 
     let timestamp = Utc::now().format("%Y%m%d_%H%M%S");
     let export_filename = format!("logs_export_{}.{}", timestamp, format);
-    
+
     let mut export_file = File::create(&export_filename)?;
-    
+
     match format {
         "csv" => {
             writeln!(export_file, "timestamp,level,message")?;
-            for line in contents.lines() {
-                if line.trim().is_empty() {
-                    continue;
-                }
-                let log_entry: LogEntry = serde_json::from_str(line)
-                    .expect("Failed to deserialize log entry");
-                
+            for log_entry in parse_log_entries(&contents) {
                 let level_str = match log_entry.level {
                     LogLevel::INFO => "INFO",
-                    LogLevel::WARN => "WARN", 
+                    LogLevel::WARN => "WARN",
                     LogLevel::ERROR => "ERROR",
                     LogLevel::DEBUG => "DEBUG",
                 };
-                
-                writeln!(export_file, "{},{},{}",
+
+                writeln!(
+                    export_file,
+                    "{},{},{}",
                     log_entry.timestamp.format("%Y-%m-%d %H:%M:%S"),
                     level_str,
                     log_entry.message.replace(",", ";") // Escape commas
@@ -578,24 +1454,11 @@ fn get_stats_as_json() -> String {
             }
         }
         "txt" => {
-            for line in contents.lines() {
-                if line.trim().is_empty() {
-                    continue;
-                }
-                let log_entry: LogEntry = serde_json::from_str(line)
-                    .expect("Failed to deserialize log entry");
-                
-                let level_str = match log_entry.level {
-                    LogLevel::INFO => "INFO",
-                    LogLevel::WARN => "WARN",
-                    LogLevel::ERROR => "ERROR", 
-                    LogLevel::DEBUG => "DEBUG",
-                };
-                
-                writeln!(export_file, "[{}] [{}] {}",
-                    log_entry.timestamp.format("%Y-%m-%d %H:%M:%S"),
-                    level_str,
-                    log_entry.message
+            for log_entry in parse_log_entries(&contents) {
+                writeln!(
+                    export_file,
+                    "{}",
+                    apply_template(output_format(), &log_entry)
                 )?;
             }
         }
@@ -604,23 +1467,207 @@ fn get_stats_as_json() -> String {
             return Ok(());
         }
     }
-    
+
     println!("Logs exported to: {}", export_filename);
     Ok(())
 }
-    let mut file = match File::open(LOG_FILE_PATH) {
-        Ok(file) => file,
-        Err(_) => {
+
+/// Parses every non-empty line, skipping corrupt ones with a warning on stderr
+fn parse_log_entries(contents: &str) -> Vec<LogEntry> {
+    let mut entries = Vec::new();
+    let mut corrupt = 0;
+    for line in contents.lines() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str::<LogEntry>(line) {
+            Ok(entry) => entries.push(entry),
+            Err(_) => corrupt += 1,
+        }
+    }
+    if corrupt > 0 {
+        eprintln!(
+            "Warning: Skipped {} corrupt log line(s). Run `logger validate` for details.",
+            corrupt
+        );
+    }
+    entries
+}
+
+/// Appends the valid entries of `file` to the log, reporting corrupt lines, and
+/// sorts the log by timestamp. Lines already in the log are kept exactly as they
+/// are; a log file with corrupt lines of its own only gets the new entries
+/// appended, so nothing is lost before `validate --fix` is run.
+fn import_logs(file: &str, deduplicate: bool) -> io::Result<()> {
+    let contents = fs::read_to_string(file)?;
+    let mut imported = Vec::new();
+    let mut corrupt = 0;
+    for (index, line) in contents.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str::<LogEntry>(line) {
+            Ok(entry) => imported.push(entry),
+            Err(e) => {
+                corrupt += 1;
+                println!("{} line {}: {}", "Corrupt".red(), index + 1, e);
+            }
+        }
+    }
+
+    let mut seen: HashSet<(DateTime<Utc>, String)> = if deduplicate {
+        let existing = read_log_entries(None)?.unwrap_or_default();
+        existing
+            .into_iter()
+            .map(|entry| (entry.timestamp, entry.message))
+            .collect()
+    } else {
+        HashSet::new()
+    };
+    let mut new_entries = Vec::new();
+    let mut duplicates = 0;
+    for entry in imported {
+        if deduplicate && !seen.insert((entry.timestamp, entry.message.clone())) {
+            duplicates += 1;
+            continue;
+        }
+        new_entries.push(entry);
+    }
+    let added = new_entries.len();
+    new_entries.sort_by_key(|entry| entry.timestamp);
+
+    let paths: Vec<PathBuf> = if SPLIT_BY_LEVEL.load(Ordering::Relaxed) {
+        LogLevel::ALL.iter().map(|&level| log_path(level)).collect()
+    } else {
+        vec![PathBuf::from(LOG_FILE_PATH)]
+    };
+    for path in paths {
+        // Existing lines as written, with the timestamp of those that parse
+        let existing = read_to_string_or_empty(&path)?;
+        let mut lines: Vec<(Option<DateTime<Utc>>, String)> = existing
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                let timestamp = serde_json::from_str::<LogEntry>(line)
+                    .ok()
+                    .map(|entry| entry.timestamp);
+                (timestamp, line.to_string())
+            })
+            .collect();
+        let mut appended = 0;
+        for entry in new_entries
+            .iter()
+            .filter(|entry| log_path(entry.level) == path)
+        {
+            lines.push((Some(entry.timestamp), serde_json::to_string(entry)?));
+            appended += 1;
+        }
+        if appended == 0 {
+            continue;
+        }
+
+        let corrupt_lines = lines
+            .iter()
+            .filter(|(timestamp, _)| timestamp.is_none())
+            .count();
+        let contents = if corrupt_lines == 0 {
+            lines.sort_by_key(|(timestamp, _)| *timestamp);
+            lines
+                .iter()
+                .map(|(_, line)| format!("{}\n", line))
+                .collect::<String>()
+        } else {
+            println!(
+                "{} has {} corrupt line(s), so the entries were appended without sorting. \
+                 Run `logger validate --fix` to remove them.",
+                path.display(),
+                corrupt_lines
+            );
+            let mut contents = existing;
+            if !contents.is_empty() && !contents.ends_with('\n') {
+                contents.push('\n');
+            }
+            for (_, line) in &lines[lines.len() - appended..] {
+                contents.push_str(line);
+                contents.push('\n');
+            }
+            contents
+        };
+        write_atomic(&path, contents.as_bytes())?;
+        refresh_checksum(&path)?;
+    }
+
+    print!("Imported {} entries from {}", added, file);
+    if deduplicate {
+        print!(", skipped {} duplicate(s)", duplicates);
+    }
+    if corrupt > 0 {
+        print!(", {} corrupt line(s) left out", corrupt);
+    }
+    println!();
+    Ok(())
+}
+
+/// Reports each corrupt line with its number, content and parse error, then a summary.
+/// With `fix`, the corrupt lines are dropped from the file. Returns the number of
+/// corrupt lines left in the file.
+fn validate_logs(fix: bool) -> io::Result<usize> {
+    let path = Path::new(LOG_FILE_PATH);
+    if !path.exists() {
+        println!("No log file found. Nothing to validate.");
+        return Ok(0);
+    }
+    let contents = fs::read_to_string(path)?;
+
+    let mut valid_lines = Vec::new();
+    let mut corrupt = 0;
+    for (index, line) in contents.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str::<LogEntry>(line) {
+            Ok(_) => valid_lines.push(line),
+            Err(e) => {
+                corrupt += 1;
+                println!("{} line {}: {}", "Corrupt".red(), index + 1, e);
+                println!("    {}", line);
+            }
+        }
+    }
+
+    println!(
+        "{} valid entries, {} corrupt entries",
+        valid_lines.len(),
+        corrupt
+    );
+
+    if fix && corrupt > 0 {
+        let mut fixed = valid_lines.join("\n");
+        if !fixed.is_empty() {
+            fixed.push('\n');
+        }
+        write_atomic(path, fixed.as_bytes())?;
+        refresh_checksum(path)?;
+        println!("Removed {} corrupt line(s) from {}", corrupt, LOG_FILE_PATH);
+        return Ok(0);
+    }
+    Ok(corrupt)
+}
+
+fn show_log_statistics() {
+    let entries = match read_log_entries(None) {
+        Ok(Some(entries)) => entries,
+        Ok(None) => {
             println!("No log file found. No statistics to show.");
             return;
         }
+        Err(e) => {
+            eprintln!("Failed to read log file: {}", e);
+            return;
+        }
     };
 
-    let mut contents = String::new();
-    file.read_to_string(&mut contents)
-        .expect("Failed to read log file");
-
-    if contents.trim().is_empty() {
+    if entries.is_empty() {
         println!("Log file is empty.");
         return;
     }
@@ -633,22 +1680,16 @@ fn get_stats_as_json() -> String {
     let mut earliest_timestamp: Option<DateTime<Utc>> = None;
     let mut latest_timestamp: Option<DateTime<Utc>> = None;
 
-    for line in contents.lines() {
-        if line.trim().is_empty() {
-            continue;
-        }
-        let log_entry: LogEntry =
-            serde_json::from_str(line).expect("Failed to deserialize log entry");
-        
+    for log_entry in entries {
         total_logs += 1;
-        
+
         match log_entry.level {
             LogLevel::INFO => info_count += 1,
             LogLevel::WARN => warn_count += 1,
             LogLevel::ERROR => error_count += 1,
             LogLevel::DEBUG => debug_count += 1,
         }
-        
+
         if earliest_timestamp.is_none() || log_entry.timestamp < earliest_timestamp.unwrap() {
             earliest_timestamp = Some(log_entry.timestamp);
         }
@@ -659,111 +1700,199 @@ fn get_stats_as_json() -> String {
 
     println!("📊 Log Statistics:");
     println!("Total logs: {}", total_logs);
-    println!("INFO: {} ({:.1}%)", info_count, (info_count as f64 / total_logs as f64) * 100.0);
-    println!("WARN: {} ({:.1}%)", warn_count, (warn_count as f64 / total_logs as f64) * 100.0);
-    println!("ERROR: {} ({:.1}%)", error_count, (error_count as f64 / total_logs as f64) * 100.0);
-    println!("DEBUG: {} ({:.1}%)", debug_count, (debug_count as f64 / total_logs as f64) * 100.0);
-    
+    println!(
+        "INFO: {} ({:.1}%)",
+        info_count,
+        (info_count as f64 / total_logs as f64) * 100.0
+    );
+    println!(
+        "WARN: {} ({:.1}%)",
+        warn_count,
+        (warn_count as f64 / total_logs as f64) * 100.0
+    );
+    println!(
+        "ERROR: {} ({:.1}%)",
+        error_count,
+        (error_count as f64 / total_logs as f64) * 100.0
+    );
+    println!(
+        "DEBUG: {} ({:.1}%)",
+        debug_count,
+        (debug_count as f64 / total_logs as f64) * 100.0
+    );
+
     if let (Some(earliest), Some(latest)) = (earliest_timestamp, latest_timestamp) {
-        println!("Time range: {} to {}", 
+        println!(
+            "Time range: {} to {}",
             earliest.format("%Y-%m-%d %H:%M:%S"),
             latest.format("%Y-%m-%d %H:%M:%S")
         );
     }
 }
-    let mut file = match File::open(LOG_FILE_PATH) {
-        Ok(file) => file,
-        Err(_) => {
+
+/// Gaps between entries longer than this are skipped rather than waited out,
+/// so clock skew or a quiet night doesn't stall the replay
+const MAX_REPLAY_DELAY: Duration = Duration::from_secs(60);
+
+/// Prints the matching entries in timestamp order, sleeping between them for
+/// the original gap divided by `speed`
+fn replay_logs(filter: &LogFilter, speed: f32) -> io::Result<()> {
+    let mut entries: Vec<LogEntry> = read_log_entries(None)?
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|entry| filter.matches(entry))
+        .collect();
+    if entries.is_empty() {
+        println!("No logs to replay.");
+        return Ok(());
+    }
+    entries.sort_by_key(|entry| entry.timestamp);
+
+    let mut previous: Option<DateTime<Utc>> = None;
+    for entry in &entries {
+        if let Some(gap) = previous.and_then(|previous| (entry.timestamp - previous).to_std().ok())
+        {
+            if gap <= MAX_REPLAY_DELAY {
+                // Slowed down, the delay still never exceeds MAX_REPLAY_DELAY
+                let delay = Duration::try_from_secs_f32(gap.as_secs_f32() / speed)
+                    .unwrap_or(MAX_REPLAY_DELAY);
+                thread::sleep(delay.min(MAX_REPLAY_DELAY));
+            }
+        }
+        previous = Some(entry.timestamp);
+        print_log_entry(entry);
+        io::stdout().flush()?;
+    }
+    Ok(())
+}
+
+/// Counts the entries that match the filter and, if given, contain `keyword`
+fn count_logs(filter: &LogFilter, keyword: Option<&str>) -> io::Result<usize> {
+    let entries = read_log_entries(filter.level)?.unwrap_or_default();
+    let needle = keyword.map(str::to_lowercase);
+    Ok(entries
+        .iter()
+        .filter(|entry| filter.matches(entry))
+        .filter(|entry| {
+            needle
+                .as_ref()
+                .is_none_or(|needle| entry.message.to_lowercase().contains(needle))
+        })
+        .count())
+}
+
+/// Prints entries matching the keyword (and filter) with `before`/`after`
+/// entries of context around each match. Context entries are shown whatever their
+/// level; overlapping windows are merged and separate groups are divided by `--`.
+fn search_logs(keyword: &str, filter: &LogFilter, before: usize, after: usize) {
+    let entries = match read_log_entries(filter.level) {
+        Ok(Some(entries)) => entries,
+        Ok(None) => {
             println!("No log file found. No logs to search.");
             return;
         }
+        Err(e) => {
+            eprintln!("Failed to read log file: {}", e);
+            return;
+        }
     };
 
-    let mut contents = String::new();
-    file.read_to_string(&mut contents)
-        .expect("Failed to read log file");
-
-    if contents.trim().is_empty() {
+    if entries.is_empty() {
         println!("Log file is empty.");
         return;
     }
 
-    let mut found = false;
-    for line in contents.lines() {
-        if line.trim().is_empty() {
-            continue;
-        }
-        let log_entry: LogEntry =
-            serde_json::from_str(line).expect("Failed to deserialize log entry");
-        
-        if log_entry.message.to_lowercase().contains(&keyword.to_lowercase()) {
-            let level_str = match log_entry.level {
-                LogLevel::INFO => "INFO".green(),
-                LogLevel::WARN => "WARN".yellow(),
-                LogLevel::ERROR => "ERROR".red(),
-                LogLevel::DEBUG => "DEBUG".blue(),
-            };
-            
-            println!("[{}] [{}] {}", 
-                log_entry.timestamp.format("%Y-%m-%d %H:%M:%S").dimmed(),
-                level_str,
-                log_entry.message
-            );
-            found = true;
+    let needle = keyword.to_lowercase();
+    let matches = entries.iter().enumerate().filter(|(_, entry)| {
+        entry.message.to_lowercase().contains(&needle) && filter.matches(entry)
+    });
+
+    // Windows arrive in order, so each one either extends the last group or starts a new one
+    let mut groups: Vec<(usize, usize)> = Vec::new();
+    for (index, _) in matches {
+        let start = index.saturating_sub(before);
+        let end = (index + after + 1).min(entries.len());
+        match groups.last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(end),
+            _ => groups.push((start, end)),
         }
     }
-    
-    if !found {
+
+    if groups.is_empty() {
         println!("No logs found containing: {}", keyword);
+        return;
+    }
+
+    for (group_index, (start, end)) in groups.into_iter().enumerate() {
+        if group_index > 0 && (before > 0 || after > 0) {
+            println!("--");
+        }
+        for entry in &entries[start..end] {
+            print_log_entry(entry);
+        }
     }
 }
-    let mut file = match File::open(LOG_FILE_PATH) {
-        Ok(file) => file,
-        Err(_) => {
+
+fn print_log_entry(log_entry: &LogEntry) {
+    if let Some(template) = OUTPUT_FORMAT.get() {
+        println!("{}", apply_template(template, log_entry));
+        return;
+    }
+
+    let level_str = match log_entry.level {
+        LogLevel::INFO => "INFO".green(),
+        LogLevel::WARN => "WARN".yellow(),
+        LogLevel::ERROR => "ERROR".red(),
+        LogLevel::DEBUG => "DEBUG".blue(),
+    };
+
+    let trace = match (&log_entry.trace_id, &log_entry.span_id) {
+        (Some(trace_id), Some(span_id)) => format!(" trace={} span={}", trace_id, span_id),
+        (Some(trace_id), None) => format!(" trace={}", trace_id),
+        _ => String::new(),
+    };
+
+    println!(
+        "[{}] [{}] {}{}",
+        log_entry
+            .timestamp
+            .format("%Y-%m-%d %H:%M:%S")
+            .to_string()
+            .dimmed(),
+        level_str,
+        log_entry.message,
+        trace.dimmed()
+    );
+}
+
+fn read_logs_filtered(filter: &LogFilter) {
+    let entries = match read_log_entries(filter.level) {
+        Ok(Some(entries)) => entries,
+        Ok(None) => {
             println!("No log file found. No logs to display.");
             return;
         }
+        Err(e) => {
+            eprintln!("Failed to read log file: {}", e);
+            return;
+        }
     };
 
-    let mut contents = String::new();
-    file.read_to_string(&mut contents)
-        .expect("Failed to read log file");
-
-    if contents.trim().is_empty() {
+    if entries.is_empty() {
         println!("Log file is empty.");
         return;
     }
 
-    for line in contents.lines() {
-        if line.trim().is_empty() {
+    for log_entry in entries {
+        if !filter.matches(&log_entry) {
             continue;
         }
-        let log_entry: LogEntry =
-            serde_json::from_str(line).expect("Failed to deserialize log entry");
-        
-        // Filter by level if specified
-        if let Some(filter_level) = level_filter {
-            if std::mem::discriminant(&log_entry.level) != std::mem::discriminant(&filter_level) {
-                continue;
-            }
-        }
-        
-        let level_str = match log_entry.level {
-            LogLevel::INFO => "INFO".green(),
-            LogLevel::WARN => "WARN".yellow(),
-            LogLevel::ERROR => "ERROR".red(),
-            LogLevel::DEBUG => "DEBUG".blue(),
-        };
-        
-        println!("[{}] [{}] {}", 
-            log_entry.timestamp.format("%Y-%m-%d %H:%M:%S").dimmed(),
-            level_str,
-            log_entry.message
-        );
+
+        print_log_entry(&log_entry);
     }
 }
 
-fn main() {
+fn run_interactive_mode() {
     println!("Please select an option:");
 
     println!(
@@ -793,19 +1922,19 @@ fn main() {
 
         match choice {
             "1" => {
-                read_logs_filtered(None);
+                read_logs_filtered(&LogFilter::default());
             }
             "2" => {
-                read_logs_filtered(Some(LogLevel::INFO));
+                read_logs_filtered(&LogFilter::by_level(LogLevel::INFO));
             }
             "3" => {
-                read_logs_filtered(Some(LogLevel::WARN));
+                read_logs_filtered(&LogFilter::by_level(LogLevel::WARN));
             }
             "4" => {
-                read_logs_filtered(Some(LogLevel::ERROR));
+                read_logs_filtered(&LogFilter::by_level(LogLevel::ERROR));
             }
             "5" => {
-                read_logs_filtered(Some(LogLevel::DEBUG));
+                read_logs_filtered(&LogFilter::by_level(LogLevel::DEBUG));
             }
             "6" => {
                 println!("Enter search keyword:");
@@ -813,7 +1942,7 @@ fn main() {
                 io::stdin()
                     .read_line(&mut keyword)
                     .expect("Failed to read line");
-                search_logs(keyword.trim());
+                search_logs(keyword.trim(), &LogFilter::default(), 0, 0);
             }
             "7" => {
                 show_log_statistics();
@@ -828,7 +1957,25 @@ fn main() {
                     println!("Failed to export logs: {}", e);
                 }
             }
-            "9" => {
+            "10" => {
+                println!("Enter INFO log message:");
+                let mut message = String::new();
+                io::stdin()
+                    .read_line(&mut message)
+                    .expect("Failed to read line");
+                log_message(LogLevel::INFO, message.trim());
+                println!("INFO log written.");
+            }
+            "11" => {
+                println!("Enter WARN log message:");
+                let mut message = String::new();
+                io::stdin()
+                    .read_line(&mut message)
+                    .expect("Failed to read line");
+                log_message(LogLevel::WARN, message.trim());
+                println!("WARN log written.");
+            }
+            "12" => {
                 println!("Enter ERROR log message:");
                 let mut message = String::new();
                 io::stdin()
@@ -837,7 +1984,7 @@ fn main() {
                 log_message(LogLevel::ERROR, message.trim());
                 println!("ERROR log written.");
             }
-            "10" => {
+            "13" => {
                 println!("Enter DEBUG log message:");
                 let mut message = String::new();
                 io::stdin()
@@ -846,7 +1993,7 @@ fn main() {
                 log_message(LogLevel::DEBUG, message.trim());
                 println!("DEBUG log written.");
             }
-            "11" => {
+            "14" => {
                 println!("Exiting...");
                 break;
             }
@@ -856,6 +2003,6 @@ fn main() {
         }
 
         println!("\nPlease select an option:");
-        println!("1. Read All Logs\n2. Read INFO Logs\n3. Read WARN Logs\n4. Read ERROR Logs\n5. Read DEBUG Logs\n6. Search Logs\n7. Show Statistics\n8. Write INFO Log\n9. Write WARN Log\n10. Write ERROR Log\n11. Write DEBUG Log\n12. Exit");
+        println!("1. Read All Logs\n2. Read INFO Logs\n3. Read WARN Logs\n4. Read ERROR Logs\n5. Read DEBUG Logs\n6. Search Logs\n7. Show Statistics\n8. Export to CSV\n9. Export to TXT\n10. Write INFO Log\n11. Write WARN Log\n12. Write ERROR Log\n13. Write DEBUG Log\n14. Exit");
     }
 }
@@ -1,88 +1,380 @@
-use std::io;
-use clap::Parser;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use chrono::Utc;
+use clap::{Parser, ValueEnum};
+use common::file_utils::{open_append_create, read_to_string_or_empty};
 
 #[derive(Parser)]
 #[command(name = "temp_converter")]
 #[command(about = "A simple temperature converter CLI")]
+#[command(
+    after_help = "By default values are absolute temperatures (0 °C = 32 °F). With --delta they are \
+temperature differences, so only the scale factor applies (a 10 °C rise is an 18 °F rise).
+
+Exit codes:
+  1  Invalid arguments or history file error
+  2  Invalid unit or other usage error
+  3  Temperature below absolute zero
+  4  Non-finite temperature value"
+)]
 struct Args {
     /// The temperature value to convert
     #[arg(long)]
     value: Option<f64>,
 
-    /// The unit of the input temperature (C, F, K)
+    /// The unit of the input temperature
+    #[arg(long, value_enum, ignore_case = true)]
+    from: Option<TemperatureUnit>,
+
+    /// The unit to convert to
+    #[arg(long, value_enum, ignore_case = true)]
+    to: Option<TemperatureUnit>,
+
+    /// Treat the value as a temperature difference instead of an absolute temperature
+    #[arg(long)]
+    delta: bool,
+
+    /// Convert every value from START to END in steps of STEP and print a table
+    #[arg(
+        long,
+        value_name = "START:END:STEP",
+        conflicts_with = "value",
+        requires_all = ["from", "to"],
+        allow_hyphen_values = true
+    )]
+    range: Option<String>,
+
+    /// Append every conversion to this CSV file
+    #[arg(long, value_name = "FILE")]
+    history: Option<PathBuf>,
+
+    /// Print the converted value without the unit symbol, for scripts
     #[arg(long)]
-    from: Option<String>,
+    no_symbol: bool,
 
-    /// The unit to convert to (C, F, K)
+    /// Print the conversion history and exit
     #[arg(long)]
-    to: Option<String>,
+    show_history: bool,
+
+    /// Clear the conversion history after showing it
+    #[arg(long, requires = "show_history")]
+    clear_history: bool,
+}
+
+const DEFAULT_HISTORY_FILE: &str = "temp_history.csv";
+
+/// Most rows a `--range` table may have
+const MAX_RANGE_ROWS: u64 = 10_000;
+
+/// Errors in one-shot mode, each mapped to its own exit code so scripts can tell them apart
+#[derive(Debug)]
+enum ConverterError {
+    InvalidArgument(String),
+    BelowAbsoluteZero,
+    NonFinite,
+}
+
+impl ConverterError {
+    fn exit_code(&self) -> i32 {
+        match self {
+            ConverterError::InvalidArgument(_) => 1,
+            // 2 is left to clap, which rejects invalid units before main runs
+            ConverterError::BelowAbsoluteZero => 3,
+            ConverterError::NonFinite => 4,
+        }
+    }
+
+    /// Reports the error on stderr and exits with its code
+    fn exit(&self) -> ! {
+        eprintln!("Error: {}", self);
+        std::process::exit(self.exit_code());
+    }
 }
 
+impl std::fmt::Display for ConverterError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConverterError::InvalidArgument(msg) => write!(f, "{}", msg),
+            ConverterError::BelowAbsoluteZero => {
+                write!(f, "Temperature below absolute zero is not possible.")
+            }
+            ConverterError::NonFinite => {
+                write!(f, "Please enter a finite number for temperature value.")
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
 enum TemperatureUnit {
+    #[value(alias = "c", alias = "centigrade")]
     Celsius,
+    #[value(alias = "f")]
     Fahrenheit,
+    #[value(alias = "k")]
     Kelvin,
+    #[value(alias = "de")]
+    Delisle,
+    #[value(alias = "n")]
+    Newton,
 }
 
-fn convert_temp(value: f64, from: TemperatureUnit, to: TemperatureUnit) -> f64 {
-    match (from, to) {
-        (TemperatureUnit::Celsius, TemperatureUnit::Fahrenheit) => value * 9.0 / 5.0 + 32.0,
-        (TemperatureUnit::Celsius, TemperatureUnit::Kelvin) => value + 273.15,
-        (TemperatureUnit::Kelvin, TemperatureUnit::Fahrenheit) => {
-            (value - 273.15) * 9.0 / 5.0 + 32.0
-        }
-        (TemperatureUnit::Kelvin, TemperatureUnit::Celsius) => value - 273.15,
-        (TemperatureUnit::Fahrenheit, TemperatureUnit::Celsius) => (value - 32.0) * 5.0 / 9.0,
-        (TemperatureUnit::Fahrenheit, TemperatureUnit::Kelvin) => {
-            (value - 32.0) * 5.0 / 9.0 + 273.15
+fn convert_temp(value: f64, from: TemperatureUnit, to: TemperatureUnit, delta: bool) -> f64 {
+    if delta {
+        return convert_delta(value, from, to);
+    }
+
+    to.convert_from_kelvin(from.to_kelvin(value))
+}
+
+/// Converts a temperature difference, which only needs the scale factor and no offset
+fn convert_delta(value: f64, from: TemperatureUnit, to: TemperatureUnit) -> f64 {
+    value * from.kelvin_per_degree() / to.kelvin_per_degree()
+}
+
+/// Absolute zero expressed in the given unit.
+/// Delisle runs backwards (colder is higher), so there it is the largest valid value.
+fn min_valid(unit: TemperatureUnit) -> f64 {
+    match unit {
+        TemperatureUnit::Celsius => -273.15,
+        TemperatureUnit::Fahrenheit => -459.67,
+        TemperatureUnit::Kelvin => 0.0,
+        TemperatureUnit::Delisle => 559.725,
+        TemperatureUnit::Newton => -90.1395,
+    }
+}
+
+fn is_below_absolute_zero(value: f64, unit: TemperatureUnit) -> bool {
+    match unit {
+        TemperatureUnit::Delisle => value > min_valid(unit),
+        _ => value < min_valid(unit),
+    }
+}
+
+/// Parses `start:end:step` into its three numbers, checking the range is usable
+fn parse_range(input: &str) -> Result<(f64, f64, f64), ConverterError> {
+    let parts: Vec<&str> = input.split(':').collect();
+    if parts.len() != 3 {
+        return Err(ConverterError::InvalidArgument(format!(
+            "Invalid range '{}'. Use START:END:STEP.",
+            input
+        )));
+    }
+
+    let mut numbers = [0.0; 3];
+    for (number, part) in numbers.iter_mut().zip(&parts) {
+        *number = part.trim().parse::<f64>().map_err(|_| {
+            ConverterError::InvalidArgument(format!("Invalid number '{}' in range.", part))
+        })?;
+        if !number.is_finite() {
+            return Err(ConverterError::NonFinite);
         }
-        _ => value,
+    }
+
+    let [start, end, step] = numbers;
+    if step <= 0.0 {
+        return Err(ConverterError::InvalidArgument(
+            "Range step must be greater than 0.".to_string(),
+        ));
+    }
+    if start > end {
+        return Err(ConverterError::InvalidArgument(
+            "Range start must not be greater than its end.".to_string(),
+        ));
+    }
+    if (end - start) / step >= MAX_RANGE_ROWS as f64 {
+        return Err(ConverterError::InvalidArgument(format!(
+            "Range would have more than {} rows. Use a larger step.",
+            MAX_RANGE_ROWS
+        )));
+    }
+    Ok((start, end, step))
+}
+
+/// Appends one conversion to the history CSV, writing the header for a new file
+fn append_history(
+    path: &Path,
+    value: f64,
+    from: TemperatureUnit,
+    to: TemperatureUnit,
+    output: f64,
+) -> io::Result<()> {
+    let is_new = !path.exists();
+    let mut file = open_append_create(path)?;
+    if is_new {
+        writeln!(file, "input,from,to,output,timestamp")?;
+    }
+    writeln!(
+        file,
+        "{},{},{},{:.2},{}",
+        value,
+        from.code(),
+        to.code(),
+        output,
+        Utc::now().to_rfc3339()
+    )
+}
+
+/// Records a conversion if history is enabled; a failed write only warns
+fn record_history(
+    history: &Option<PathBuf>,
+    value: f64,
+    from: TemperatureUnit,
+    to: TemperatureUnit,
+    output: f64,
+) {
+    if let Some(path) = history
+        && let Err(e) = append_history(path, value, from, to, output)
+    {
+        eprintln!(
+            "Warning: Could not write history to {}: {}",
+            path.display(),
+            e
+        );
+    }
+}
+
+/// `32.00 °F`, or the bare number when symbols are turned off
+fn format_result(value: f64, unit: TemperatureUnit, no_symbol: bool) -> String {
+    if no_symbol {
+        format!("{:.2}", value)
+    } else {
+        format!("{:.2} {}", value, unit.symbol())
     }
 }
 
+fn show_history(path: &Path, clear: bool) -> io::Result<()> {
+    let contents = read_to_string_or_empty(path)?;
+    if contents.trim().is_empty() {
+        println!("No conversion history yet.");
+    } else {
+        print!("{}", contents);
+    }
+
+    if clear && path.exists() {
+        fs::remove_file(path)?;
+        println!("History cleared.");
+    }
+    Ok(())
+}
+
 impl TemperatureUnit {
-    fn from_str(input: &str) -> Option<TemperatureUnit> {
-        match input.to_uppercase().as_str() {
-            "C" => Some(TemperatureUnit::Celsius),
-            "F" => Some(TemperatureUnit::Fahrenheit),
-            "K" => Some(TemperatureUnit::Kelvin),
-            _ => None,
+    fn code(&self) -> &str {
+        match self {
+            TemperatureUnit::Celsius => "C",
+            TemperatureUnit::Fahrenheit => "F",
+            TemperatureUnit::Kelvin => "K",
+            TemperatureUnit::Delisle => "De",
+            TemperatureUnit::Newton => "N",
+        }
+    }
+
+    fn symbol(&self) -> &str {
+        match self {
+            TemperatureUnit::Celsius => "°C",
+            TemperatureUnit::Fahrenheit => "°F",
+            TemperatureUnit::Kelvin => "K",
+            TemperatureUnit::Delisle => "°De",
+            TemperatureUnit::Newton => "°N",
+        }
+    }
+
+    fn to_kelvin(self, value: f64) -> f64 {
+        match self {
+            TemperatureUnit::Celsius => value + 273.15,
+            TemperatureUnit::Fahrenheit => (value - 32.0) * 5.0 / 9.0 + 273.15,
+            TemperatureUnit::Kelvin => value,
+            TemperatureUnit::Delisle => 373.15 - value * 2.0 / 3.0,
+            TemperatureUnit::Newton => value * 100.0 / 33.0 + 273.15,
+        }
+    }
+
+    fn convert_from_kelvin(self, kelvin: f64) -> f64 {
+        match self {
+            TemperatureUnit::Celsius => kelvin - 273.15,
+            TemperatureUnit::Fahrenheit => (kelvin - 273.15) * 9.0 / 5.0 + 32.0,
+            TemperatureUnit::Kelvin => kelvin,
+            TemperatureUnit::Delisle => (373.15 - kelvin) * 3.0 / 2.0,
+            TemperatureUnit::Newton => (kelvin - 273.15) * 33.0 / 100.0,
+        }
+    }
+
+    /// Size of one degree of this unit in kelvin (negative for the inverted Delisle scale)
+    fn kelvin_per_degree(self) -> f64 {
+        match self {
+            TemperatureUnit::Celsius | TemperatureUnit::Kelvin => 1.0,
+            TemperatureUnit::Fahrenheit => 5.0 / 9.0,
+            TemperatureUnit::Delisle => -2.0 / 3.0,
+            TemperatureUnit::Newton => 100.0 / 33.0,
         }
     }
+
+    /// Parses interactive input with the same names and aliases as `--from`/`--to`
+    fn parse(input: &str) -> Option<TemperatureUnit> {
+        <TemperatureUnit as ValueEnum>::from_str(input.trim(), true).ok()
+    }
 }
 
 fn main() {
     let args = Args::parse();
 
-    if let (Some(value), Some(from_str), Some(to_str)) = (args.value, args.from, args.to) {
-        let from_unit = match TemperatureUnit::from_str(&from_str) {
-            Some(unit) => unit,
-            None => {
-                eprintln!("Error: Invalid 'from' unit '{}'. Use C, F, or K.", from_str);
-                std::process::exit(1);
-            }
-        };
+    if args.show_history {
+        let path = args
+            .history
+            .as_deref()
+            .unwrap_or(Path::new(DEFAULT_HISTORY_FILE));
+        if let Err(e) = show_history(path, args.clear_history) {
+            ConverterError::InvalidArgument(format!(
+                "Could not read history {}: {}",
+                path.display(),
+                e
+            ))
+            .exit();
+        }
+        return;
+    }
 
-        let to_unit = match TemperatureUnit::from_str(&to_str) {
-            Some(unit) => unit,
-            None => {
-                eprintln!("Error: Invalid 'to' unit '{}'. Use C, F, or K.", to_str);
-                std::process::exit(1);
-            }
+    if let (Some(range), Some(from_unit), Some(to_unit)) = (&args.range, args.from, args.to) {
+        let (start, end, step) = parse_range(range).unwrap_or_else(|e| e.exit());
+
+        // Computing each value from the start avoids accumulating float error
+        let steps = ((end - start) / step + 1e-9).floor() as u64;
+        let value_at = |i: u64| start + step * i as f64;
+
+        // The coldest value is the first, or the last on the inverted Delisle scale
+        let coldest = match from_unit {
+            TemperatureUnit::Delisle => value_at(steps),
+            _ => start,
         };
+        if !args.delta && is_below_absolute_zero(coldest, from_unit) {
+            ConverterError::BelowAbsoluteZero.exit();
+        }
 
-        if value < -273.15 {
-            eprintln!("Error: Temperature below absolute zero is not possible.");
-            std::process::exit(1);
+        println!("{:>12} {:>12}", from_unit.code(), to_unit.code());
+        for value in (0..=steps).map(value_at) {
+            let converted_value = convert_temp(value, from_unit, to_unit, args.delta);
+            record_history(&args.history, value, from_unit, to_unit, converted_value);
+            println!("{:>12.2} {:>12.2}", value, converted_value);
         }
+        return;
+    }
 
+    if let (Some(value), Some(from_unit), Some(to_unit)) = (args.value, args.from, args.to) {
         if value.is_infinite() || value.is_nan() {
-            eprintln!("Error: Please enter a finite number for temperature value.");
-            std::process::exit(1);
+            ConverterError::NonFinite.exit();
+        }
+
+        if !args.delta && is_below_absolute_zero(value, from_unit) {
+            ConverterError::BelowAbsoluteZero.exit();
         }
 
-        let converted_value = convert_temp(value, from_unit, to_unit);
-        println!("Converted temperature: {:.2}", converted_value);
+        let converted_value = convert_temp(value, from_unit, to_unit, args.delta);
+        record_history(&args.history, value, from_unit, to_unit, converted_value);
+        println!(
+            "Converted temperature: {}",
+            format_result(converted_value, to_unit, args.no_symbol)
+        );
         return;
     }
 
@@ -109,46 +401,56 @@ fn main() {
             }
         };
 
-        if temp_value < -273.15 {
-            println!(
-                "\nTemperature below absolute zero is not possible. Please enter a valid temperature.\n"
-            );
-            continue;
-        }
-
         if temp_value.is_infinite() || temp_value.is_nan() {
             println!("\nPlease enter a finite number for temperature value.\n");
             continue;
         }
 
-        println!("\nPlease enter the unit of the temperature (C, F, K): \n");
+        println!("\nPlease enter the unit of the temperature (C, F, K, De, N or full name): \n");
         let mut unit_input = String::new();
         io::stdin()
             .read_line(&mut unit_input)
             .expect("Failed to read line");
-        let from_unit = match TemperatureUnit::from_str(unit_input.trim()) {
+        let from_unit = match TemperatureUnit::parse(unit_input.trim()) {
             Some(unit) => unit,
             None => {
-                println!("\nInvalid unit. Please enter C, F, or K.\n");
+                println!("\nInvalid unit. Please enter C, F, K, De, N or a full unit name.\n");
                 continue;
             }
         };
 
-        println!("\nPlease enter the unit to convert to (C, F, K): \n");
+        if !args.delta && is_below_absolute_zero(temp_value, from_unit) {
+            println!(
+                "\nTemperature below absolute zero is not possible. Please enter a valid temperature.\n"
+            );
+            continue;
+        }
+
+        println!("\nPlease enter the unit to convert to (C, F, K, De, N or full name): \n");
         let mut to_unit_input = String::new();
         io::stdin()
             .read_line(&mut to_unit_input)
             .expect("Failed to read line");
-        let to_unit = match TemperatureUnit::from_str(to_unit_input.trim()) {
+        let to_unit = match TemperatureUnit::parse(to_unit_input.trim()) {
             Some(unit) => unit,
             None => {
-                println!("\nInvalid unit. Please enter C, F, or K.\n");
+                println!("\nInvalid unit. Please enter C, F, K, De, N or a full unit name.\n");
                 continue;
             }
         };
 
-        let converted_value = convert_temp(temp_value, from_unit, to_unit);
-        println!("\nConverted temperature: {:.2}\n", converted_value);
+        let converted_value = convert_temp(temp_value, from_unit, to_unit, args.delta);
+        record_history(
+            &args.history,
+            temp_value,
+            from_unit,
+            to_unit,
+            converted_value,
+        );
+        println!(
+            "\nConverted temperature: {}\n",
+            format_result(converted_value, to_unit, args.no_symbol)
+        );
 
         println!("Do you want to reverse the conversion (swap units)? (y/n): ");
         let mut reverse_input = String::new();
@@ -158,8 +460,18 @@ fn main() {
         let reverse_choice = reverse_input.trim().to_lowercase();
 
         if reverse_choice == "y" || reverse_choice == "yes" {
-            let reversed_value = convert_temp(temp_value, to_unit, from_unit);
-            println!("\nReversed conversion: {:.2}\n", reversed_value);
+            let reversed_value = convert_temp(temp_value, to_unit, from_unit, args.delta);
+            record_history(
+                &args.history,
+                temp_value,
+                to_unit,
+                from_unit,
+                reversed_value,
+            );
+            println!(
+                "\nReversed conversion: {}\n",
+                format_result(reversed_value, from_unit, args.no_symbol)
+            );
         }
     }
 }
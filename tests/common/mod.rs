@@ -0,0 +1,33 @@
+//! Helpers shared by the workspace integration tests.
+
+use std::path::PathBuf;
+use std::process;
+use std::sync::Once;
+use std::time::Duration;
+
+use assert_cmd::Command;
+
+static BUILD: Once = Once::new();
+
+/// Command for one of the workspace binaries.
+pub fn bin(name: &str) -> Command {
+    let mut cmd = Command::new(bin_path(name));
+    cmd.timeout(Duration::from_secs(30));
+    cmd
+}
+
+/// Path of one of the workspace binaries, for tests that need to drive a
+/// long-running process themselves.
+/// Cargo only builds the binaries of the package under test, so the
+/// other members are built once per test binary before the first spawn.
+pub fn bin_path(name: &str) -> PathBuf {
+    BUILD.call_once(|| {
+        let status = process::Command::new(env!("CARGO"))
+            .args(["build", "--workspace", "--bins", "--quiet"])
+            .status()
+            .expect("Failed to run cargo build");
+        assert!(status.success(), "Failed to build workspace binaries");
+    });
+
+    assert_cmd::cargo::cargo_bin(name)
+}
@@ -0,0 +1,330 @@
+mod common;
+
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::process::Stdio;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+use predicates::prelude::*;
+use tempfile::tempdir;
+
+const VALID_CONFIG: &str = r#"
+[server]
+host = "127.0.0.1"
+port = 8080
+
+[database]
+host = "localhost"
+port = 5432
+username = "postgres"
+password = ""
+database = "myapp"
+
+[logging]
+level = "info"
+
+[features]
+"#;
+
+#[test]
+fn validate_accepts_valid_config() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("app.toml"), VALID_CONFIG).unwrap();
+
+    common::bin("config_reader")
+        .current_dir(dir.path())
+        .args(["validate", "--config", "app.toml"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Configuration is valid!"));
+}
+
+#[test]
+fn validate_reports_parse_error() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("app.toml"), "[server\nhost = ").unwrap();
+
+    common::bin("config_reader")
+        .current_dir(dir.path())
+        .args(["validate", "--config", "app.toml"])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("TOML parse error"));
+}
+
+#[test]
+fn run_reports_invalid_port() {
+    let dir = tempdir().unwrap();
+
+    common::bin("config_reader")
+        .current_dir(dir.path())
+        .args(["run", "--server-port", "0"])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "Server port must be greater than 0",
+        ));
+}
+
+/// Forwards each line of a child's output to a channel
+fn line_channel(output: impl std::io::Read + Send + 'static) -> mpsc::Receiver<String> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        for line in BufReader::new(output).lines().map_while(Result::ok) {
+            if sender.send(line).is_err() {
+                break;
+            }
+        }
+    });
+    receiver
+}
+
+/// Waits up to five seconds for a line containing `needle`
+fn wait_for_line(lines: &mpsc::Receiver<String>, needle: &str) {
+    loop {
+        match lines.recv_timeout(Duration::from_secs(5)) {
+            Ok(line) if line.contains(needle) => return,
+            Ok(_) => {}
+            Err(_) => panic!("no line containing {needle:?}"),
+        }
+    }
+}
+
+#[test]
+fn watch_reports_changes_and_survives_parse_errors() {
+    let dir = tempdir().unwrap();
+    let config_path = dir.path().join("app.toml");
+    fs::write(&config_path, VALID_CONFIG).unwrap();
+
+    let mut child = std::process::Command::new(common::bin_path("config_reader"))
+        .current_dir(dir.path())
+        .args(["watch", "--config", "app.toml", "--interval", "50"])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let stdout = line_channel(child.stdout.take().unwrap());
+    let stderr = line_channel(child.stderr.take().unwrap());
+
+    wait_for_line(&stdout, "Watching app.toml");
+    // Keep the new modification time clearly apart from the original one
+    thread::sleep(Duration::from_millis(20));
+    fs::write(
+        &config_path,
+        VALID_CONFIG.replace("port = 8080", "port = 9090"),
+    )
+    .unwrap();
+    wait_for_line(&stdout, "Configuration reloaded");
+    wait_for_line(&stdout, "Port: 9090");
+
+    fs::write(&config_path, "[server\nhost = ").unwrap();
+    wait_for_line(&stderr, "Reload failed: Parse error: TOML parse error");
+
+    fs::write(
+        &config_path,
+        VALID_CONFIG.replace("port = 8080", "port = 7070"),
+    )
+    .unwrap();
+    wait_for_line(&stdout, "Port: 7070");
+
+    child.kill().unwrap();
+    child.wait().unwrap();
+}
+
+#[test]
+fn save_config_round_trips_through_toml() {
+    let dir = tempdir().unwrap();
+    fs::write(
+        dir.path().join("app.toml"),
+        VALID_CONFIG.replace(
+            "database = \"myapp\"",
+            "database = \"shop\"\nmax_connections = 20",
+        ),
+    )
+    .unwrap();
+
+    common::bin("config_reader")
+        .current_dir(dir.path())
+        .args([
+            "run",
+            "--config",
+            "app.toml",
+            "--server-port",
+            "9000",
+            "--save-config",
+            "saved.toml",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Saved configuration to saved.toml",
+        ));
+
+    let saved = fs::read_to_string(dir.path().join("saved.toml")).unwrap();
+    assert!(saved.contains("port = 9000"), "{saved}");
+    assert!(!saved.contains("password"), "{saved}");
+
+    // Values from the file survive next to the CLI override
+    common::bin("config_reader")
+        .current_dir(dir.path())
+        .args(["info", "--config", "saved.toml"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Port: 9000"))
+        .stdout(predicate::str::contains("Database: shop"))
+        .stdout(predicate::str::contains("Max Connections: 20"));
+
+    common::bin("config_reader")
+        .current_dir(dir.path())
+        .args([
+            "--save-config",
+            "secret.toml",
+            "run",
+            "--config",
+            "saved.toml",
+            "--database-password",
+            "hunter2",
+        ])
+        .assert()
+        .success();
+    let secret = fs::read_to_string(dir.path().join("secret.toml")).unwrap();
+    assert!(secret.contains("password = \"hunter2\""), "{secret}");
+    assert!(secret.contains("port = 9000"), "{secret}");
+}
+
+#[test]
+fn default_config_satisfies_schema() {
+    let dir = tempdir().unwrap();
+
+    let output = common::bin("config_reader").arg("schema").output().unwrap();
+    assert!(output.status.success());
+    let schema: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        schema["properties"]["logging"]["properties"]["level"]["enum"],
+        serde_json::json!(["debug", "info", "warn", "error"])
+    );
+    assert_eq!(
+        schema["properties"]["server"]["properties"]["port"]["maximum"],
+        65535
+    );
+    let validator = jsonschema::validator_for(&schema).unwrap();
+
+    common::bin("config_reader")
+        .current_dir(dir.path())
+        .args(["--save-config", "defaults.toml", "defaults"])
+        .assert()
+        .success();
+    let defaults: serde_json::Value =
+        toml::from_str(&fs::read_to_string(dir.path().join("defaults.toml")).unwrap()).unwrap();
+    let errors: Vec<String> = validator
+        .iter_errors(&defaults)
+        .map(|e| e.to_string())
+        .collect();
+    assert!(errors.is_empty(), "{errors:?}");
+
+    let mut invalid = defaults.clone();
+    invalid["logging"]["level"] = "verbose".into();
+    invalid["server"]["port"] = 70000.into();
+    assert_eq!(validator.iter_errors(&invalid).count(), 2);
+}
+
+const OVERLAY_CONFIG: &str = r#"
+server:
+  host: 0.0.0.0
+  port: 443
+  workers: 8
+database:
+  host: db.prod
+  port: 5432
+  username: app
+  database: myapp
+logging:
+  level: warn
+features:
+  debug_mode: false
+  metrics: true
+"#;
+
+#[test]
+fn merge_combines_base_and_overlay() {
+    let dir = tempdir().unwrap();
+    fs::write(
+        dir.path().join("config.toml"),
+        VALID_CONFIG.replace(
+            "[features]",
+            "file = \"app.log\"\n\n[features]\ndebug_mode = true\ncache = true",
+        ),
+    )
+    .unwrap();
+    fs::write(dir.path().join("config.prod.yaml"), OVERLAY_CONFIG).unwrap();
+
+    let merge = |strategy: &str, output: &str| {
+        common::bin("config_reader")
+            .current_dir(dir.path())
+            .args([
+                "merge",
+                "--base",
+                "config.toml",
+                "--overlay",
+                "config.prod.yaml",
+            ])
+            .args(["--output", output, "--strategy", strategy])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(format!(
+                "Merged config.toml and config.prod.yaml into {output}"
+            )));
+        fs::read_to_string(dir.path().join(output)).unwrap()
+    };
+
+    let merged: toml::Value = toml::from_str(&merge("overlay", "merged.toml")).unwrap();
+    assert_eq!(merged["server"]["host"].as_str(), Some("0.0.0.0"));
+    assert_eq!(merged["server"]["port"].as_integer(), Some(443));
+    assert_eq!(merged["server"]["workers"].as_integer(), Some(8));
+    assert_eq!(merged["database"]["host"].as_str(), Some("db.prod"));
+    // Only the base sets these
+    assert_eq!(merged["logging"]["level"].as_str(), Some("warn"));
+    assert_eq!(merged["logging"]["file"].as_str(), Some("app.log"));
+    assert_eq!(merged["features"]["cache"].as_bool(), Some(true));
+    assert_eq!(merged["features"]["metrics"].as_bool(), Some(true));
+    assert_eq!(merged["features"]["debug_mode"].as_bool(), Some(false));
+
+    let additive: serde_json::Value =
+        serde_json::from_str(&merge("additive", "merged.json")).unwrap();
+    assert_eq!(additive["server"]["port"], 443);
+    assert_eq!(
+        additive["features"],
+        serde_json::json!({ "cache": true, "debug_mode": true, "metrics": true })
+    );
+}
+
+#[test]
+fn merge_rejects_invalid_result() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("base.toml"), VALID_CONFIG).unwrap();
+    fs::write(
+        dir.path().join("overlay.yaml"),
+        OVERLAY_CONFIG.replace("level: warn", "level: loud"),
+    )
+    .unwrap();
+
+    common::bin("config_reader")
+        .current_dir(dir.path())
+        .args([
+            "merge",
+            "--base",
+            "base.toml",
+            "--overlay",
+            "overlay.yaml",
+            "--output",
+            "out.toml",
+        ])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "Failed to merge configuration: Validation error",
+        ));
+    assert!(!dir.path().join("out.toml").exists());
+}
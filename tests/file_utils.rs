@@ -0,0 +1,47 @@
+use std::fs;
+use std::io::Write;
+
+use common::file_utils::{open_append_create, read_to_string_or_empty, write_atomic};
+use tempfile::tempdir;
+
+#[test]
+fn missing_file_reads_as_empty() {
+    let dir = tempdir().unwrap();
+    assert_eq!(
+        read_to_string_or_empty(&dir.path().join("missing.txt")).unwrap(),
+        ""
+    );
+
+    // Other errors, like reading a directory, still surface
+    assert!(read_to_string_or_empty(dir.path()).is_err());
+}
+
+#[test]
+fn write_atomic_replaces_the_contents() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("data.json");
+    fs::write(&path, "a much longer old version of the file\n").unwrap();
+
+    write_atomic(&path, b"new\n").unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), "new\n");
+
+    // The temp file doesn't outlive the write
+    let names: Vec<_> = fs::read_dir(dir.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name())
+        .collect();
+    assert_eq!(names, ["data.json"]);
+}
+
+#[test]
+fn append_creates_then_appends() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("log.txt");
+    for line in ["one\n", "two\n"] {
+        open_append_create(&path)
+            .unwrap()
+            .write_all(line.as_bytes())
+            .unwrap();
+    }
+    assert_eq!(fs::read_to_string(&path).unwrap(), "one\ntwo\n");
+}
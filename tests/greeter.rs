@@ -0,0 +1,321 @@
+mod common;
+
+use predicates::prelude::*;
+
+#[test]
+fn arguments_skip_the_prompts() {
+    common::bin("CLIGreeter")
+        .env_remove("LANG")
+        .args(["--name", "Mary", "--age", "30", "--time", "14:00"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Good afternoon, nice to meet you Mary!",
+        ))
+        .stdout(predicate::str::contains("You are 30 years old!"))
+        .stdout(predicate::str::contains("What is your name?").not())
+        .stdout(predicate::str::contains("What is your age?").not());
+
+    common::bin("CLIGreeter")
+        .env_remove("LANG")
+        .args(["--name", "mary", "--age", "30"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Oh, it's you mary..."));
+}
+
+#[test]
+fn invalid_age_argument_is_rejected() {
+    common::bin("CLIGreeter")
+        .env_remove("LANG")
+        .args(["--name", "Mary", "--age", "old"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("That's not a valid age!"));
+}
+
+#[test]
+fn missing_values_are_prompted_for() {
+    common::bin("CLIGreeter")
+        .env_remove("LANG")
+        .args(["--time", "08:15"])
+        .write_stdin("John Smith\n42\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("What is your name?"))
+        .stdout(predicate::str::contains(
+            "Good morning, nice to meet you John Smith!",
+        ))
+        .stdout(predicate::str::contains("You are 42 years old!"));
+
+    common::bin("CLIGreeter")
+        .env_remove("LANG")
+        .args(["--name", "John"])
+        .write_stdin("abc\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("What is your name?").not())
+        .stdout(predicate::str::contains("What is your age?"))
+        .stdout(predicate::str::contains(
+            "That's not a valid age! Please enter a number.",
+        ));
+}
+
+#[test]
+fn blank_names_are_asked_for_again() {
+    common::bin("CLIGreeter")
+        .env_remove("LANG")
+        .args(["--age", "30", "--time", "14:00"])
+        .write_stdin("\n   \nMary\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Please enter a name.").count(2))
+        .stdout(predicate::str::contains(
+            "Good afternoon, nice to meet you Mary!",
+        ));
+
+    // After three blank answers the rude greeting is all that's left
+    common::bin("CLIGreeter")
+        .env_remove("LANG")
+        .args(["--age", "30", "--time", "14:00"])
+        .write_stdin("\n\n\nMary\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Please enter a name.").count(2))
+        .stdout(predicate::str::contains("Oh, it's you ...,"));
+}
+
+#[test]
+fn age_gets_a_follow_up_comment() {
+    for (age, comment) in [
+        ("8", "You're still a kid!"),
+        ("13", "Enjoy your teens!"),
+        ("19", "Enjoy your teens!"),
+        ("65", "Respect your wisdom!"),
+    ] {
+        common::bin("CLIGreeter")
+            .env_remove("LANG")
+            .args(["--name", "Mary", "--age", age, "--time", "14:00"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(format!(
+                "You are {age} years old!\n{comment}"
+            )));
+    }
+}
+
+#[test]
+fn greets_in_the_selected_language() {
+    let greet = |lang: &str, name: &str| {
+        common::bin("CLIGreeter")
+            .env_remove("LANG")
+            .args([
+                "--lang", lang, "--name", name, "--age", "30", "--time", "13:00",
+            ])
+            .assert()
+            .success()
+    };
+
+    greet("es", "Mary").stdout(predicate::str::contains(
+        "¡Buenas tardes, encantado de conocerte, Mary!",
+    ));
+    greet("fr", "Mary").stdout(predicate::str::contains(
+        "Bonjour, ravi de vous rencontrer, Mary !",
+    ));
+    greet("de", "mary").stdout(predicate::str::contains("Ach, du bist es, mary..."));
+    greet("xx", "Mary")
+        .stdout(predicate::str::contains(
+            "Good afternoon, nice to meet you Mary!",
+        ))
+        .stderr(predicate::str::contains("unknown language 'xx'"));
+
+    common::bin("CLIGreeter")
+        .env("LANG", "de_DE.UTF-8")
+        .args(["--name", "Mary", "--age", "30", "--time", "20:00"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Guten Abend, schön, Sie kennenzulernen, Mary!",
+        ));
+}
+
+#[test]
+fn greeting_follows_the_time_of_day() {
+    let greet = |name: &str, time: &str| {
+        common::bin("CLIGreeter")
+            .env_remove("LANG")
+            .args(["--name", name, "--age", "30", "--time", time])
+            .assert()
+    };
+
+    greet("Mary", "05:00")
+        .success()
+        .stdout(predicate::str::contains(
+            "Good morning, nice to meet you Mary!",
+        ));
+    greet("Mary", "11:59")
+        .success()
+        .stdout(predicate::str::contains("Good morning"));
+    greet("Mary", "12:00")
+        .success()
+        .stdout(predicate::str::contains("Good afternoon"));
+    greet("Mary", "18:00")
+        .success()
+        .stdout(predicate::str::contains("Good evening"));
+    greet("Mary", "04:59")
+        .success()
+        .stdout(predicate::str::contains("Good evening"));
+    greet("mary", "07:00")
+        .success()
+        .stdout(predicate::str::contains(
+            "Too early for this, but I guess we have to say hi.",
+        ));
+    greet("mary", "23:00")
+        .success()
+        .stdout(predicate::str::contains(
+            "I was about to leave, but I guess we have to say hi.",
+        ));
+    greet("Mary", "25:00")
+        .code(2)
+        .stderr(predicate::str::contains("Invalid time '25:00'. Use HH:MM"));
+}
+
+#[test]
+fn manner_flag_overrides_detection() {
+    let greet = |manner: &str, name: &str| {
+        common::bin("CLIGreeter")
+            .env_remove("LANG")
+            .args([
+                "--manner", manner, "--name", name, "--age", "30", "--time", "10:00",
+            ])
+            .assert()
+            .success()
+    };
+
+    greet("formal", "smith").stdout(predicate::str::contains("Good day, Mr./Ms. smith."));
+    greet("casual", "Mary").stdout(predicate::str::contains("Hey Mary!"));
+    greet("polite", "mary").stdout(predicate::str::contains(
+        "Good morning, nice to meet you mary!",
+    ));
+    greet("rude", "Mary").stdout(predicate::str::contains("Oh, it's you Mary..."));
+
+    common::bin("CLIGreeter")
+        .args(["--manner", "grumpy", "--name", "Mary"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "possible values: polite, rude, formal, casual",
+        ));
+}
+
+#[test]
+fn real_names_are_greeted_politely() {
+    let greet = |name: &str| {
+        common::bin("CLIGreeter")
+            .env_remove("LANG")
+            .arg(format!("--name={name}"))
+            .args(["--age", "30", "--time", "15:00"])
+            .assert()
+            .success()
+    };
+
+    for name in [
+        "O'Brien",
+        "O\u{2019}Brien",
+        "Jean-Luc",
+        "José",
+        "Zoë Saldaña",
+        "Mary-Kate O'Neil",
+    ] {
+        greet(name).stdout(predicate::str::contains(format!(
+            "Good afternoon, nice to meet you {name}!"
+        )));
+    }
+    for name in [
+        "o'Brien",
+        "Jean-luc",
+        "JosÉ",
+        "-Jean",
+        "Jean-",
+        "O'' Brien",
+        "Jean- Luc",
+        "R2D2",
+    ] {
+        greet(name).stdout(predicate::str::contains(format!("Oh, it's you {name}...")));
+    }
+}
+
+#[test]
+fn from_file_greets_every_name() {
+    let dir = tempfile::tempdir().unwrap();
+    let roster = dir.path().join("roster.txt");
+    std::fs::write(&roster, "Mary\n\n  john  \nJean-Luc\n").unwrap();
+
+    let greet = |extra: &[&str]| {
+        common::bin("CLIGreeter")
+            .env_remove("LANG")
+            .arg("--from-file")
+            .arg(&roster)
+            .args(["--time", "09:00"])
+            .args(extra)
+            .assert()
+    };
+
+    greet(&[]).success().stdout(
+        "\nGood morning, nice to meet you Mary!\n\
+             \nOh, it's you john...,\n---\nToo early for this, but I guess we have to say hi.\n\
+             \nGood morning, nice to meet you Jean-Luc!\n",
+    );
+
+    let output = dir.path().join("greetings.txt");
+    greet(&["--manner", "casual", "--output", output.to_str().unwrap()])
+        .success()
+        .stdout(predicate::str::contains("Wrote 3 greeting(s) to"));
+    assert_eq!(
+        std::fs::read_to_string(&output).unwrap(),
+        "\nHey Mary!\n\nHey john!\n\nHey Jean-Luc!\n"
+    );
+
+    greet(&["--name", "Mary"]).code(2);
+    common::bin("CLIGreeter")
+        .args(["--from-file", "missing.txt"])
+        .current_dir(dir.path())
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("Could not read missing.txt"));
+}
+
+#[test]
+fn json_output_describes_the_greeting() {
+    let output = common::bin("CLIGreeter")
+        .env_remove("LANG")
+        .args(["--name", "Mary", "--age", "30", "--time", "14:00", "--json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let result: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        result,
+        serde_json::json!({
+            "name": "Mary",
+            "manner": "Polite",
+            "greeting": "Good afternoon, nice to meet you Mary!",
+            "age": 30,
+            "age_valid": true,
+        })
+    );
+
+    // Questions go to stderr so stdout stays valid JSON
+    let output = common::bin("CLIGreeter")
+        .env_remove("LANG")
+        .args(["--time", "14:00", "--json"])
+        .write_stdin("bob\nold\n")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let result: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(result["manner"], "Rude");
+    assert_eq!(result["age"], serde_json::Value::Null);
+    assert_eq!(result["age_valid"], false);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("What is your age?"));
+}
@@ -0,0 +1,719 @@
+mod common;
+
+use std::path::Path;
+
+use predicates::prelude::*;
+use tempfile::tempdir;
+
+/// The game, playing in `dir` so high scores don't leak between tests
+fn game(dir: &Path) -> assert_cmd::Command {
+    let mut cmd = common::bin("guess_game");
+    cmd.current_dir(dir);
+    cmd
+}
+
+#[test]
+fn guessing_every_number_wins() {
+    let dir = tempdir().unwrap();
+    let guesses: String = (1..=100).map(|n| format!("{}\n", n)).collect();
+
+    game(dir.path())
+        .write_stdin(guesses)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("You guessed it!"));
+}
+
+#[test]
+fn range_can_be_configured() {
+    let dir = tempdir().unwrap();
+    let guesses: String = (10..=20).map(|n| format!("{}\n", n)).collect();
+
+    game(dir.path())
+        .args(["--min", "10", "--max", "20"])
+        .write_stdin(guesses)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Guess the number between 10 and 20!",
+        ))
+        .stdout(predicate::str::contains("You guessed it!"));
+
+    game(dir.path())
+        .args(["--min", "5", "--max", "5"])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "--min (5) must be less than --max (5)",
+        ));
+}
+
+#[test]
+fn limited_attempts_reveal_the_number_when_exhausted() {
+    let dir = tempdir().unwrap();
+    // 0 is below any secret, so both guesses miss
+    let output = game(dir.path())
+        .args(["--difficulty", "hard", "--attempts", "2"])
+        .write_stdin("0\n0\n")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        stdout.contains("Guess the number between 1 and 1000!"),
+        "{stdout}"
+    );
+    assert!(stdout.contains("2 guesses left."), "{stdout}");
+    assert!(stdout.contains("1 guess left."), "{stdout}");
+    assert!(
+        stdout.contains("Out of guesses! The number was"),
+        "{stdout}"
+    );
+
+    // Ten guesses are plenty for a range of five
+    let guesses: String = (1..=100).map(|n| format!("{}\n", n)).collect();
+    game(dir.path())
+        .args(["--difficulty", "easy", "--max", "5"])
+        .write_stdin(guesses)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Guess the number between 1 and 5!",
+        ))
+        .stdout(predicate::str::contains("10 guesses left."))
+        .stdout(predicate::str::contains("You guessed it!"));
+}
+
+#[test]
+fn invalid_guesses_are_asked_again() {
+    let dir = tempdir().unwrap();
+    game(dir.path())
+        .args(["--max", "2", "--attempts", "2"])
+        .write_stdin("abc\n-5\n\n1\n2\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Please type a number!").count(3))
+        .stdout(predicate::str::contains("You guessed it!"));
+
+    // Running out of input ends the game instead of spinning
+    game(dir.path())
+        .write_stdin("oops\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No more input. The number was"));
+}
+
+#[test]
+fn high_scores_are_kept_per_range() {
+    let dir = tempdir().unwrap();
+    // Counting up from the bottom wins in at most as many guesses as the range is wide
+    let count_up = |max: u32| (1..=max).map(|n| format!("{}\n", n)).collect::<String>();
+
+    game(dir.path())
+        .args(["--max", "3"])
+        .write_stdin(count_up(3))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No high score for 1 to 3 yet."))
+        .stdout(predicate::str::contains("New high score:"));
+    let scores: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(dir.path().join("highscores.json")).unwrap())
+            .unwrap();
+    let best = scores["best"]["1-3"].as_u64().unwrap();
+    assert!((1..=3).contains(&best));
+
+    // Guessing 0 first always costs an extra guess, so the record can't be beaten
+    game(dir.path())
+        .args(["--max", "3"])
+        .write_stdin(format!("0\n0\n0\n{}", count_up(3)))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "High score for 1 to 3: {best} guesses"
+        )))
+        .stdout(predicate::str::contains("New high score:").not());
+
+    // A limited number of attempts keeps its own record
+    game(dir.path())
+        .args(["--max", "3", "--attempts", "5"])
+        .write_stdin(count_up(3))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No high score for 1 to 3 yet."))
+        .stdout(predicate::str::contains("New high score:"));
+    let scores: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(dir.path().join("highscores.json")).unwrap())
+            .unwrap();
+    assert_eq!(scores["best"]["1-3"], best);
+    assert!(scores["best"]["1-3/5"].is_u64());
+
+    std::fs::write(dir.path().join("highscores.json"), "{ not json").unwrap();
+    game(dir.path())
+        .args(["--max", "3"])
+        .write_stdin(count_up(3))
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Ignoring corrupt highscores.json"))
+        .stdout(predicate::str::contains("New high score:"));
+}
+
+#[test]
+fn play_again_starts_a_new_round() {
+    let dir = tempdir().unwrap();
+    // A single guess of 0 always loses, so every round takes exactly one line
+    let round = "0\n";
+
+    game(dir.path())
+        .args(["--max", "2", "--attempts", "1"])
+        .write_stdin(format!("{round}y\n{round}YES\n{round}n\n{round}"))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Guess the number between 1 and 2!").count(3))
+        .stdout(predicate::str::contains("Play again? (y/n)").count(3));
+
+    game(dir.path())
+        .args(["--max", "2", "--attempts", "1"])
+        .write_stdin(format!("{round}maybe\n{round}"))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Guess the number between 1 and 2!").count(1));
+}
+
+#[test]
+fn reverse_mode_bisects_and_catches_cheats() {
+    let dir = tempdir().unwrap();
+
+    // Thinking of 70 in 1..=100: 50 -> h, 75 -> l, 62 -> h, 68 -> h, 71 -> l, 69 -> h, 70 -> c
+    game(dir.path())
+        .arg("--reverse")
+        .write_stdin("h\nl\nmaybe\nh\nh\nl\nh\nc\nn\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Is it 50?"))
+        .stdout(predicate::str::contains("Is it 75?"))
+        .stdout(predicate::str::contains("Please answer h, l or c."))
+        .stdout(predicate::str::contains("Is it 70?"))
+        .stdout(predicate::str::contains("Got it in 7 guesses!"));
+
+    game(dir.path())
+        .args(["--reverse", "--max", "10"])
+        .write_stdin("h\nl\nh\nh\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Cheater!"));
+}
+
+#[test]
+fn seed_makes_the_game_reproducible() {
+    let dir = tempdir().unwrap();
+    let guesses: String = (1..=100).map(|n| format!("{}\n", n)).collect();
+    let secret = |seed: &str| {
+        let output = game(dir.path())
+            .args(["--seed", seed])
+            .write_stdin(guesses.clone())
+            .output()
+            .unwrap();
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let (_, rest) = stdout
+            .split_once("You guessed it! The number was ")
+            .unwrap();
+        rest.split('.').next().unwrap().parse::<u32>().unwrap()
+    };
+
+    let first = secret("42");
+    assert_eq!(secret("42"), first);
+
+    // Knowing the secret, the whole game plays out the same way every time
+    game(dir.path())
+        .args(["--seed", "42"])
+        .write_stdin(format!("0\n101\n{first}\nn\n"))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Too small!\n"))
+        .stdout(predicate::str::contains("Too big!\n"))
+        .stdout(predicate::str::contains(format!(
+            "You guessed it! The number was {first}."
+        )));
+}
+
+#[test]
+fn guess_history_is_listed_after_each_round() {
+    let dir = tempdir().unwrap();
+    game(dir.path())
+        .args(["--max", "2", "--attempts", "2"])
+        .write_stdin("0\nx\n0\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Your 2 guesses: 0 (too small), 0 (too small)\n",
+        ));
+
+    // Winning lists the correct guess last
+    game(dir.path())
+        .args(["--min", "7", "--max", "8"])
+        .write_stdin("9\n7\n8\n")
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::is_match(
+                r"Your (2 guesses: 9 \(too big\), 7 \(correct\)|3 guesses: 9 \(too big\), 7 \(too small\), 8 \(correct\))\n",
+            )
+            .unwrap(),
+        );
+}
+
+#[test]
+fn tournament_ends_with_a_scoreboard() {
+    let dir = tempdir().unwrap();
+    // Losing every round with a single miss reveals the seeded secrets
+    let output = game(dir.path())
+        .args(["--seed", "7", "--tournament", "3", "--attempts", "1"])
+        .write_stdin("0\n0\n0\n")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Round 3 of 3"), "{stdout}");
+    assert!(
+        stdout.contains("  Round 2: lost after 1 guess\n"),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("Won 0 of 3 rounds with 3 guesses in total."),
+        "{stdout}"
+    );
+    assert!(stdout.contains("No round was won."), "{stdout}");
+    assert!(stdout.contains("Nobody won the tournament."), "{stdout}");
+    let secrets: Vec<&str> = stdout
+        .split("Out of guesses! The number was ")
+        .skip(1)
+        .map(|rest| rest.split('.').next().unwrap())
+        .collect();
+    assert_eq!(secrets.len(), 3);
+
+    // Guessing the seeded secrets wins every round at the first try
+    game(dir.path())
+        .args(["--seed", "7", "--tournament", "3", "--attempts", "1"])
+        .write_stdin(
+            secrets
+                .iter()
+                .map(|secret| format!("{secret}\n"))
+                .collect::<String>(),
+        )
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("You guessed it!").count(3))
+        .stdout(predicate::str::contains(
+            "Won 3 of 3 rounds with 3 guesses in total.",
+        ))
+        .stdout(predicate::str::contains("Best round: round 1 in 1 guess"))
+        .stdout(predicate::str::contains("You won the tournament!"))
+        .stdout(predicate::str::contains("Play again?").not());
+}
+
+#[test]
+fn hints_compare_each_guess_with_the_previous_one() {
+    let dir = tempdir().unwrap();
+    // Secrets are 1 to 100, so 300 is always further away than 0 and 150 always closer than 300
+    game(dir.path())
+        .args(["--hints", "--attempts", "4"])
+        .write_stdin("0\n300\n150\n150\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Too small!\n"))
+        .stdout(predicate::str::contains("Too big! Colder.\n"))
+        .stdout(predicate::str::contains("Too big! Warmer.\n"))
+        .stdout(predicate::str::contains(
+            "Too big! Same distance as before.\n",
+        ));
+
+    game(dir.path())
+        .args(["--attempts", "2"])
+        .write_stdin("0\n200\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Colder").not());
+}
+
+#[test]
+fn coach_draws_the_remaining_range() {
+    let dir = tempdir().unwrap();
+    // With 60 numbers on a 60-character line, position i shows number i + 1
+    let expected = |lo: u32, hi: u32, guess: u32| {
+        let mid = lo + (hi - lo) / 2;
+        let line: String = (1..=60)
+            .map(|n| match n {
+                n if n == guess.clamp(1, 60) => '*',
+                n if n == mid => '?',
+                n if n < lo => '<',
+                n if n > hi => '>',
+                _ => '-',
+            })
+            .collect();
+        format!("[{line}]\n")
+    };
+
+    let output = game(dir.path())
+        .args([
+            "--min",
+            "1",
+            "--max",
+            "60",
+            "--seed",
+            "3",
+            "--attempts",
+            "1",
+        ])
+        .write_stdin("0\n")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let (_, rest) = stdout.split_once("The number was ").unwrap();
+    let secret: u32 = rest.split('.').next().unwrap().parse().unwrap();
+    assert!(
+        (3..=58).contains(&secret),
+        "pick a seed with a secret away from the edges, got {secret}"
+    );
+
+    game(dir.path())
+        .args(["--min", "1", "--max", "60", "--seed", "3", "--coach"])
+        .write_stdin(format!("0\n{}\n{}\n{secret}\n", secret - 2, secret + 2))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Coach: it's between 1 and 60, the best next guess is 30.",
+        ))
+        .stdout(predicate::str::contains(expected(1, 60, 0)))
+        .stdout(predicate::str::contains(expected(
+            secret - 1,
+            60,
+            secret - 2,
+        )))
+        .stdout(predicate::str::contains(expected(
+            secret - 1,
+            secret + 1,
+            secret + 2,
+        )))
+        .stdout(predicate::str::contains("[").count(3));
+
+    // On a wider range each character covers several numbers; guesses past the end sit at the edge
+    game(dir.path())
+        .args(["--max", "1000", "--coach", "--attempts", "1"])
+        .write_stdin("1001\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "[{}?{}*]\n",
+            "-".repeat(29),
+            "-".repeat(29)
+        )));
+}
+
+#[test]
+fn quit_abandons_the_game() {
+    let dir = tempdir().unwrap();
+    game(dir.path())
+        .write_stdin("0\nabc\nQuit\ny\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Please type a number!"))
+        .stdout(predicate::str::is_match(r"The number was \d+\. Goodbye!\n").unwrap())
+        .stdout(predicate::str::contains("Your 1 guess: 0 (too small)"))
+        .stdout(predicate::str::contains("Play again?").not());
+
+    game(dir.path())
+        .args(["--tournament", "3"])
+        .write_stdin("q\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Round 2 of 3").not())
+        .stdout(predicate::str::contains(
+            "Won 0 of 1 rounds with 0 guesses in total.",
+        ))
+        .stdout(predicate::str::contains("Nobody won the tournament."));
+}
+
+#[test]
+fn wins_are_compared_with_the_optimal_number_of_guesses() {
+    let dir = tempdir().unwrap();
+    let count_up = |max: u32| (1..=max).map(|n| format!("{}\n", n)).collect::<String>();
+
+    // ceil(log2(range size)) for a few sizes
+    for (max, optimal) in [(2, 1), (100, 7), (128, 7), (129, 8), (1000, 10)] {
+        game(dir.path())
+            .args(["--max", &max.to_string()])
+            .write_stdin(count_up(max))
+            .assert()
+            .success()
+            .stdout(
+                predicate::str::is_match(format!(
+                    r"You used \d+ guess(es)? vs optimal {optimal} \(efficiency: "
+                ))
+                .unwrap(),
+            );
+    }
+    let stats: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(dir.path().join("game_stats.json")).unwrap())
+            .unwrap();
+    assert_eq!(stats["efficiency"].as_array().unwrap().len(), 5);
+
+    // Missing with 0 first always takes two guesses or more, one more than optimal for 1 to 2
+    game(dir.path())
+        .args(["--max", "2", "--require-optimal"])
+        .write_stdin("0\n1\n2\n")
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("optimal 1 (efficiency: 50.0%)")
+                .or(predicate::str::contains("(efficiency: 33.3%)")),
+        )
+        .stdout(predicate::str::contains(
+            "That's more than optimal, so it counts as a loss.",
+        ))
+        .stdout(predicate::str::contains("Average efficiency over 6 games:"))
+        .stdout(predicate::str::contains("New high score").not());
+}
+
+#[test]
+fn wins_are_scored_by_guesses_and_time() {
+    let dir = tempdir().unwrap();
+    let output = game(dir.path())
+        .args([
+            "--min",
+            "1",
+            "--max",
+            "60",
+            "--seed",
+            "3",
+            "--attempts",
+            "1",
+        ])
+        .write_stdin("0\n")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let (_, rest) = stdout.split_once("The number was ").unwrap();
+    let secret = rest.split('.').next().unwrap();
+
+    // Answering at once takes well under a second, so only the guesses cost points
+    game(dir.path())
+        .args(["--min", "1", "--max", "60", "--seed", "3"])
+        .write_stdin(format!("0\n{secret}\nn\n"))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Score: 900\n"))
+        .stdout(predicate::str::contains("New best score: 900!"));
+
+    game(dir.path())
+        .args([
+            "--min",
+            "1",
+            "--max",
+            "60",
+            "--seed",
+            "3",
+            "--guess-penalty",
+            "400",
+        ])
+        .write_stdin(format!("0\n0\n0\n{secret}\nn\n"))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Best score for 1 to 60: 900"))
+        .stdout(predicate::str::contains("Score: 0\n"))
+        .stdout(predicate::str::contains("New best score").not());
+
+    let scores: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(dir.path().join("highscores.json")).unwrap())
+            .unwrap();
+    assert_eq!(scores["best_score"]["1-60"], 900);
+}
+
+#[test]
+fn float_guesses_win_within_the_tolerance() {
+    let dir = tempdir().unwrap();
+    let output = game(dir.path())
+        .args(["--float", "--seed", "3", "--attempts", "1"])
+        .write_stdin("-1\n")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        stdout.contains("Guess the number between 0 and 1, to within 0.01!"),
+        "{stdout}"
+    );
+    assert!(stdout.contains("Too small! Off by"), "{stdout}");
+    let (_, rest) = stdout.split_once("The number was ").unwrap();
+    let secret: f64 = rest
+        .lines()
+        .next()
+        .unwrap()
+        .trim_end_matches('.')
+        .parse()
+        .unwrap();
+    assert!((0.0..=1.0).contains(&secret));
+
+    // Just outside the default tolerance of 0.01 on either side is close, just inside wins
+    game(dir.path())
+        .args(["--float", "--seed", "3"])
+        .write_stdin(format!(
+            "{}\n{}\n{}\nn\n",
+            secret + 0.0101,
+            secret - 0.0101,
+            secret + 0.0099
+        ))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Too big! Close! Off by 1.0% of the range.",
+        ))
+        .stdout(predicate::str::contains(
+            "Too small! Close! Off by 1.0% of the range.",
+        ))
+        .stdout(predicate::str::contains("You got it in 3 guesses!"));
+
+    game(dir.path())
+        .args([
+            "--float",
+            "--seed",
+            "3",
+            "--tolerance",
+            "0.5",
+            "--max",
+            "10",
+        ])
+        .write_stdin("nan\n100\nq\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Please type a number!"))
+        .stdout(predicate::str::contains("Too big!"))
+        .stdout(predicate::str::contains("Goodbye!"));
+
+    game(dir.path())
+        .args(["--tolerance", "0.1"])
+        .assert()
+        .failure();
+    game(dir.path())
+        .args(["--float", "--tolerance", "-1"])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn float_bounds_can_be_fractions() {
+    let dir = tempdir().unwrap();
+    for (min, max) in [("0.5", "2.5"), ("-1", "-0.25")] {
+        let output = game(dir.path())
+            .args(["--float", "--min", min, "--max", max, "--attempts", "1"])
+            .write_stdin("1000\n")
+            .output()
+            .unwrap();
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert!(
+            stdout.contains(&format!(
+                "Guess the number between {min} and {max}, to within 0.01!"
+            )),
+            "{stdout}"
+        );
+        let (_, rest) = stdout.split_once("The number was ").unwrap();
+        let secret: f64 = rest
+            .lines()
+            .next()
+            .unwrap()
+            .trim_end_matches('.')
+            .parse()
+            .unwrap();
+        assert!(
+            (min.parse::<f64>().unwrap()..=max.parse().unwrap()).contains(&secret),
+            "{secret}"
+        );
+    }
+
+    game(dir.path())
+        .args(["--min", "0.5", "--max", "10"])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "--min (0.5) must be a whole number from 0 to 4294967295 unless --float is set",
+        ));
+    game(dir.path())
+        .args(["--max", "-3"])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "--max (-3) must be a whole number",
+        ));
+}
+
+#[test]
+fn feedback_is_colored_on_request() {
+    let dir = tempdir().unwrap();
+    game(dir.path())
+        .args(["--max", "2"])
+        .env("CLICOLOR_FORCE", "1")
+        .write_stdin("0\n3\n1\n2\nn\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\u{1b}[34mToo small!\u{1b}[0m\n"))
+        .stdout(predicate::str::contains("\u{1b}[31mToo big!\u{1b}[0m\n"))
+        .stdout(predicate::str::contains(
+            "\u{1b}[32mYou guessed it! The number was",
+        ));
+
+    // Piped output and NO_COLOR stay plain
+    for no_color in [None, Some("1")] {
+        let mut cmd = game(dir.path());
+        if let Some(value) = no_color {
+            cmd.env("NO_COLOR", value);
+        }
+        cmd.args(["--max", "2"])
+            .write_stdin("0\n3\n1\n2\nn\n")
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("\u{1b}[").not());
+    }
+}
+
+#[test]
+fn guesses_compare_correctly_at_the_edges_of_u32() {
+    let dir = tempdir().unwrap();
+    let max = u32::MAX.to_string();
+
+    // 0 is below and u32::MAX above every secret from 1 to 100
+    game(dir.path())
+        .args(["--attempts", "2"])
+        .write_stdin(format!("0\n{max}\n"))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Too small!\n"))
+        .stdout(predicate::str::contains("Too big!\n"));
+
+    // With two possible secrets, a single guess at either edge is either correct or
+    // points towards the other one
+    let below_max = (u32::MAX - 1).to_string();
+    let edges = [
+        (
+            "0",
+            "1",
+            "0",
+            "Too small!\nOut of guesses! The number was 1.".to_string(),
+        ),
+        (
+            &below_max,
+            &max,
+            &max,
+            format!("Too big!\nOut of guesses! The number was {below_max}."),
+        ),
+    ];
+    for (min, range_max, guess, miss) in edges {
+        let output = game(dir.path())
+            .args(["--min", min, "--max", range_max, "--attempts", "1"])
+            .write_stdin(format!("{guess}\nn\n"))
+            .output()
+            .unwrap();
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let correct = format!("You guessed it! The number was {guess}.");
+        assert!(
+            stdout.contains(&correct) != stdout.contains(&miss),
+            "{stdout}"
+        );
+    }
+}
@@ -0,0 +1,1116 @@
+mod common;
+
+use predicates::prelude::*;
+use tempfile::tempdir;
+
+#[test]
+fn write_then_read_round_trip() {
+    let dir = tempdir().unwrap();
+
+    common::bin("logger")
+        .current_dir(dir.path())
+        .args(["write", "--level", "warn", "disk almost full"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("WARN log written."));
+
+    common::bin("logger")
+        .current_dir(dir.path())
+        .arg("read")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("[WARN] disk almost full"));
+}
+
+#[test]
+fn read_filters_by_level() {
+    let dir = tempdir().unwrap();
+
+    for (level, message) in [("info", "service started"), ("error", "connection lost")] {
+        common::bin("logger")
+            .current_dir(dir.path())
+            .args(["write", "--level", level, message])
+            .assert()
+            .success();
+    }
+
+    common::bin("logger")
+        .current_dir(dir.path())
+        .args(["read", "--level", "error"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("connection lost"))
+        .stdout(predicate::str::contains("service started").not());
+}
+
+#[test]
+fn write_stdin_logs_one_entry_per_line() {
+    let dir = tempdir().unwrap();
+
+    common::bin("logger")
+        .current_dir(dir.path())
+        .args(["write", "--stdin"])
+        .write_stdin("first\nsecond\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::diff(
+            "INFO log written.\nINFO log written.\n",
+        ));
+    // Without --stdin a lone dash is just a message
+    common::bin("logger")
+        .current_dir(dir.path())
+        .args(["write", "-"])
+        .assert()
+        .success();
+
+    common::bin("logger")
+        .current_dir(dir.path())
+        .arg("read")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("[INFO] first"))
+        .stdout(predicate::str::contains("[INFO] second"))
+        .stdout(predicate::str::contains("[INFO] -"));
+
+    common::bin("logger")
+        .current_dir(dir.path())
+        .args(["write", "--stdin", "extra"])
+        .assert()
+        .code(2);
+    common::bin("logger")
+        .current_dir(dir.path())
+        .arg("write")
+        .assert()
+        .code(2);
+}
+
+#[test]
+fn write_rejects_unknown_level() {
+    let dir = tempdir().unwrap();
+
+    common::bin("logger")
+        .current_dir(dir.path())
+        .args(["write", "--level", "loud", "hello"])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("Invalid log level: loud"));
+}
+
+#[test]
+fn search_shows_context_and_merges_windows() {
+    let dir = tempdir().unwrap();
+
+    let messages = [
+        ("info", "boot"),
+        ("info", "step one"),
+        ("error", "timeout A"),
+        ("info", "step two"),
+        ("error", "timeout B"),
+        ("info", "step three"),
+        ("info", "step four"),
+        ("info", "step five"),
+        ("warn", "timeout C"),
+        ("info", "shutdown"),
+    ];
+    for (level, message) in messages {
+        common::bin("logger")
+            .current_dir(dir.path())
+            .args(["write", "--level", level, message])
+            .assert()
+            .success();
+    }
+
+    let output = common::bin("logger")
+        .current_dir(dir.path())
+        .args([
+            "read", "--search", "timeout", "--level", "error", "--before", "1", "--after", "1",
+        ])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    // The windows around A and B overlap and are printed once; C is filtered out by level
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let messages: Vec<&str> = stdout
+        .lines()
+        .map(|line| line.rsplit("] ").next().unwrap())
+        .collect();
+    assert_eq!(
+        messages,
+        vec![
+            "step one",
+            "timeout A",
+            "step two",
+            "timeout B",
+            "step three"
+        ]
+    );
+
+    let output = common::bin("logger")
+        .current_dir(dir.path())
+        .args(["read", "--search", "timeout", "-B", "0", "-A", "1"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let messages: Vec<&str> = stdout
+        .lines()
+        .map(|line| line.rsplit("] ").next().unwrap())
+        .collect();
+    assert_eq!(
+        messages,
+        vec![
+            "timeout A",
+            "step two",
+            "timeout B",
+            "step three",
+            "--",
+            "timeout C",
+            "shutdown"
+        ]
+    );
+}
+
+#[test]
+fn validate_reports_and_fixes_corrupt_lines() {
+    let dir = tempdir().unwrap();
+
+    for message in ["first", "second"] {
+        common::bin("logger")
+            .current_dir(dir.path())
+            .args(["write", message])
+            .assert()
+            .success();
+    }
+    let log_path = dir.path().join("log.json");
+    let mut contents = std::fs::read_to_string(&log_path).unwrap();
+    contents.insert_str(contents.find('\n').unwrap() + 1, "{not valid json\n");
+    std::fs::write(&log_path, contents).unwrap();
+
+    common::bin("logger")
+        .current_dir(dir.path())
+        .arg("stats")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Total logs: 2"))
+        .stderr(predicate::str::contains("Skipped 1 corrupt log line(s)"));
+
+    common::bin("logger")
+        .current_dir(dir.path())
+        .arg("validate")
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains("line 2:"))
+        .stdout(predicate::str::contains("{not valid json"))
+        .stdout(predicate::str::contains(
+            "2 valid entries, 1 corrupt entries",
+        ));
+
+    common::bin("logger")
+        .current_dir(dir.path())
+        .args(["validate", "--fix"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Removed 1 corrupt line(s)"));
+
+    common::bin("logger")
+        .current_dir(dir.path())
+        .arg("validate")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "2 valid entries, 0 corrupt entries",
+        ));
+}
+
+#[test]
+fn read_filters_by_trace_id() {
+    let dir = tempdir().unwrap();
+    let trace = "4bf92f3577b34da6a3ce929d0e0e4736";
+    let other_trace = "00f067aa0ba902b700f067aa0ba902b7";
+
+    let writes: [&[&str]; 3] = [
+        &[
+            "write",
+            "checkout started",
+            "--trace-id",
+            trace,
+            "--span-id",
+            "00f067aa0ba902b7",
+        ],
+        &["write", "unrelated request", "--trace-id", other_trace],
+        &["write", "no trace here"],
+    ];
+    for args in writes {
+        common::bin("logger")
+            .current_dir(dir.path())
+            .args(args)
+            .assert()
+            .success();
+    }
+
+    common::bin("logger")
+        .current_dir(dir.path())
+        .args(["read", "--trace-id", trace])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "checkout started trace={trace} span=00f067aa0ba902b7"
+        )))
+        .stdout(predicate::str::contains("unrelated request").not())
+        .stdout(predicate::str::contains("no trace here").not());
+
+    common::bin("logger")
+        .current_dir(dir.path())
+        .arg("read")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("no trace here\n"));
+
+    common::bin("logger")
+        .current_dir(dir.path())
+        .args(["write", "bad", "--trace-id", "4BF92F"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "trace ID must be 32 lowercase hex characters",
+        ));
+}
+
+/// Accepts a single HTTP request, answers 200 and hands back the raw request text
+fn mock_webhook_server() -> (String, std::thread::JoinHandle<String>) {
+    use std::io::{Read, Write};
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/hook", listener.local_addr().unwrap());
+    let handle = std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut request = Vec::new();
+        let mut buf = [0; 4096];
+        loop {
+            let n = stream.read(&mut buf).unwrap();
+            request.extend_from_slice(&buf[..n]);
+            let text = String::from_utf8_lossy(&request);
+            if let Some(header_end) = text.find("\r\n\r\n") {
+                let length = text[..header_end]
+                    .lines()
+                    .find_map(|line| {
+                        let line = line.to_lowercase();
+                        line.strip_prefix("content-length:")
+                            .map(|v| v.trim().parse::<usize>().unwrap())
+                    })
+                    .unwrap_or(0);
+                if request.len() >= header_end + 4 + length {
+                    break;
+                }
+            }
+        }
+        stream
+            .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+            .unwrap();
+        String::from_utf8(request).unwrap()
+    });
+    (url, handle)
+}
+
+#[test]
+fn error_entries_trigger_webhook() {
+    let dir = tempdir().unwrap();
+    let (url, server) = mock_webhook_server();
+
+    common::bin("logger")
+        .current_dir(dir.path())
+        .env("LOGGER_WEBHOOK_URL", &url)
+        .args(["write", "--level", "error", "payment failed"])
+        .assert()
+        .success()
+        .stderr(predicate::str::is_empty());
+
+    let request = server.join().unwrap();
+    assert!(request.starts_with("POST /hook "));
+    let body: serde_json::Value =
+        serde_json::from_str(request.split("\r\n\r\n").nth(1).unwrap()).unwrap();
+    assert_eq!(body["level"], "error");
+    assert_eq!(body["message"], "payment failed");
+    assert!(
+        body["timestamp"]
+            .as_str()
+            .unwrap()
+            .parse::<chrono::DateTime<chrono::Utc>>()
+            .is_ok()
+    );
+}
+
+#[test]
+fn failed_webhook_only_warns() {
+    let dir = tempdir().unwrap();
+    // Bind and drop a listener to get a port nothing is listening on
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+
+    common::bin("logger")
+        .current_dir(dir.path())
+        .args(["--webhook-url", &format!("http://127.0.0.1:{port}/hook")])
+        .args(["write", "--level", "error", "disk failure"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("ERROR log written."))
+        .stderr(predicate::str::contains(
+            "Warning: webhook notification failed",
+        ));
+
+    common::bin("logger")
+        .current_dir(dir.path())
+        .args(["--webhook-url", &format!("http://127.0.0.1:{port}/hook")])
+        .args(["write", "--level", "info", "all good"])
+        .assert()
+        .success()
+        .stderr(predicate::str::is_empty());
+}
+
+#[test]
+fn read_filter_expressions() {
+    let dir = tempdir().unwrap();
+
+    for (level, message) in [
+        ("info", "user login ok"),
+        ("warn", "database slow"),
+        ("error", "database timeout"),
+        ("debug", "auth token refreshed"),
+        ("error", "payment declined"),
+    ] {
+        common::bin("logger")
+            .current_dir(dir.path())
+            .args(["write", "--level", level, message])
+            .assert()
+            .success();
+    }
+
+    let filtered = |expression: &str| {
+        let output = common::bin("logger")
+            .current_dir(dir.path())
+            .args(["read", "--filter", expression])
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{expression}");
+        String::from_utf8(output.stdout)
+            .unwrap()
+            .lines()
+            .map(|line| line.rsplit("] ").next().unwrap().to_string())
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        filtered("level:error"),
+        vec!["database timeout", "payment declined"]
+    );
+    assert_eq!(filtered("message:TIMEOUT"), vec!["database timeout"]);
+    assert_eq!(
+        filtered("level:warn AND message:database"),
+        vec!["database slow"]
+    );
+    assert_eq!(
+        filtered("message:auth OR message:login"),
+        vec!["user login ok", "auth token refreshed"]
+    );
+    assert_eq!(
+        filtered("NOT level:debug AND NOT level:error"),
+        vec!["user login ok", "database slow"]
+    );
+    // AND binds tighter than OR; parentheses override it
+    assert_eq!(
+        filtered("level:info OR level:error and message:payment"),
+        vec!["user login ok", "payment declined"]
+    );
+    assert_eq!(
+        filtered("(level:info OR level:error) AND message:\"database timeout\""),
+        vec!["database timeout"]
+    );
+    let today = chrono::Utc::now().format("%Y-%m-%d").to_string();
+    assert_eq!(
+        filtered(&format!("timestamp:{today} AND level:debug")),
+        vec!["auth token refreshed"]
+    );
+    assert!(filtered("timestamp:1999").is_empty());
+
+    for (expression, error) in [
+        ("level:loud", "unknown level 'loud'"),
+        ("host:web1", "unknown field 'host'"),
+        ("level:error AND", "expected a condition"),
+        ("(level:error", "missing ')'"),
+        ("message:\"open", "unterminated string literal"),
+    ] {
+        common::bin("logger")
+            .current_dir(dir.path())
+            .args(["read", "--filter", expression])
+            .assert()
+            .code(2)
+            .stderr(predicate::str::contains(error));
+    }
+}
+
+#[test]
+fn sampled_writes_keep_about_one_in_n() {
+    let dir = tempdir().unwrap();
+
+    let ticks: String = (0..10_000).map(|i| format!("tick {i}\n")).collect();
+    let sample = || {
+        common::bin("logger")
+            .current_dir(dir.path())
+            .args(["write", "--level", "debug", "--stdin"])
+            .args(["--sample-rate", "100", "--sample-seed", "7"])
+            .write_stdin(ticks.clone())
+            .assert()
+            .success();
+        std::fs::read_to_string(dir.path().join("log.json")).unwrap()
+    };
+    let stats = || -> serde_json::Value {
+        serde_json::from_str(
+            &std::fs::read_to_string(dir.path().join("log_sampling.json")).unwrap(),
+        )
+        .unwrap()
+    };
+
+    let written = sample().lines().count();
+    assert!((70..=130).contains(&written), "{written} entries written");
+    assert_eq!(stats()["seen"], 10_000);
+    assert_eq!(stats()["skipped"], 10_000 - written as u64);
+
+    // The same seed keeps the same ticks, even with totals from an earlier run around
+    let kept = |log: &str| -> Vec<String> {
+        log.lines()
+            .map(|line| {
+                serde_json::from_str::<serde_json::Value>(line).unwrap()["message"].to_string()
+            })
+            .collect()
+    };
+    let both_runs = kept(&sample());
+    assert_eq!(both_runs[..written], both_runs[written..]);
+    assert_eq!(stats()["seen"], 20_000);
+    assert_eq!(stats()["skipped"], 2 * (10_000 - written as u64));
+
+    common::bin("logger")
+        .current_dir(dir.path())
+        .args(["write", "--sample-rate", "0", "never"])
+        .assert()
+        .code(2);
+    common::bin("logger")
+        .current_dir(dir.path())
+        .args(["write", "--sample-seed", "7", "unsampled"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("--sample-rate <N>"));
+}
+
+#[test]
+fn split_by_level_writes_one_file_per_level() {
+    let dir = tempdir().unwrap();
+
+    for (level, message) in [
+        ("info", "booted"),
+        ("error", "crashed"),
+        ("warn", "slow"),
+        ("error", "crashed again"),
+    ] {
+        common::bin("logger")
+            .current_dir(dir.path())
+            .args(["--split-by-level", "write", "--level", level, message])
+            .assert()
+            .success();
+    }
+
+    let messages_in = |file: &str| -> Vec<String> {
+        std::fs::read_to_string(dir.path().join(file))
+            .unwrap()
+            .lines()
+            .map(|line| {
+                serde_json::from_str::<serde_json::Value>(line).unwrap()["message"]
+                    .as_str()
+                    .unwrap()
+                    .to_string()
+            })
+            .collect()
+    };
+    assert_eq!(messages_in("log_info.json"), vec!["booted"]);
+    assert_eq!(
+        messages_in("log_error.json"),
+        vec!["crashed", "crashed again"]
+    );
+    assert_eq!(messages_in("log_warn.json"), vec!["slow"]);
+    assert!(!dir.path().join("log_debug.json").exists());
+    assert!(!dir.path().join("log.json").exists());
+
+    // A corrupt line in another level's file shows that --level reads only its own file
+    let mut info = std::fs::read_to_string(dir.path().join("log_info.json")).unwrap();
+    info.push_str("{broken\n");
+    std::fs::write(dir.path().join("log_info.json"), info).unwrap();
+    common::bin("logger")
+        .current_dir(dir.path())
+        .args(["read", "--split-by-level", "--level", "error"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("crashed\n"))
+        .stdout(predicate::str::contains("crashed again\n"))
+        .stdout(predicate::str::contains("booted").not())
+        .stderr(predicate::str::is_empty());
+
+    let output = common::bin("logger")
+        .current_dir(dir.path())
+        .args(["read", "--split-by-level"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let messages: Vec<&str> = stdout
+        .lines()
+        .map(|line| line.rsplit("] ").next().unwrap())
+        .collect();
+    assert_eq!(messages, vec!["booted", "crashed", "slow", "crashed again"]);
+
+    common::bin("logger")
+        .current_dir(dir.path())
+        .args(["stats", "--split-by-level"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Total logs: 4"))
+        .stdout(predicate::str::contains("ERROR: 2 (50.0%)"));
+}
+
+/// Metric values by name (with labels) from Prometheus text output
+fn parse_metrics(text: &str) -> std::collections::HashMap<String, f64> {
+    text.lines()
+        .filter(|line| !line.starts_with('#') && !line.is_empty())
+        .map(|line| {
+            let (name, value) = line.rsplit_once(' ').unwrap();
+            (name.to_string(), value.parse().unwrap())
+        })
+        .collect()
+}
+
+#[test]
+fn stats_exports_prometheus_metrics() {
+    let dir = tempdir().unwrap();
+
+    let empty = common::bin("logger")
+        .current_dir(dir.path())
+        .args(["stats", "--prometheus"])
+        .output()
+        .unwrap();
+    let metrics = parse_metrics(&String::from_utf8(empty.stdout).unwrap());
+    assert_eq!(metrics["logger_total_entries_total"], 0.0);
+    assert_eq!(metrics["logger_file_size_bytes"], 0.0);
+    assert!(!metrics.contains_key("logger_oldest_entry_age_seconds"));
+
+    for (level, message) in [("info", "booted"), ("error", "crashed"), ("info", "ready")] {
+        common::bin("logger")
+            .current_dir(dir.path())
+            .args(["write", "--level", level, message])
+            .assert()
+            .success();
+    }
+
+    let output = common::bin("logger")
+        .current_dir(dir.path())
+        .args(["stats", "--prometheus"])
+        .output()
+        .unwrap();
+    let text = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        text.contains("# TYPE logger_total_entries_total counter\n"),
+        "{text}"
+    );
+    assert!(
+        text.contains("# TYPE logger_entries_by_level gauge\n"),
+        "{text}"
+    );
+    let metrics = parse_metrics(&text);
+    assert_eq!(metrics["logger_total_entries_total"], 3.0);
+    assert_eq!(metrics["logger_entries_by_level{level=\"info\"}"], 2.0);
+    assert_eq!(metrics["logger_entries_by_level{level=\"error\"}"], 1.0);
+    assert_eq!(metrics["logger_entries_by_level{level=\"warn\"}"], 0.0);
+    assert_eq!(metrics["logger_entries_by_level{level=\"debug\"}"], 0.0);
+    let age = metrics["logger_oldest_entry_age_seconds"];
+    assert!((0.0..60.0).contains(&age), "{age}");
+    let size = std::fs::metadata(dir.path().join("log.json"))
+        .unwrap()
+        .len();
+    assert_eq!(metrics["logger_file_size_bytes"], size as f64);
+}
+
+#[test]
+fn metrics_port_serves_metrics_over_http() {
+    use std::io::{Read, Write};
+
+    let dir = tempdir().unwrap();
+    common::bin("logger")
+        .current_dir(dir.path())
+        .args(["write", "--level", "warn", "disk almost full"])
+        .assert()
+        .success();
+
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let mut server = std::process::Command::new(common::bin_path("logger"))
+        .current_dir(dir.path())
+        .args(["stats", "--metrics-port", &port.to_string()])
+        .stdout(std::process::Stdio::null())
+        .spawn()
+        .unwrap();
+
+    let get = |path: &str| -> Option<String> {
+        let mut stream = std::net::TcpStream::connect(("127.0.0.1", port)).ok()?;
+        write!(
+            stream,
+            "GET {path} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n"
+        )
+        .ok()?;
+        let mut response = String::new();
+        stream.read_to_string(&mut response).ok()?;
+        Some(response)
+    };
+    let mut response = None;
+    for _ in 0..100 {
+        response = get("/metrics");
+        if response.is_some() {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(50));
+    }
+    let not_found = get("/other");
+    server.kill().unwrap();
+    server.wait().unwrap();
+
+    let response = response.expect("metrics server did not start");
+    assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+    assert!(response.contains("text/plain; version=0.0.4"), "{response}");
+    let (_, body) = response.split_once("\r\n\r\n").unwrap();
+    let metrics = parse_metrics(body);
+    assert_eq!(metrics["logger_total_entries_total"], 1.0);
+    assert_eq!(metrics["logger_entries_by_level{level=\"warn\"}"], 1.0);
+    assert!(not_found.unwrap().starts_with("HTTP/1.1 404"));
+}
+
+#[test]
+fn output_format_template_shapes_read_and_export() {
+    let dir = tempdir().unwrap();
+    let trace_id = "4bf92f3577b34da6a3ce929d0e0e4736";
+
+    common::bin("logger")
+        .current_dir(dir.path())
+        .args(["write", "--level", "warn", "disk at {level}%"])
+        .assert()
+        .success();
+    common::bin("logger")
+        .current_dir(dir.path())
+        .args([
+            "write",
+            "--level",
+            "error",
+            "payment failed",
+            "--trace-id",
+            trace_id,
+        ])
+        .assert()
+        .success();
+
+    common::bin("logger")
+        .current_dir(dir.path())
+        .args(["read", "--output-format", "{level}|{id}|{message}"])
+        .assert()
+        .success()
+        .stdout(format!(
+            "WARN||disk at {{level}}%\nERROR|{trace_id}|payment failed\n"
+        ));
+
+    common::bin("logger")
+        .current_dir(dir.path())
+        .args([
+            "--output-format",
+            "{message} @ {hostname}",
+            "export",
+            "--format",
+            "txt",
+        ])
+        .assert()
+        .success();
+    let export = std::fs::read_dir(dir.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .find(|path| path.extension().is_some_and(|ext| ext == "txt"))
+        .unwrap();
+    let lines: Vec<String> = std::fs::read_to_string(export)
+        .unwrap()
+        .lines()
+        .map(String::from)
+        .collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[1].starts_with("payment failed @ "), "{lines:?}");
+    assert!(!lines[1].contains("{hostname}"), "{lines:?}");
+
+    common::bin("logger")
+        .current_dir(dir.path())
+        .args(["read", "--output-format", "[{level}]"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "the template must contain {message}",
+        ));
+}
+
+#[test]
+fn syslog_output_keeps_writing_the_log_file() {
+    let dir = tempdir().unwrap();
+
+    // Whether or not a syslog daemon is listening, the entry still lands in log.json
+    common::bin("logger")
+        .current_dir(dir.path())
+        .args([
+            "write",
+            "--level",
+            "error",
+            "disk failure",
+            "--syslog",
+            "--syslog-facility",
+            "local0",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("ERROR log written."));
+
+    common::bin("logger")
+        .current_dir(dir.path())
+        .arg("read")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("[ERROR] disk failure"));
+
+    common::bin("logger")
+        .current_dir(dir.path())
+        .args(["write", "hello", "--syslog", "--syslog-facility", "printer"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "unknown syslog facility 'printer'",
+        ));
+}
+
+#[test]
+fn count_prints_matches_and_sets_exit_code() {
+    let dir = tempdir().unwrap();
+    let count = |args: &[&str]| {
+        common::bin("logger")
+            .current_dir(dir.path())
+            .arg("count")
+            .args(args)
+            .assert()
+    };
+
+    // No log file yet
+    count(&[]).code(1).stdout("0\n");
+
+    std::fs::write(
+        dir.path().join("log.json"),
+        "{\"timestamp\":\"2020-01-01T10:00:00Z\",\"level\":\"ERROR\",\"message\":\"old outage\"}\n",
+    )
+    .unwrap();
+    for (level, message) in [
+        ("error", "database down"),
+        ("error", "database timeout"),
+        ("info", "database ok"),
+    ] {
+        common::bin("logger")
+            .current_dir(dir.path())
+            .args(["write", "--level", level, message])
+            .assert()
+            .success();
+    }
+
+    count(&[]).success().stdout("4\n");
+    count(&["--level", "error"]).success().stdout("3\n");
+    count(&["--level", "error", "--since", "1h"])
+        .success()
+        .stdout("2\n");
+    count(&["--search", "DATABASE", "--until", "2020-06-01"])
+        .code(1)
+        .stdout("0\n");
+    count(&["--since", "2019-12-31", "--until", "2020-01-01 10:00:00"])
+        .success()
+        .stdout("1\n");
+    count(&["--level", "loud"])
+        .code(2)
+        .stderr(predicate::str::contains("Invalid log level: loud"));
+    count(&["--since", "yesterday"]).code(2);
+}
+
+#[test]
+fn checksum_detects_changes_made_outside_logger() {
+    let dir = tempdir().unwrap();
+    let write = |message: &str| {
+        common::bin("logger")
+            .current_dir(dir.path())
+            .args(["write", message])
+            .assert()
+            .success();
+    };
+    let checksum = |args: &[&str]| {
+        common::bin("logger")
+            .current_dir(dir.path())
+            .arg("checksum")
+            .args(args)
+            .assert()
+    };
+
+    write("first");
+    checksum(&["--verify"])
+        .code(1)
+        .stdout(predicate::str::contains("log.json: no stored checksum"));
+    checksum(&[]).success().stdout(predicate::str::contains(
+        "Stored checksum of log.json in log.json.sha256",
+    ));
+    let stored = std::fs::read_to_string(dir.path().join("log.json.sha256")).unwrap();
+    assert!(stored.ends_with("  log.json\n"), "{stored}");
+
+    // logger's own writes keep the checksum current
+    write("second");
+    checksum(&["--verify"])
+        .success()
+        .stdout(predicate::str::contains("log.json: OK"));
+
+    let mut file = std::fs::OpenOptions::new()
+        .append(true)
+        .open(dir.path().join("log.json"))
+        .unwrap();
+    std::io::Write::write_all(&mut file, b"x").unwrap();
+    drop(file);
+    checksum(&["--verify"])
+        .code(1)
+        .stdout(predicate::str::contains("outside of logger"));
+
+    // Without --verify the change is reported and the new state accepted
+    checksum(&[])
+        .success()
+        .stdout(predicate::str::contains("outside of logger"));
+    checksum(&["--verify"]).success();
+}
+
+#[test]
+fn import_keeps_corrupt_lines_already_in_the_log() {
+    let dir = tempdir().unwrap();
+    let entry = |time: &str, message: &str| {
+        format!(
+            "{{\"timestamp\":\"2024-05-01T{time}Z\",\"level\":\"INFO\",\"message\":\"{message}\"}}\n"
+        )
+    };
+    let original = format!(
+        "{}{{ half a line\n{}",
+        entry("12:00:00", "late"),
+        entry("10:00:00", "early")
+    );
+    std::fs::write(dir.path().join("log.json"), &original).unwrap();
+    std::fs::write(dir.path().join("other.json"), entry("11:00:00", "imported")).unwrap();
+
+    common::bin("logger")
+        .current_dir(dir.path())
+        .args(["import", "other.json", "--deduplicate"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "log.json has 1 corrupt line(s), so the entries were appended without sorting.",
+        ))
+        .stdout(predicate::str::contains(
+            "Imported 1 entries from other.json",
+        ));
+
+    let log = std::fs::read_to_string(dir.path().join("log.json")).unwrap();
+    assert_eq!(
+        log,
+        format!("{}{}", original, entry("11:00:00", "imported"))
+    );
+}
+
+#[test]
+fn import_merges_sorts_and_deduplicates() {
+    let dir = tempdir().unwrap();
+    let entry = |time: &str, level: &str, message: &str| {
+        format!(
+            "{{\"timestamp\":\"2024-05-01T{time}Z\",\"level\":\"{level}\",\"message\":\"{message}\"}}\n"
+        )
+    };
+    std::fs::write(
+        dir.path().join("log.json"),
+        [
+            entry("10:00:00", "INFO", "boot"),
+            entry("12:00:00", "WARN", "slow disk"),
+            entry("14:00:00", "ERROR", "crash"),
+        ]
+        .concat(),
+    )
+    .unwrap();
+    std::fs::write(
+        dir.path().join("other.json"),
+        [
+            entry("12:00:00", "WARN", "slow disk"),
+            entry("11:00:00", "INFO", "cron ran"),
+            "not json\n".to_string(),
+            entry("14:00:00", "ERROR", "crash"),
+            entry("11:00:00", "INFO", "cron ran"),
+        ]
+        .concat(),
+    )
+    .unwrap();
+
+    common::bin("logger")
+        .current_dir(dir.path())
+        .args(["import", "other.json", "--deduplicate"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Corrupt line 3"))
+        .stdout(predicate::str::contains(
+            "Imported 1 entries from other.json, skipped 3 duplicate(s), 1 corrupt line(s) left out",
+        ));
+
+    let log = std::fs::read_to_string(dir.path().join("log.json")).unwrap();
+    let messages: Vec<String> = log
+        .lines()
+        .map(|line| {
+            serde_json::from_str::<serde_json::Value>(line).unwrap()["message"]
+                .as_str()
+                .unwrap()
+                .to_string()
+        })
+        .collect();
+    assert_eq!(messages, ["boot", "cron ran", "slow disk", "crash"]);
+
+    // Without --deduplicate everything valid is added
+    common::bin("logger")
+        .current_dir(dir.path())
+        .args(["import", "other.json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Imported 4 entries from other.json",
+        ));
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join("log.json"))
+            .unwrap()
+            .lines()
+            .count(),
+        8
+    );
+}
+
+#[test]
+fn replay_prints_entries_in_time_order() {
+    let dir = tempdir().unwrap();
+    let entry = |time: &str, message: &str| {
+        format!(
+            "{{\"timestamp\":\"2024-05-01T{time}Z\",\"level\":\"INFO\",\"message\":\"{message}\"}}\n"
+        )
+    };
+    std::fs::write(
+        dir.path().join("log.json"),
+        [
+            entry("10:00:02", "third"),
+            entry("10:00:00", "first"),
+            entry("10:00:01", "second"),
+            // Two hours later: far past the 60 second cap, so not waited for
+            entry("12:00:00", "fourth"),
+        ]
+        .concat(),
+    )
+    .unwrap();
+
+    let mut replay = common::bin("logger");
+    replay
+        .current_dir(dir.path())
+        .args(["replay", "--speed", "1000.0"]);
+    let started = std::time::Instant::now();
+    let output = replay.output().unwrap();
+    assert!(output.status.success());
+    assert!(started.elapsed() < std::time::Duration::from_secs(10));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let order: Vec<&str> = ["first", "second", "third", "fourth"]
+        .into_iter()
+        .map(|message| (stdout.find(message).unwrap(), message))
+        .collect::<std::collections::BTreeMap<_, _>>()
+        .into_values()
+        .collect();
+    assert_eq!(order, ["first", "second", "third", "fourth"]);
+
+    common::bin("logger")
+        .current_dir(dir.path())
+        .args([
+            "replay",
+            "--speed",
+            "1000",
+            "--from",
+            "2024-05-01 10:00:01",
+            "--to",
+            "2024-05-01 10:00:02",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("second"))
+        .stdout(predicate::str::contains("third"))
+        .stdout(predicate::str::contains("first").not())
+        .stdout(predicate::str::contains("fourth").not());
+
+    // Speeds that would overflow or stall the delays are rejected up front
+    for speed in ["0", "1e-20", "5000", "NaN"] {
+        common::bin("logger")
+            .current_dir(dir.path())
+            .args(["replay", "--speed", speed])
+            .assert()
+            .code(2)
+            .stderr(predicate::str::contains("use a number from 0.01 to 1000"));
+    }
+}
+
+#[test]
+fn max_total_backups_size_removes_the_oldest_backups() {
+    let dir = tempdir().unwrap();
+    let kib = |n: usize| "x".repeat(n * 1024);
+    std::fs::write(dir.path().join("log_backup_20200101_000000.json"), kib(700)).unwrap();
+    std::fs::write(dir.path().join("log_backup_20200102_000000.json"), kib(700)).unwrap();
+    // Past the 1 MiB rotation threshold, so the next write rotates it
+    std::fs::write(dir.path().join("log.json"), kib(1100)).unwrap();
+
+    common::bin("logger")
+        .current_dir(dir.path())
+        .args(["--max-total-backups-size", "2M", "write", "after rotation"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Log file rotated to: log_backup_"))
+        .stdout(predicate::str::contains(
+            "Removed old backup log_backup_20200101_000000.json (716800 bytes) to stay within 2097152 bytes",
+        ))
+        .stdout(predicate::str::contains("20200102").not());
+
+    assert!(!dir.path().join("log_backup_20200101_000000.json").exists());
+    assert!(dir.path().join("log_backup_20200102_000000.json").exists());
+    let backups = std::fs::read_dir(dir.path())
+        .unwrap()
+        .filter(|entry| {
+            entry
+                .as_ref()
+                .unwrap()
+                .file_name()
+                .to_string_lossy()
+                .starts_with("log_backup_")
+        })
+        .count();
+    assert_eq!(backups, 2);
+
+    for size in ["50X", "M", "1.5G"] {
+        common::bin("logger")
+            .current_dir(dir.path())
+            .args(["--max-total-backups-size", size, "read"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("expected a size such as"));
+    }
+}
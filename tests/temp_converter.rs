@@ -0,0 +1,268 @@
+mod common;
+
+use predicates::prelude::*;
+
+fn convert(value: &str, from: &str, to: &str) -> assert_cmd::assert::Assert {
+    common::bin("temp_conventer")
+        .arg(format!("--value={}", value))
+        .args(["--from", from, "--to", to])
+        .assert()
+}
+
+#[test]
+fn converts_celsius_to_fahrenheit() {
+    convert("100", "C", "F")
+        .success()
+        .stdout(predicate::str::contains("Converted temperature: 212.00"));
+}
+
+#[test]
+fn accepts_unit_aliases() {
+    let aliases = [
+        ("C", "273.15"),
+        ("c", "273.15"),
+        ("celsius", "273.15"),
+        ("Centigrade", "273.15"),
+        ("F", "255.37"),
+        ("fahrenheit", "255.37"),
+        ("FAHRENHEIT", "255.37"),
+        ("K", "0.00"),
+        ("kelvin", "0.00"),
+    ];
+
+    for (alias, expected) in aliases {
+        convert("0", alias, "K")
+            .success()
+            .stdout(predicate::str::contains(format!(
+                "Converted temperature: {}",
+                expected
+            )));
+    }
+}
+
+#[test]
+fn rejects_unknown_unit() {
+    convert("0", "rankine", "C")
+        .code(2)
+        .stderr(predicate::str::contains(
+            "invalid value 'rankine' for '--from <FROM>'",
+        ))
+        .stderr(predicate::str::contains(
+            "possible values: celsius, fahrenheit, kelvin, delisle, newton",
+        ));
+}
+
+#[test]
+fn validates_absolute_zero_per_unit() {
+    let boundaries = [
+        ("C", "-273.15", "-273.16"),
+        ("F", "-459.67", "-459.68"),
+        ("K", "0", "-0.01"),
+    ];
+
+    for (unit, at_zero, below_zero) in boundaries {
+        convert(at_zero, unit, "K").success();
+        convert(below_zero, unit, "K")
+            .code(3)
+            .stderr(predicate::str::contains("below absolute zero"));
+    }
+}
+
+#[test]
+fn delta_uses_scale_factor_only() {
+    let cases = [
+        ("C", "F", "50.00", "18.00"),
+        ("F", "C", "-12.22", "5.56"),
+        ("C", "K", "283.15", "10.00"),
+    ];
+
+    for (from, to, absolute, delta) in cases {
+        convert("10", from, to)
+            .success()
+            .stdout(predicate::str::contains(format!(
+                "Converted temperature: {}",
+                absolute
+            )));
+
+        common::bin("temp_conventer")
+            .args(["--value", "10", "--from", from, "--to", to, "--delta"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(format!(
+                "Converted temperature: {}",
+                delta
+            )));
+    }
+}
+
+#[test]
+fn delta_allows_negative_differences() {
+    common::bin("temp_conventer")
+        .args(["--value=-500", "--from", "F", "--to", "C", "--delta"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Converted temperature: -277.78"));
+}
+
+#[test]
+fn history_records_and_clears_conversions() {
+    let dir = tempfile::tempdir().unwrap();
+    let history = dir.path().join("history.csv");
+
+    common::bin("temp_conventer")
+        .args(["--value", "100", "--from", "C", "--to", "F", "--history"])
+        .arg(&history)
+        .assert()
+        .success();
+
+    let contents = std::fs::read_to_string(&history).unwrap();
+    let lines: Vec<&str> = contents.lines().collect();
+    assert_eq!(lines[0], "input,from,to,output,timestamp");
+    assert!(lines[1].starts_with("100,C,F,212.00,"));
+
+    common::bin("temp_conventer")
+        .args(["--show-history", "--clear-history", "--history"])
+        .arg(&history)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("100,C,F,212.00"))
+        .stdout(predicate::str::contains("History cleared."));
+    assert!(!history.exists());
+}
+
+#[test]
+fn range_prints_conversion_table() {
+    let output = common::bin("temp_conventer")
+        .args(["--from", "C", "--to", "F", "--range", "0:100:50"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let rows: Vec<Vec<&str>> = stdout
+        .lines()
+        .map(|line| line.split_whitespace().collect())
+        .collect();
+    assert_eq!(
+        rows,
+        vec![
+            vec!["C", "F"],
+            vec!["0.00", "32.00"],
+            vec!["50.00", "122.00"],
+            vec!["100.00", "212.00"]
+        ]
+    );
+}
+
+#[test]
+fn range_rejects_invalid_bounds() {
+    let cases = [
+        ("0:10:0", 1, "step must be greater than 0"),
+        ("10:0:1", 1, "start must not be greater than its end"),
+        ("0:10", 1, "Use START:END:STEP"),
+        ("-300:0:100", 3, "below absolute zero"),
+        ("0:inf:1", 4, "finite number"),
+        ("0:1e12:1", 1, "more than 10000 rows"),
+        ("0:1e300:1e-300", 1, "more than 10000 rows"),
+    ];
+
+    for (range, code, message) in cases {
+        common::bin("temp_conventer")
+            .args(["--from", "C", "--to", "F", "--range", range])
+            .assert()
+            .code(code)
+            .stderr(predicate::str::contains(message));
+    }
+
+    // Delisle runs the other way, so its end is the coldest value
+    common::bin("temp_conventer")
+        .args(["--from", "De", "--to", "C", "--range", "0:600:100"])
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains("below absolute zero"));
+
+    // Without both units there is nothing to tabulate
+    common::bin("temp_conventer")
+        .args(["--from", "C", "--range", "0:10:1"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("--to"));
+}
+
+#[test]
+fn converts_historical_scales() {
+    // Water boils at 100 °C = 0 °De = 33 °N and freezes at 0 °C = 150 °De = 0 °N
+    let cases = [
+        ("100", "C", "De", "0.00"),
+        ("0", "C", "delisle", "150.00"),
+        ("100", "C", "N", "33.00"),
+        ("0", "celsius", "newton", "0.00"),
+        ("0", "De", "C", "100.00"),
+        ("33", "N", "K", "373.15"),
+    ];
+
+    for (value, from, to, expected) in cases {
+        convert(value, from, to)
+            .success()
+            .stdout(predicate::str::contains(format!(
+                "Converted temperature: {}",
+                expected
+            )));
+    }
+}
+
+#[test]
+fn delisle_absolute_zero_is_an_upper_bound() {
+    convert("559.725", "De", "K").success();
+    convert("560", "De", "K")
+        .code(3)
+        .stderr(predicate::str::contains("below absolute zero"));
+    convert("-90.1395", "N", "K").success();
+    convert("-90.2", "N", "K").code(3);
+}
+
+#[test]
+fn non_finite_value_has_its_own_exit_code() {
+    for value in ["inf", "-inf", "NaN"] {
+        convert(value, "C", "F")
+            .code(4)
+            .stderr(predicate::str::contains("finite number"));
+    }
+}
+
+#[test]
+fn prints_unit_symbol_unless_disabled() {
+    convert("100", "C", "F")
+        .success()
+        .stdout(predicate::str::contains(
+            "Converted temperature: 212.00 °F\n",
+        ));
+
+    convert("0", "C", "K")
+        .success()
+        .stdout(predicate::str::contains(
+            "Converted temperature: 273.15 K\n",
+        ));
+
+    common::bin("temp_conventer")
+        .args(["--value=100", "--from", "C", "--to", "F", "--no-symbol"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Converted temperature: 212.00\n"));
+}
+
+#[test]
+fn interactive_reversal_prints_source_unit() {
+    common::bin("temp_conventer")
+        .write_stdin("100\nC\nF\ny\nexit\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Reversed conversion: 37.78 °C\n"));
+
+    common::bin("temp_conventer")
+        .arg("--no-symbol")
+        .write_stdin("100\nC\nF\ny\nexit\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Reversed conversion: 37.78\n"));
+}
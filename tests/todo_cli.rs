@@ -0,0 +1,1363 @@
+mod common;
+
+use std::fs;
+use std::path::Path;
+
+use predicates::prelude::*;
+use serde_json::{Value, json};
+use tempfile::tempdir;
+
+/// The tasks stored in `todo.json`
+fn stored_tasks(dir: &Path) -> Vec<Value> {
+    let contents = fs::read_to_string(dir.join("todo.json")).unwrap();
+    serde_json::from_str(&contents).unwrap()
+}
+
+/// `todo_cli` keeping its data in `dir`
+fn todo(dir: &Path) -> assert_cmd::Command {
+    let mut cmd = common::bin("todo_cli");
+    cmd.arg("--data-dir").arg(dir);
+    cmd
+}
+
+fn descriptions(tasks: &[Value]) -> Vec<&str> {
+    tasks
+        .iter()
+        .map(|task| task["description"].as_str().unwrap())
+        .collect()
+}
+
+#[test]
+fn add_list_and_complete() {
+    let dir = tempdir().unwrap();
+
+    todo(dir.path())
+        .write_stdin("add Buy milk\nlist\ncomplete 1\nexit\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Added task: 'Buy milk' with priority: Medium",
+        ))
+        .stdout(predicate::str::contains("1: [Medium] Buy milk"))
+        .stdout(predicate::str::contains(
+            "Marked task as complete: 'Buy milk'",
+        ));
+
+    let tasks = stored_tasks(dir.path());
+    assert_eq!(tasks.len(), 1);
+    assert_eq!(tasks[0]["description"], "Buy milk");
+    assert_eq!(tasks[0]["status"], "done");
+    assert!(tasks[0]["completed_at"].is_string());
+}
+
+#[test]
+fn data_dir_is_created_when_missing() {
+    let dir = tempdir().unwrap();
+    let data_dir = dir.path().join("nested").join("todo");
+
+    todo(&data_dir)
+        .write_stdin("add Water plants\nexit\n")
+        .assert()
+        .success();
+
+    assert_eq!(
+        stored_tasks(&data_dir),
+        vec![
+            json!({ "id": 1, "description": "Water plants", "priority": "Medium", "status": "pending", "total_time_seconds": 0 })
+        ]
+    );
+}
+
+#[test]
+fn fresh_install_reports_no_tasks() {
+    let dir = tempdir().unwrap();
+    let data_dir = dir.path().join("todo");
+
+    for command in ["list", "scheduled", "stats"] {
+        todo(&data_dir)
+            .arg(command)
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("No tasks yet."));
+    }
+
+    // Running out of input ends the interactive session cleanly
+    todo(&data_dir)
+        .write_stdin("list\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No tasks yet."));
+}
+
+#[test]
+fn data_dir_can_come_from_environment() {
+    let dir = tempdir().unwrap();
+
+    common::bin("todo_cli")
+        .env("TODO_DATA_DIR", dir.path())
+        .write_stdin("add Call mom\nexit\n")
+        .assert()
+        .success();
+
+    assert!(dir.path().join("todo.json").exists());
+}
+
+#[test]
+fn file_option_keeps_separate_lists() {
+    let dir = tempdir().unwrap();
+    let todo = |file: &str, args: &[&str]| {
+        common::bin("todo_cli")
+            .current_dir(dir.path())
+            .env_remove("TODO_FILE")
+            .args(["--file", file])
+            .args(args)
+            .assert()
+            .success()
+    };
+
+    todo("work.json", &["add", "Ship release"]);
+    todo("personal.json", &["add", "Water plants"]);
+    todo("work.json", &["list"])
+        .stdout(predicate::str::contains("Ship release"))
+        .stdout(predicate::str::contains("Water plants").not());
+
+    let stored: Vec<Value> =
+        serde_json::from_str(&fs::read_to_string(dir.path().join("personal.json")).unwrap())
+            .unwrap();
+    assert_eq!(descriptions(&stored), ["Water plants"]);
+
+    todo("work.json", &["complete", "1"]);
+    todo("work.json", &["clear-done", "--archive"]).stdout(predicate::str::contains(
+        "Archived 1 completed task(s) to work_done.json",
+    ));
+    assert!(dir.path().join("work_done.json").exists());
+
+    common::bin("todo_cli")
+        .current_dir(dir.path())
+        .env("TODO_FILE", "personal.json")
+        .arg("list")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Water plants"));
+}
+
+#[test]
+fn undo_and_redo_last_add() {
+    let dir = tempdir().unwrap();
+
+    todo(dir.path())
+        .write_stdin("add Keep me\nadd Oops\nexit\n")
+        .assert()
+        .success();
+
+    todo(dir.path())
+        .write_stdin("undo\nlist\nexit\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Undid the last change."))
+        .stdout(predicate::str::contains("1: [Medium] Keep me"))
+        .stdout(predicate::str::contains("Oops").not());
+
+    todo(dir.path())
+        .write_stdin("redo\nlist\nexit\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Redid the last undone change."))
+        .stdout(predicate::str::contains("2: [Medium] Oops"));
+
+    todo(dir.path())
+        .write_stdin("redo\nexit\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Warning: Nothing to redo."));
+}
+
+#[test]
+fn undo_warns_without_backup() {
+    let dir = tempdir().unwrap();
+
+    todo(dir.path())
+        .write_stdin("undo\nexit\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Warning: Nothing to undo."));
+}
+
+#[test]
+fn undo_failure_exits_non_zero() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("todo.json"), "[]").unwrap();
+    fs::write(dir.path().join("todo.json.bak"), "[]").unwrap();
+    // A non-empty directory where the redo copy goes makes the swap fail
+    fs::create_dir_all(dir.path().join("todo.json.redo/blocked")).unwrap();
+
+    todo(dir.path())
+        .arg("undo")
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("Could not undo"));
+    assert!(dir.path().join("todo.json.bak").exists());
+}
+
+#[test]
+fn stats_renders_progress_bar() {
+    let cases = [
+        ("[Medium] A\n[Medium] B\n", "[░░░░░░░░░░░░░░░░░░░░] 0%"),
+        (
+            "[Medium] [Done] A\n[Medium] B\n",
+            "[██████████░░░░░░░░░░] 50%",
+        ),
+        (
+            "[Medium] [Done] A\n[Medium] [Done] B\n",
+            "[████████████████████] 100%",
+        ),
+    ];
+
+    for (contents, bar) in cases {
+        let dir = tempdir().unwrap();
+        std::fs::write(dir.path().join("todo.txt"), contents).unwrap();
+
+        todo(dir.path())
+            .write_stdin("stats\nexit\n")
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(bar));
+    }
+}
+
+#[test]
+fn stats_reports_goal_and_streak() {
+    let dir = tempdir().unwrap();
+    let now = chrono::Utc::now().to_rfc3339();
+    std::fs::write(
+        dir.path().join("todo.txt"),
+        format!("[High] [Done:{now}] Ship it\n[Low] Later\n"),
+    )
+    .unwrap();
+
+    todo(dir.path())
+        .write_stdin("stats --goal 2\nexit\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Completed 1 of 2 tasks"))
+        .stdout(predicate::str::contains("🔥 1-day streak"))
+        .stdout(predicate::str::contains(
+            "Today: 1/2 tasks completed (keep going)",
+        ));
+}
+
+#[test]
+fn start_and_stop_accumulate_time() {
+    let dir = tempdir().unwrap();
+    let started = (chrono::Utc::now() - chrono::Duration::minutes(83)).to_rfc3339();
+    std::fs::write(
+        dir.path().join("todo.txt"),
+        format!(
+            "[High] [Started:{started}] Write report\n[Low] [Time:600] Reply to email\n[Low] Idle\n"
+        ),
+    )
+    .unwrap();
+
+    todo(dir.path())
+        .write_stdin("stop 1\nlist\nexit\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Stopped timer for task 1: +1h23m (total 1h23m)",
+        ))
+        .stdout(predicate::str::contains("1: [High] Write report ⏱ 1h23m"))
+        .stdout(predicate::str::contains("3: [Low] Idle\n"));
+
+    todo(dir.path())
+        .write_stdin("stop 1\nstart 2\nstart 2\nstop 2\nexit\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No timer running for task 1."))
+        .stdout(predicate::str::contains(
+            "Started timer for task 2: 'Reply to email'",
+        ))
+        .stdout(predicate::str::contains(
+            "Timer already running for task 2.",
+        ))
+        .stdout(predicate::str::contains("(total 10m)"));
+
+    todo(dir.path())
+        .write_stdin("stats --time\nexit\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "1: Write report - 1h23m\n2: Reply to email - 10m",
+        ));
+}
+
+#[test]
+fn completion_note_is_listed_with_completed_tasks() {
+    let dir = tempdir().unwrap();
+
+    todo(dir.path())
+        .write_stdin("add Fix login bug\nadd Write docs\nexit\n")
+        .assert()
+        .success();
+
+    todo(dir.path())
+        .write_stdin("complete 1 --note \"Patched the [session] check\"\nlist --completed\nexit\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Marked task as complete: 'Fix login bug'",
+        ))
+        .stdout(predicate::str::contains(
+            "1: [Medium] [Done] Fix login bug\n   📝 Patched the [session] check",
+        ))
+        .stdout(predicate::str::contains("Write docs").not());
+
+    let long_note = "x".repeat(501);
+    todo(dir.path())
+        .write_stdin(format!("complete 2 --note {long_note}\nexit\n"))
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "The note is too long (max 500 characters).",
+        ));
+}
+
+#[test]
+fn add_persists_priority_marker() {
+    let dir = tempdir().unwrap();
+
+    todo(dir.path())
+        .write_stdin("add --priority high Fix the build\nadd -p low Water plants\nlist\nexit\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Added task: 'Fix the build' with priority: High",
+        ))
+        .stdout(predicate::str::contains("1: [High] Fix the build"))
+        .stdout(predicate::str::contains("2: [Low] Water plants"));
+
+    let priorities: Vec<Value> = stored_tasks(dir.path())
+        .iter()
+        .map(|task| task["priority"].clone())
+        .collect();
+    assert_eq!(priorities, vec!["High", "Low"]);
+}
+
+#[test]
+fn bulk_complete_by_priority() {
+    let dir = tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("todo.txt"),
+        "[High] Fix outage\n[Low] Tidy desk\n[High] Call client @work\n[Medium] Plan trip\n",
+    )
+    .unwrap();
+
+    todo(dir.path())
+        .write_stdin("complete --all --priority high --dry-run\nexit\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Would complete 2 task(s):"));
+    assert!(
+        stored_tasks(dir.path())
+            .iter()
+            .all(|task| task["status"] == "pending")
+    );
+
+    todo(dir.path())
+        .write_stdin("complete --all --priority high\nlist --completed\nexit\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Completed 2 task(s):"))
+        .stdout(predicate::str::contains("1: [High] [Done] Fix outage"))
+        .stdout(predicate::str::contains(
+            "3: [High] [Done] Call client @work",
+        ));
+
+    let tasks = stored_tasks(dir.path());
+    let done: Vec<&Value> = tasks
+        .iter()
+        .filter(|task| task["status"] == "done")
+        .collect();
+    assert_eq!(done.len(), 2);
+    assert!(
+        done.iter()
+            .all(|task| task["priority"] == "High" && task["completed_at"].is_string())
+    );
+}
+
+#[test]
+fn bulk_delete_by_ids_and_tag() {
+    let dir = tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("todo.txt"),
+        "[Low] One @home\n[Low] Two @work\n[Low] Three @home\n[Low] Four\n",
+    )
+    .unwrap();
+
+    todo(dir.path())
+        .write_stdin("delete --ids 1,2,3 --tag home\ndelete --ids 9\nexit\n")
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains("Deleted 2 task(s):"))
+        .stderr(predicate::str::contains("No task with number 9."));
+
+    assert_eq!(
+        descriptions(&stored_tasks(dir.path())),
+        vec!["Two @work", "Four"]
+    );
+}
+
+#[test]
+fn complete_marks_task_done_or_fails() {
+    let dir = tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("todo.txt"),
+        "[Low] Buy bread\n[High] Pay rent\n",
+    )
+    .unwrap();
+
+    todo(dir.path())
+        .write_stdin("complete 2\nlist\nexit\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Marked task as complete: 'Pay rent'",
+        ))
+        .stdout(predicate::str::contains("2: [High] [Done] Pay rent"));
+
+    todo(dir.path())
+        .write_stdin("complete 3\nexit\n")
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("Error: No task with number 3."));
+
+    todo(dir.path())
+        .write_stdin("complete bread\nexit\n")
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("invalid value 'bread' for '[ID]'"));
+
+    let tasks = stored_tasks(dir.path());
+    assert_eq!(tasks[0]["status"], "pending");
+    assert_eq!(tasks[1]["status"], "done");
+    assert!(!dir.path().join("todo.json.tmp").exists());
+}
+
+#[test]
+fn delete_removes_task_by_index() {
+    let dir = tempdir().unwrap();
+    std::fs::write(dir.path().join("todo.txt"), "[Low] Keep\n[High] Drop\n").unwrap();
+
+    todo(dir.path())
+        .write_stdin("delete 2\nexit\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Deleted task: '[High] Drop'"));
+    assert_eq!(descriptions(&stored_tasks(dir.path())), vec!["Keep"]);
+
+    todo(dir.path())
+        .write_stdin("delete 5\nexit\n")
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("Error: No task with number 5."));
+    assert_eq!(descriptions(&stored_tasks(dir.path())), vec!["Keep"]);
+}
+
+#[test]
+fn estimates_feed_velocity_and_filters() {
+    let dir = tempdir().unwrap();
+    let now = chrono::Utc::now();
+    // Seven tasks finished on seven consecutive days, 14 points in total
+    let mut contents = String::new();
+    for (days_ago, points) in [(0, 1), (1, 2), (2, 3), (3, 1), (4, 2), (5, 3), (6, 2)] {
+        let completed_at = (now - chrono::Duration::days(days_ago)).to_rfc3339();
+        contents.push_str(&format!(
+            "[Medium] [Done:{completed_at}] [Est:{points}] Done {days_ago}\n"
+        ));
+    }
+    std::fs::write(dir.path().join("todo.txt"), contents).unwrap();
+
+    todo(dir.path())
+        .write_stdin("add Design API --estimate 5\nadd Write tests --estimate 3\nstats\nexit\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Backlog: 8 points"))
+        .stdout(predicate::str::contains("Points completed this week: "))
+        .stdout(predicate::str::contains("Velocity: 2.00 points/day"));
+
+    todo(dir.path())
+        .write_stdin("list --estimate-filter 4..\nexit\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("8: [Medium] Design API (5 pts)"))
+        .stdout(predicate::str::contains("Write tests").not());
+}
+
+#[test]
+fn completing_recurring_task_creates_next_occurrence() {
+    let dir = tempdir().unwrap();
+    // 1 January 2024 was a Monday
+    std::fs::write(
+        dir.path().join("todo.txt"),
+        "[High] [Due:2024-01-01] [Recur:weekly:Monday] Team sync\n",
+    )
+    .unwrap();
+
+    todo(dir.path())
+        .write_stdin("complete 1\nadd Water plants --recur every:3\nlist\nexit\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "♻ Next occurrence: 'Team sync' due 2024-01-08",
+        ))
+        .stdout(predicate::str::contains(
+            "2: [High] Team sync ♻ weekly:Monday",
+        ))
+        .stdout(predicate::str::contains(
+            "3: [Medium] Water plants ♻ every:3",
+        ));
+
+    let tasks = stored_tasks(dir.path());
+    assert_eq!(tasks[0]["status"], "done");
+    assert_eq!(tasks[1]["id"], 2);
+    assert_eq!(tasks[1]["status"], "pending");
+    assert_eq!(tasks[1]["due_date"], "2024-01-08");
+    assert_eq!(tasks[1]["recurrence"], "weekly:Monday");
+    assert!(tasks[1]["created_at"].is_string());
+}
+
+#[test]
+fn monthly_recurrence_picks_the_next_matching_day() {
+    let cases = [
+        ("2024-01-05", "monthly:20", "2024-01-20"),
+        ("2024-01-20", "monthly:20", "2024-02-20"),
+        ("2024-01-31", "monthly:31", "2024-02-29"),
+        ("2024-04-30", "monthly:31", "2024-05-31"),
+        ("2024-12-25", "monthly:5", "2025-01-05"),
+    ];
+
+    for (due, recur, next) in cases {
+        let dir = tempdir().unwrap();
+        todo(dir.path())
+            .args(["add", "Pay rent", "--due", due, "--recur", recur])
+            .assert()
+            .success();
+        todo(dir.path())
+            .args(["complete", "1"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(format!("due {next}")));
+        assert_eq!(
+            stored_tasks(dir.path())[1]["due_date"],
+            next,
+            "{recur} from {due}"
+        );
+    }
+}
+
+#[test]
+fn import_markdown_checklist() {
+    let dir = tempdir().unwrap();
+    let notes = dir.path().join("minutes.md");
+    std::fs::write(
+        &notes,
+        "# Weekly sync\n\n- [ ] Send agenda\n- [x] Book room\n* [X] Order snacks\n- plain bullet\n- [ ] Existing task\n",
+    )
+    .unwrap();
+    std::fs::write(dir.path().join("todo.txt"), "[High] Existing task\n").unwrap();
+
+    todo(dir.path())
+        .write_stdin(format!("import {}\nlist\nexit\n", notes.display()))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Imported 3 task(s)"))
+        .stderr(predicate::str::contains(
+            "Warning: Skipping duplicate task 'Existing task'",
+        ));
+
+    let tasks = stored_tasks(dir.path());
+    let summary: Vec<(&str, &str, &str)> = tasks
+        .iter()
+        .map(|task| {
+            (
+                task["description"].as_str().unwrap(),
+                task["priority"].as_str().unwrap(),
+                task["status"].as_str().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("Existing task", "High", "pending"),
+            ("Send agenda", "Medium", "pending"),
+            ("Book room", "Medium", "done"),
+            ("Order snacks", "Medium", "done"),
+        ]
+    );
+}
+
+#[test]
+fn subcommands_run_without_interactive_session() {
+    let dir = tempdir().unwrap();
+
+    todo(dir.path())
+        .args(["add", "--priority", "high", "Renew passport"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Added task: 'Renew passport' with priority: High",
+        ))
+        .stdout(predicate::str::contains("Welcome").not());
+    todo(dir.path())
+        .args(["add", "Buy stamps"])
+        .assert()
+        .success();
+
+    todo(dir.path())
+        .args(["complete", "2", "--note", "Post office on Main St"])
+        .assert()
+        .success();
+    todo(dir.path())
+        .args(["list", "--completed"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "2: [Medium] [Done] Buy stamps\n   📝 Post office on Main St",
+        ));
+
+    todo(dir.path())
+        .args(["complete", "9"])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("Error: No task with number 9."));
+    todo(dir.path())
+        .args(["add", "--priority", "urgent", "Oops"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "possible values: low, medium, high",
+        ));
+}
+
+#[test]
+fn scheduled_sorts_by_due_date_and_flags_overdue() {
+    let dir = tempdir().unwrap();
+
+    todo(dir.path()).args(["add", "No date"]).assert().success();
+    todo(dir.path())
+        .args(["add", "--due", "2999-03-01", "Far future"])
+        .assert()
+        .success();
+    todo(dir.path())
+        .args(["add", "--due", "2001-01-01T09:00:00+00:00", "Long overdue"])
+        .assert()
+        .success();
+    todo(dir.path())
+        .args(["add", "--due", "next week", "Bad date"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("Invalid date 'next week'"));
+
+    let output = todo(dir.path())
+        .arg("scheduled")
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let stdout = String::from_utf8(output).unwrap();
+    let lines: Vec<&str> = stdout.lines().skip(1).collect();
+    assert_eq!(
+        lines,
+        vec![
+            "3: [Medium] Long overdue (due 2001-01-01) ⚠ OVERDUE",
+            "2: [Medium] Far future (due 2999-03-01)",
+        ]
+    );
+}
+
+#[test]
+fn list_sorts_by_priority_or_due_date() {
+    let dir = tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("todo.txt"),
+        "[Low] Dust shelves\n[High] [Due:2030-05-01] Pay taxes\n[Medium] [Due:2030-01-15] Book dentist\n[High] Call bank\n",
+    )
+    .unwrap();
+    let listed = |sort: &str| {
+        let output = todo(dir.path())
+            .args(["list", "--sort", sort])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        String::from_utf8(output.stdout)
+            .unwrap()
+            .lines()
+            .skip(1)
+            .map(|line| line.split(':').next().unwrap().to_string())
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(listed("priority"), vec!["2", "4", "3", "1"]);
+    assert_eq!(listed("due"), vec!["3", "2", "1", "4"]);
+}
+
+#[test]
+fn list_filters_by_priority() {
+    let dir = tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("todo.txt"),
+        "[Low] Dust shelves\n[High] Pay taxes\n[Medium] Book dentist\n[High] Call bank\n",
+    )
+    .unwrap();
+
+    todo(dir.path())
+        .args(["list", "--priority", "high"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "2: [High] Pay taxes\n4: [High] Call bank",
+        ))
+        .stdout(predicate::str::contains("Low").not())
+        .stdout(predicate::str::contains("Medium").not());
+
+    todo(dir.path())
+        .args(["list", "--priority", "low", "--sort", "priority"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1: [Low] Dust shelves"))
+        .stdout(predicate::str::contains("High").not());
+
+    todo(dir.path())
+        .args(["list", "--priority", "urgent"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "possible values: low, medium, high",
+        ));
+}
+
+#[test]
+fn legacy_todo_txt_is_migrated_once() {
+    let dir = tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("todo.txt"),
+        "[High] [Done:2024-03-01T10:00:00+00:00] [Note:Sent \\] twice] Email @Work report\n[Low] [Due:2024-04-01] Buy @home @HOME bulbs\n",
+    )
+    .unwrap();
+
+    todo(dir.path())
+        .arg("list")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Migrated 2 task(s)"))
+        .stdout(predicate::str::contains(
+            "1: [High] [Done] Email @Work report",
+        ))
+        .stdout(predicate::str::contains(
+            "2: [Low] Buy @home @HOME bulbs (due 2024-04-01)",
+        ));
+
+    let tasks = stored_tasks(dir.path());
+    assert_eq!(tasks[0]["id"], 1);
+    assert_eq!(tasks[0]["status"], "done");
+    assert_eq!(tasks[0]["completed_at"], "2024-03-01T10:00:00Z");
+    assert_eq!(tasks[0]["completion_note"], "Sent ] twice");
+    assert_eq!(tasks[0]["tags"], json!(["work"]));
+    assert_eq!(tasks[1]["id"], 2);
+    assert_eq!(tasks[1]["due_date"], "2024-04-01");
+    assert_eq!(tasks[1]["tags"], json!(["home"]));
+
+    // The old file is kept but no longer read
+    todo(dir.path())
+        .args(["delete", "1"])
+        .assert()
+        .success()
+        .stderr(predicate::str::is_empty());
+    assert!(dir.path().join("todo.txt").exists());
+    assert_eq!(
+        descriptions(&stored_tasks(dir.path())),
+        vec!["Buy @home @HOME bulbs"]
+    );
+}
+
+#[test]
+fn search_matches_description_case_insensitively() {
+    let dir = tempdir().unwrap();
+
+    todo(dir.path())
+        .args(["add", "Renew passport"])
+        .assert()
+        .success();
+    todo(dir.path())
+        .args(["add", "Call the bank"])
+        .assert()
+        .success();
+    todo(dir.path())
+        .args(["add", "-p", "high", "Pay PASSPORT fee"])
+        .assert()
+        .success();
+
+    todo(dir.path())
+        .args(["search", "passport"])
+        .assert()
+        .success()
+        .stdout(predicate::str::diff(
+            "1: [Medium] Renew passport\n3: [High] Pay PASSPORT fee\n",
+        ));
+    todo(dir.path())
+        .args(["search", "the", "bank"])
+        .assert()
+        .success()
+        .stdout(predicate::str::diff("2: [Medium] Call the bank\n"));
+    todo(dir.path())
+        .args(["search", "dentist"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No tasks matching 'dentist'."));
+}
+
+#[test]
+fn edit_rewrites_description_only() {
+    let dir = tempdir().unwrap();
+
+    todo(dir.path())
+        .args([
+            "add",
+            "-p",
+            "high",
+            "--due",
+            "2030-01-01",
+            "Pay teh rent @home",
+        ])
+        .assert()
+        .success();
+    todo(dir.path()).args(["complete", "1"]).assert().success();
+
+    todo(dir.path())
+        .args(["edit", "1", "Pay the rent", "@flat"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Edited task 1:\n  before: 'Pay teh rent @home'\n  after:  'Pay the rent @flat'",
+        ));
+
+    let tasks = stored_tasks(dir.path());
+    assert_eq!(tasks[0]["description"], "Pay the rent @flat");
+    assert_eq!(tasks[0]["priority"], "High");
+    assert_eq!(tasks[0]["status"], "done");
+    assert_eq!(tasks[0]["due_date"], "2030-01-01");
+    assert_eq!(tasks[0]["tags"], json!(["flat"]));
+
+    todo(dir.path())
+        .args(["edit", "4", "Nothing"])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("Error: No task with number 4."));
+    todo(dir.path()).arg("undo").assert().success();
+    assert_eq!(
+        stored_tasks(dir.path())[0]["description"],
+        "Pay teh rent @home"
+    );
+
+    todo(dir.path())
+        .args(["edit", "1", "--estimate", "3"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("  estimate: 3 pts"));
+    todo(dir.path())
+        .args(["edit", "1", "--estimate", "5"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("  estimate: 3 -> 5 pts"));
+    let tasks = stored_tasks(dir.path());
+    assert_eq!(tasks[0]["estimate"], 5);
+    assert_eq!(tasks[0]["description"], "Pay teh rent @home");
+}
+
+#[test]
+fn list_filters_and_highlights_tags() {
+    let dir = tempdir().unwrap();
+
+    todo(dir.path())
+        .args(["add", "Prepare slides @work @Talks"])
+        .assert()
+        .success();
+    todo(dir.path())
+        .args(["add", "Fix sink @home"])
+        .assert()
+        .success();
+    todo(dir.path())
+        .args(["add", "Book venue @talks"])
+        .assert()
+        .success();
+    assert_eq!(
+        stored_tasks(dir.path())[0]["tags"],
+        json!(["work", "talks"])
+    );
+
+    todo(dir.path())
+        .args(["list", "--tag", "@TALKS"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "1: [Medium] Prepare slides @work @Talks\n3: [Medium] Book venue @talks",
+        ))
+        .stdout(predicate::str::contains("Fix sink").not());
+    todo(dir.path())
+        .args(["list", "--tag", "garden"])
+        .assert()
+        .success()
+        .stdout(predicate::str::diff("Listing all tasks...\n"));
+
+    todo(dir.path())
+        .env("CLICOLOR_FORCE", "1")
+        .args(["list", "--tag", "home"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Fix sink \u{1b}[36m@home\u{1b}[0m",
+        ));
+}
+
+#[test]
+fn export_ical_creates_event_per_due_task() {
+    let dir = tempdir().unwrap();
+
+    let long_summary = format!(
+        "Plan the offsite; book rooms, catering {}",
+        "and much more ".repeat(6)
+    );
+    todo(dir.path())
+        .args(["add", "-p", "high", "--due", "2030-03-04", "File taxes"])
+        .assert()
+        .success();
+    todo(dir.path()).args(["add", "No date"]).assert().success();
+    todo(dir.path())
+        .args([
+            "add",
+            "-p",
+            "low",
+            "--due",
+            "2030-05-06",
+            long_summary.trim(),
+        ])
+        .assert()
+        .success();
+    todo(dir.path()).args(["complete", "1"]).assert().success();
+
+    let calendar = dir.path().join("tasks.ics");
+    todo(dir.path())
+        .args([
+            "export",
+            "--format",
+            "ical",
+            "--output",
+            calendar.to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Exported tasks to"));
+
+    let raw = fs::read_to_string(&calendar).unwrap();
+    assert!(raw.ends_with("END:VCALENDAR\r\n"));
+    assert!(raw.split("\r\n").all(|line| line.len() <= 75), "{raw}");
+
+    // Unfold continuation lines before looking at properties
+    let unfolded = raw.replace("\r\n ", "");
+    let lines: Vec<&str> = unfolded.split("\r\n").collect();
+    assert_eq!(
+        lines[..3],
+        [
+            "BEGIN:VCALENDAR",
+            "VERSION:2.0",
+            "PRODID:-//75HC-Rust//todo_cli//EN"
+        ]
+    );
+    assert_eq!(
+        lines.iter().filter(|line| **line == "BEGIN:VEVENT").count(),
+        2
+    );
+    assert_eq!(
+        lines.iter().filter(|line| **line == "END:VEVENT").count(),
+        2
+    );
+
+    let events: Vec<&str> = unfolded.split("BEGIN:VEVENT\r\n").skip(1).collect();
+    assert!(events[0].contains("DTSTART;VALUE=DATE:20300304\r\nDTEND;VALUE=DATE:20300304\r\n"));
+    assert!(events[0].contains("SUMMARY:File taxes\r\nPRIORITY:1\r\nSTATUS:COMPLETED\r\n"));
+    let escaped = long_summary.trim().replace(';', "\\;").replace(',', "\\,");
+    assert!(
+        events[1].contains(&format!("SUMMARY:{escaped}\r\n")),
+        "{}",
+        events[1]
+    );
+    assert!(events[1].contains("PRIORITY:9\r\nSTATUS:NEEDS-ACTION\r\n"));
+}
+
+#[test]
+fn export_csv_and_markdown() {
+    let dir = tempdir().unwrap();
+
+    todo(dir.path())
+        .args([
+            "add",
+            "-p",
+            "high",
+            "--due",
+            "2030-03-04",
+            "Buy milk, eggs @home @errands",
+        ])
+        .assert()
+        .success();
+    todo(dir.path())
+        .args(["add", "Say \"hi\""])
+        .assert()
+        .success();
+    todo(dir.path())
+        .args(["complete", "2", "--note", "Said it twice, to be sure"])
+        .assert()
+        .success();
+
+    let table = dir.path().join("tasks.csv");
+    todo(dir.path())
+        .args([
+            "export",
+            "--format",
+            "csv",
+            "--output",
+            table.to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Exported tasks to"));
+    assert_eq!(
+        fs::read_to_string(&table).unwrap(),
+        "id,description,priority,status,due,tags,note\n\
+         1,\"Buy milk, eggs @home @errands\",High,pending,2030-03-04,home errands,\n\
+         2,\"Say \"\"hi\"\"\",Medium,done,,,\"Said it twice, to be sure\"\n"
+    );
+
+    let checklist = dir.path().join("tasks.md");
+    todo(dir.path())
+        .args([
+            "export",
+            "--format",
+            "markdown",
+            "--output",
+            checklist.to_str().unwrap(),
+        ])
+        .assert()
+        .success();
+    assert_eq!(
+        fs::read_to_string(&checklist).unwrap(),
+        "- [ ] Buy milk, eggs @home @errands (due 2030-03-04)\n- [x] Say \"hi\"\n"
+    );
+}
+
+#[test]
+fn priorities_are_colored_on_request() {
+    let dir = tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("todo.txt"),
+        "[High] [Due:2030-01-01] Pay rent\n[Medium] Call mom\n[Low] [Due:2030-02-01] Dust\n",
+    )
+    .unwrap();
+
+    todo(dir.path())
+        .env("CLICOLOR_FORCE", "1")
+        .arg("list")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "1: \u{1b}[31m[High]\u{1b}[0m Pay rent",
+        ))
+        .stdout(predicate::str::contains(
+            "2: \u{1b}[33m[Medium]\u{1b}[0m Call mom",
+        ))
+        .stdout(predicate::str::contains("3: \u{1b}[32m[Low]\u{1b}[0m Dust"));
+    todo(dir.path())
+        .env("CLICOLOR_FORCE", "1")
+        .arg("scheduled")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "1: \u{1b}[31m[High]\u{1b}[0m Pay rent (due 2030-01-01)",
+        ))
+        .stdout(predicate::str::contains(
+            "3: \u{1b}[32m[Low]\u{1b}[0m Dust (due 2030-02-01)",
+        ));
+
+    // Piped output stays plain
+    todo(dir.path())
+        .arg("list")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\u{1b}[").not());
+}
+
+#[test]
+fn completion_time_is_recorded_and_shown() {
+    let dir = tempdir().unwrap();
+
+    todo(dir.path())
+        .args(["add", "Renew passport"])
+        .assert()
+        .success();
+    todo(dir.path())
+        .args(["add", "Book flights"])
+        .assert()
+        .success();
+    let before = chrono::Utc::now();
+    todo(dir.path()).args(["complete", "1"]).assert().success();
+
+    let tasks = stored_tasks(dir.path());
+    let completed_at: chrono::DateTime<chrono::Utc> =
+        tasks[0]["completed_at"].as_str().unwrap().parse().unwrap();
+    assert!(completed_at >= before - chrono::Duration::seconds(1));
+    assert!(tasks[1].get("completed_at").is_none());
+
+    let shown = completed_at
+        .with_timezone(&chrono::Local)
+        .format("%Y-%m-%d %H:%M")
+        .to_string();
+    todo(dir.path())
+        .arg("list")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "1: [Medium] [Done] Renew passport\n   ✔ Completed {shown}\n2: [Medium] Book flights\n"
+        )));
+    todo(dir.path())
+        .arg("stats")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Completed this week: 1"));
+
+    // Completing again keeps the original timestamp
+    todo(dir.path())
+        .args(["complete", "1"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Task already complete: 'Renew passport'",
+        ));
+    assert_eq!(
+        stored_tasks(dir.path())[0]["completed_at"],
+        tasks[0]["completed_at"]
+    );
+}
+
+#[test]
+fn clear_done_removes_or_archives_completed_tasks() {
+    let dir = tempdir().unwrap();
+    let reset = || {
+        fs::remove_file(dir.path().join("todo.json")).ok();
+        std::fs::write(
+            dir.path().join("todo.txt"),
+            "[High] [Done] Shipped\n[Low] Pending\n[Medium] [Done:2024-01-01T00:00:00+00:00] Filed\n",
+        )
+        .unwrap();
+    };
+
+    reset();
+    todo(dir.path())
+        .arg("clear-done")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Removed 2 completed task(s)."));
+    assert_eq!(descriptions(&stored_tasks(dir.path())), vec!["Pending"]);
+    todo(dir.path())
+        .arg("clear-done")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No completed tasks to clear."));
+
+    reset();
+    todo(dir.path())
+        .args(["clear-done", "--archive"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Archived 2 completed task(s) to todo_done.json",
+        ));
+    reset();
+    todo(dir.path())
+        .args(["clear-done", "--archive"])
+        .assert()
+        .success();
+
+    assert_eq!(descriptions(&stored_tasks(dir.path())), vec!["Pending"]);
+    let archived: Vec<Value> =
+        serde_json::from_str(&fs::read_to_string(dir.path().join("todo_done.json")).unwrap())
+            .unwrap();
+    assert_eq!(
+        descriptions(&archived),
+        vec!["Shipped", "Filed", "Shipped", "Filed"]
+    );
+    assert!(archived.iter().all(|task| task["status"] == "done"));
+}
+
+#[test]
+fn stats_summarizes_priority_status_and_overdue() {
+    let dir = tempdir().unwrap();
+
+    todo(dir.path())
+        .arg("stats")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No tasks yet."))
+        .stdout(predicate::str::contains("%").not());
+
+    fs::remove_file(dir.path().join("todo.json")).unwrap();
+    std::fs::write(
+        dir.path().join("todo.txt"),
+        "[High] [Due:2001-01-01] Overdue\n[High] [Done] [Due:2001-01-01] Done late\n[Medium] Plain\n[Low] [Due:2999-01-01] Later\n",
+    )
+    .unwrap();
+    todo(dir.path())
+        .arg("stats")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Total tasks: 4\nPending: 3 (75.0%)\nDone: 1 (25.0%)\nHigh: 2 (50.0%)\nMedium: 1 (25.0%)\nLow: 1 (25.0%)\nOverdue: 1 (25.0%)\n",
+        ));
+}
+
+#[test]
+fn streaks_count_consecutive_completion_days() {
+    let dir = tempdir().unwrap();
+    let done = |id: u64, completed_at: String| {
+        json!({ "id": id, "description": format!("Task {id}"), "priority": "Medium", "status": "done",
+                "completed_at": completed_at, "total_time_seconds": 0 })
+    };
+    let days_ago = |days: i64| (chrono::Utc::now() - chrono::Duration::days(days)).to_rfc3339();
+
+    // Fourteen days in a row, long ago, and one task still open
+    let mut tasks: Vec<Value> = (1..=14)
+        .map(|day| done(day, format!("2024-03-{day:02}T12:00:00Z")))
+        .collect();
+    tasks.push(
+        json!({ "id": 15, "description": "Task 15", "priority": "Medium",
+                       "status": "pending", "total_time_seconds": 0 }),
+    );
+    fs::write(
+        dir.path().join("todo.json"),
+        serde_json::to_string(&tasks).unwrap(),
+    )
+    .unwrap();
+    let stats_path = dir.path().join("todo_stats.json");
+    todo(dir.path())
+        .arg("stats")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Streak broken: 0 days (best was 14)",
+        ));
+    // Stats only reads; completing a task records the best
+    assert!(!stats_path.exists());
+    todo(dir.path()).args(["complete", "15"]).assert().success();
+    let history: Value = serde_json::from_str(&fs::read_to_string(&stats_path).unwrap()).unwrap();
+    assert_eq!(history["longest_streak"], 14);
+
+    // The best survives the old tasks being cleared
+    fs::write(
+        dir.path().join("todo.json"),
+        serde_json::to_string(&[done(15, days_ago(5))]).unwrap(),
+    )
+    .unwrap();
+    todo(dir.path())
+        .arg("stats")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Streak broken: 0 days (best was 14)",
+        ));
+
+    // Yesterday keeps a streak alive
+    tasks = vec![
+        done(15, days_ago(3)),
+        done(16, days_ago(2)),
+        done(17, days_ago(1)),
+    ];
+    fs::write(
+        dir.path().join("todo.json"),
+        serde_json::to_string(&tasks).unwrap(),
+    )
+    .unwrap();
+    todo(dir.path())
+        .arg("stats")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("🔥 3-day streak"));
+
+    tasks.push(done(18, days_ago(0)));
+    fs::write(
+        dir.path().join("todo.json"),
+        serde_json::to_string(&tasks).unwrap(),
+    )
+    .unwrap();
+    todo(dir.path())
+        .arg("stats")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("🔥 4-day streak"));
+}
+
+#[test]
+fn score_sorting_overrides_priority() {
+    let dir = tempdir().unwrap();
+
+    todo(dir.path())
+        .args(["add", "Default high", "--priority", "high"])
+        .assert()
+        .success();
+    todo(dir.path())
+        .args(["add", "Nudged low", "--priority", "low", "--score", "9"])
+        .assert()
+        .success();
+    todo(dir.path())
+        .args(["add", "Plain medium"])
+        .assert()
+        .success();
+    todo(dir.path())
+        .args(["add", "Barely high", "--priority", "high", "--score", "3"])
+        .assert()
+        .success();
+    todo(dir.path())
+        .args(["add", "Too much", "--score", "11"])
+        .assert()
+        .code(2);
+    todo(dir.path())
+        .args(["edit", "3", "--score", "6"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("score:  5 -> 6"));
+
+    let output = todo(dir.path())
+        .args(["list", "--sort-by", "score"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let listing = String::from_utf8(output).unwrap();
+    let order: Vec<&str> = listing
+        .lines()
+        .filter(|line| line.contains(": ["))
+        .collect();
+    assert_eq!(
+        order,
+        [
+            "2: [Low] [Score 9] Nudged low",
+            "1: [High] Default high",
+            "3: [Medium] [Score 6] Plain medium",
+            "4: [High] [Score 3] Barely high",
+        ]
+    );
+
+    let tasks = stored_tasks(dir.path());
+    assert_eq!(tasks[0].get("priority_score"), None);
+    assert_eq!(tasks[1]["priority_score"], 9);
+    assert_eq!(tasks[2]["description"], "Plain medium");
+}
@@ -0,0 +1,526 @@
+mod common;
+
+use std::fs;
+
+use predicates::prelude::*;
+use tempfile::tempdir;
+
+#[test]
+fn counts_characters_per_line() {
+    let dir = tempdir().unwrap();
+    let input = dir.path().join("input.txt");
+    fs::write(&input, "hello world\nabc\n").unwrap();
+
+    common::bin("word_counter")
+        .arg("--input")
+        .arg(&input)
+        .arg("--summary")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Line 1: hello world - Char count: 10",
+        ))
+        .stdout(predicate::str::contains("Line 2: abc - Char count: 3"))
+        .stdout(predicate::str::contains("Total lines: 2, Total chars: 13"));
+}
+
+#[test]
+fn json_output_lists_every_line() {
+    let dir = tempdir().unwrap();
+    let input = dir.path().join("input.txt");
+    fs::write(&input, "one\ntwo\n").unwrap();
+
+    let output = common::bin("word_counter")
+        .arg("--input")
+        .arg(&input)
+        .args(["--format", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let file = input.to_str().unwrap();
+    assert_eq!(
+        json["results"],
+        serde_json::json!([
+            { "file": file, "line_number": 1, "content": "one", "char_count": 3 },
+            { "file": file, "line_number": 2, "content": "two", "char_count": 3 },
+        ])
+    );
+    assert_eq!(json["summary"]["total_lines"], 2);
+    assert_eq!(json["summary"]["total_chars"], 6);
+}
+
+#[test]
+fn json_output_streams_results_of_every_file() {
+    let dir = tempdir().unwrap();
+    let empty = dir.path().join("empty.txt");
+    fs::write(&empty, "").unwrap();
+    let output_file = dir.path().join("out.json");
+
+    common::bin("word_counter")
+        .arg("--input")
+        .arg(&empty)
+        .args(["--format", "json", "--output"])
+        .arg(&output_file)
+        .assert()
+        .success()
+        .stdout("");
+    let json: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&output_file).unwrap()).unwrap();
+    assert_eq!(json["results"], serde_json::json!([]));
+    assert_eq!(json["summary"]["total_lines"], 0);
+
+    let inputs: Vec<_> = (0..4)
+        .map(|i| {
+            let path = dir.path().join(format!("input{i}.txt"));
+            fs::write(&path, "line\n".repeat(500)).unwrap();
+            path
+        })
+        .collect();
+    common::bin("word_counter")
+        .arg("--input")
+        .args(&inputs)
+        .args(["--format", "json", "--output"])
+        .arg(&output_file)
+        .assert()
+        .success();
+    let json: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&output_file).unwrap()).unwrap();
+    let results = json["results"].as_array().unwrap();
+    assert_eq!(results.len(), 2000);
+    for input in &inputs {
+        let file = input.to_str().unwrap();
+        assert_eq!(
+            results
+                .iter()
+                .filter(|result| result["file"] == file)
+                .count(),
+            500
+        );
+    }
+    assert_eq!(json["summary"]["total_chars"], 8000);
+}
+
+#[test]
+fn verbose_reports_each_file_on_stderr() {
+    let dir = tempdir().unwrap();
+    let first = dir.path().join("first.txt");
+    let second = dir.path().join("second.txt");
+    fs::write(&first, "alpha\n").unwrap();
+    fs::write(&second, "beta\n").unwrap();
+
+    common::bin("word_counter")
+        .arg("--input")
+        .arg(&first)
+        .arg(&second)
+        .arg("--verbose")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(format!(
+            "Processing: {} (6 bytes)",
+            first.display()
+        )))
+        .stderr(predicate::str::contains(format!(
+            "Done: {} in",
+            first.display()
+        )))
+        .stderr(predicate::str::contains(format!(
+            "Processing: {} (5 bytes)",
+            second.display()
+        )))
+        .stdout(predicate::str::contains("Processing:").not());
+}
+
+#[test]
+fn plain_progress_is_written_to_stderr() {
+    let dir = tempdir().unwrap();
+    let first = dir.path().join("first.txt");
+    let second = dir.path().join("second.txt");
+    fs::write(&first, "alpha\n").unwrap();
+    fs::write(&second, "beta\n").unwrap();
+
+    let output = common::bin("word_counter")
+        .arg("--input")
+        .arg(&first)
+        .arg(&second)
+        .arg("--progress-stderr")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "Progress: 50% (1/2 files)\nProgress: 100% (2/2 files)\nProcessing complete\n"
+    );
+
+    // Plain progress is also the default when stdout is piped
+    common::bin("word_counter")
+        .env("HOME", dir.path())
+        .arg("--input")
+        .arg(&first)
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Progress: 100% (1/1 files)"))
+        .stderr(predicate::str::contains("\u{1b}[").not());
+}
+
+#[test]
+fn quiet_suppresses_progress() {
+    let dir = tempdir().unwrap();
+    let input = dir.path().join("input.txt");
+    fs::write(&input, "hello\n").unwrap();
+
+    common::bin("word_counter")
+        .arg("--input")
+        .arg(&input)
+        .arg("--quiet")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Char count: 5"))
+        .stderr("");
+
+    common::bin("word_counter")
+        .arg("--input")
+        .arg(&input)
+        .args(["--quiet", "--progress-stderr"])
+        .assert()
+        .code(2);
+}
+
+#[test]
+fn bar_style_none_hides_progress() {
+    let dir = tempdir().unwrap();
+    let input = dir.path().join("input.txt");
+    fs::write(&input, "hello\n").unwrap();
+
+    common::bin("word_counter")
+        .env("HOME", dir.path())
+        .arg("--input")
+        .arg(&input)
+        .args(["--bar-style", "none"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Char count: 5"))
+        .stderr("");
+
+    // A saved preference applies until overridden on the command line
+    common::bin("word_counter")
+        .env("HOME", dir.path())
+        .args(["config", "--bar-style", "none", "--bar-theme", "=>."])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(".wordcounter_config.toml"));
+    common::bin("word_counter")
+        .env("HOME", dir.path())
+        .arg("config")
+        .assert()
+        .success()
+        .stdout("bar_style = \"none\"\nbar_theme = \"=>.\"\n");
+    common::bin("word_counter")
+        .env("HOME", dir.path())
+        .arg("--input")
+        .arg(&input)
+        .assert()
+        .success()
+        .stderr("");
+    common::bin("word_counter")
+        .env("HOME", dir.path())
+        .arg("--input")
+        .arg(&input)
+        .args(["--bar-style", "minimal"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Processing complete"));
+
+    common::bin("word_counter")
+        .env("HOME", dir.path())
+        .args(["config", "--bar-theme", "##"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("expected 3 characters"));
+}
+
+#[test]
+fn char_thresholds_filter_lines() {
+    let dir = tempdir().unwrap();
+    let input = dir.path().join("input.txt");
+    // Lengths 1, 4, 8 and 12 without delimiters
+    fs::write(&input, "a\nabcd\nabcdefgh\nabcdefghijkl\n").unwrap();
+
+    common::bin("word_counter")
+        .arg("--input")
+        .arg(&input)
+        .args(["--min-chars", "4", "--max-chars", "8", "--summary"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Line 2: abcd - Char count: 4"))
+        .stdout(predicate::str::contains("Line 3: abcdefgh - Char count: 8"))
+        .stdout(predicate::str::contains("Char count: 1\n").not())
+        .stdout(predicate::str::contains("Char count: 12").not())
+        .stdout(predicate::str::contains(
+            "Total lines: 2, Total chars: 12, Average chars per line: 6.00, Filtered lines: 2",
+        ));
+
+    let output = common::bin("word_counter")
+        .arg("--input")
+        .arg(&input)
+        .args(["--min-chars", "5", "--format", "json", "--quiet"])
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let counts: Vec<u64> = json["results"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|line| line["char_count"].as_u64().unwrap())
+        .collect();
+    assert_eq!(counts, [8, 12]);
+
+    common::bin("word_counter")
+        .arg("--input")
+        .arg(&input)
+        .args(["--min-chars", "9", "--max-chars", "3"])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("must not be greater than"));
+}
+
+#[test]
+fn hash_reports_sha256_of_contents() {
+    let dir = tempdir().unwrap();
+    let input = dir.path().join("input.txt");
+    fs::write(&input, "hello world\n").unwrap();
+
+    let hash_of = |format: &str| {
+        common::bin("word_counter")
+            .arg("--input")
+            .arg(&input)
+            .args(["--hash", "--quiet", "--format", format])
+            .output()
+            .unwrap()
+            .stdout
+    };
+
+    let json: serde_json::Value = serde_json::from_slice(&hash_of("json")).unwrap();
+    let original = json["files"][0]["content_hash"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    // sha256sum of "hello world\n"
+    assert_eq!(
+        original,
+        "a948904f2f0f479b8f8197694b30184b0d2ed1c1cd2a1ec0fb85d299a192a447"
+    );
+    assert_eq!(json["files"][0]["file"], input.to_str().unwrap());
+
+    let csv = String::from_utf8(hash_of("csv")).unwrap();
+    assert_eq!(
+        csv,
+        format!("line_number,content,char_count,content_hash\n1,hello world,10,{original}\n")
+    );
+
+    fs::write(&input, "hello world!\n").unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&hash_of("json")).unwrap();
+    assert_ne!(json["files"][0]["content_hash"].as_str().unwrap(), original);
+
+    // Without --hash the output stays as before
+    let output = common::bin("word_counter")
+        .arg("--input")
+        .arg(&input)
+        .args(["--quiet", "--format", "json"])
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(json.get("files").is_none());
+}
+
+/// JSON output of word_counter for one file, as a value
+fn json_counts(input: &std::path::Path, extra_args: &[&str]) -> serde_json::Value {
+    let output = common::bin("word_counter")
+        .arg("--input")
+        .arg(input)
+        .args(["--format", "json", "--quiet"])
+        .args(extra_args)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+proptest::proptest! {
+    #![proptest_config(proptest::prelude::ProptestConfig::with_cases(24))]
+
+    #[test]
+    fn chunked_counts_match_sequential(content in "[a-zé \t\r\n]{0,300}", chunk_size in 1usize..64) {
+        let dir = tempdir().unwrap();
+        let input = dir.path().join("input.txt");
+        fs::write(&input, &content).unwrap();
+
+        let sequential = json_counts(&input, &[]);
+        let chunked = json_counts(&input, &["--chunk-size", &chunk_size.to_string()]);
+        proptest::prop_assert_eq!(&chunked, &sequential);
+        proptest::prop_assert_eq!(
+            sequential["summary"]["total_lines"].as_u64().unwrap() as usize,
+            content.lines().count()
+        );
+    }
+}
+
+#[test]
+fn char_breakdown_counts_categories() {
+    let dir = tempdir().unwrap();
+    let input = dir.path().join("input.txt");
+    // 8 letters, 3 digits, 3 spaces, 3 punctuation, 1 other
+    fs::write(&input, "Hello, abc 123!\n§ ?\n").unwrap();
+
+    common::bin("word_counter")
+        .arg("--input")
+        .arg(&input)
+        .args(["--char-breakdown", "--summary"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Line 1: Hello, abc 123! - Char count: 13 - Letters: 8, Digits: 3, Whitespace: 2, Punctuation: 2, Other: 0",
+        ))
+        .stdout(predicate::str::contains(
+            "Character breakdown: Letters: 8, Digits: 3, Whitespace: 3, Punctuation: 3, Other: 1",
+        ));
+
+    fs::write(&input, "a1 ☃.\n").unwrap();
+    let output = common::bin("word_counter")
+        .arg("--input")
+        .arg(&input)
+        .args(["--char-breakdown", "--format", "json", "--quiet"])
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let expected = serde_json::json!({ "letters": 1, "digits": 1, "whitespace": 1, "punctuation": 1, "other": 1 });
+    assert_eq!(json["results"][0]["char_breakdown"], expected);
+    assert_eq!(json["summary"]["char_breakdown"], expected);
+}
+
+#[test]
+fn camel_case_and_word_delimiters_split_words() {
+    let dir = tempdir().unwrap();
+    let input = dir.path().join("input.txt");
+    fs::write(&input, "camelCaseWord well-known_name\n").unwrap();
+
+    let camel = json_counts(&input, &["--camel-case"]);
+    assert_eq!(
+        camel["results"][0]["words"],
+        serde_json::json!(["camel", "Case", "Word", "well-known_name"])
+    );
+
+    let delimited = json_counts(&input, &["--word-delimiters", "_-"]);
+    assert_eq!(
+        delimited["results"][0]["words"],
+        serde_json::json!(["camelCaseWord", "well", "known", "name"])
+    );
+    assert_eq!(delimited["summary"]["total_words"], 4);
+
+    common::bin("word_counter")
+        .arg("--input")
+        .arg(&input)
+        .args([
+            "--camel-case",
+            "--word-delimiters",
+            "-_",
+            "--summary",
+            "--quiet",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("- Char count: 28 - Word count: 6"))
+        .stdout(predicate::str::contains("Total words: 6"));
+}
+
+#[test]
+fn detect_language_recognises_english() {
+    let dir = tempdir().unwrap();
+    let input = dir.path().join("english.txt");
+    fs::write(
+        &input,
+        "The quick brown fox jumps over the lazy dog, and then it runs into the forest.\n\
+         It was the best of times, it was the worst of times, that is what they said.\n\
+         Nothing is certain in this world except for the things that we are willing to wait for.\n",
+    )
+    .unwrap();
+
+    let json = json_counts(&input, &["--detect-language"]);
+    assert_eq!(json["files"][0]["language"]["language"], "English");
+    assert!(json["files"][0]["language"]["confidence"].as_f64().unwrap() > 30.0);
+
+    fs::write(
+        &input,
+        "Der Hund und die Katze schlafen in der Sonne, weil es ein schöner Tag ist.\n",
+    )
+    .unwrap();
+    common::bin("word_counter")
+        .arg("--input")
+        .arg(&input)
+        .args(["--detect-language", "--quiet"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("- Language: German ("));
+}
+
+#[test]
+fn readability_scores_known_sentences() {
+    let dir = tempdir().unwrap();
+    let input = dir.path().join("easy.txt");
+    // 6 words, 1 sentence, 6 syllables: 206.835 - 1.015 * 6 - 84.6 * 1
+    fs::write(&input, "The cat sat on the mat.\n").unwrap();
+
+    let json = json_counts(&input, &["--readability"]);
+    let score = json["files"][0]["readability"]["score"].as_f64().unwrap();
+    assert!((score - 116.145).abs() < 1e-9);
+    assert_eq!(json["files"][0]["readability"]["level"], "Very Easy");
+    assert_eq!(json["summary"]["readability"]["level"], "Very Easy");
+
+    // 5 words, 1 sentence, 21 syllables
+    fs::write(
+        &input,
+        "Readability calculation requires considerable concentration.\n",
+    )
+    .unwrap();
+    common::bin("word_counter")
+        .arg("--input")
+        .arg(&input)
+        .args(["--readability", "--summary", "--quiet"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "- Readability: -153.6 (Very Confusing)",
+        ))
+        .stdout(predicate::str::contains(
+            "\nReadability: -153.6 (Very Confusing)",
+        ));
+}
+
+#[test]
+fn hidden_files_are_skipped_unless_requested() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("visible.txt"), "shown\n").unwrap();
+    fs::write(dir.path().join(".hidden.txt"), "secret\n").unwrap();
+    fs::create_dir(dir.path().join(".cache")).unwrap();
+    fs::write(dir.path().join(".cache").join("inside.txt"), "cached\n").unwrap();
+
+    common::bin("word_counter")
+        .arg("--input")
+        .arg(dir.path())
+        .args(["--recursive", "--quiet"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("shown"))
+        .stdout(predicate::str::contains("secret").not())
+        .stdout(predicate::str::contains("cached").not());
+
+    common::bin("word_counter")
+        .arg("--input")
+        .arg(dir.path())
+        .args(["--recursive", "--quiet", "--include-hidden"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("shown"))
+        .stdout(predicate::str::contains("secret"))
+        .stdout(predicate::str::contains("cached"));
+}
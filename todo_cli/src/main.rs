@@ -1,136 +1,1975 @@
+use std::collections::BTreeSet;
 use std::fs;
-use std::fs::File;
 use std::io;
-use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
 
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, Utc, Weekday};
+use clap::{Parser, Subcommand, ValueEnum};
+use colored::Colorize;
+use common::file_utils::{read_to_string_or_empty, write_atomic};
+use serde::{Deserialize, Serialize};
+
+#[derive(Parser)]
+#[command(name = "todo_cli")]
+#[command(about = "A mini to-do list CLI")]
+#[command(
+    after_help = "Without a command, starts an interactive session that accepts the same commands."
+)]
+struct Args {
+    /// Directory holding the task list (defaults to the platform data directory)
+    #[arg(long, env = "TODO_DATA_DIR", global = true)]
+    data_dir: Option<PathBuf>,
+
+    /// Task list to use instead of todo.json in the data directory, e.g. to keep
+    /// separate work and personal lists
+    #[arg(long, env = "TODO_FILE", global = true, value_name = "PATH")]
+    file: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+/// One line typed into the interactive session
+#[derive(Parser)]
+#[command(name = "todo_cli", no_binary_name = true, disable_version_flag = true)]
+struct InteractiveLine {
+    #[command(subcommand)]
+    command: Command,
+}
+
+const TODO_FILE_NAME: &str = "todo.json";
+/// Task file of earlier versions, migrated to `todo.json` on first run
+const LEGACY_TODO_FILE_NAME: &str = "todo.txt";
+
+/// `~/.local/share/todo_cli` on Linux, `%APPDATA%\todo_cli` on Windows
+fn default_data_dir() -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("todo_cli")
+}
+
+#[derive(Subcommand)]
 enum Command {
-    Add,
-    List,
+    /// Add a task
+    Add {
+        /// What needs doing
+        #[arg(required = true)]
+        description: Vec<String>,
+
+        #[arg(short, long, value_enum, default_value_t = TaskPriority::Medium)]
+        priority: TaskPriority,
+
+        /// Finer priority from 1 to 10, used by `list --sort score`
+        #[arg(long, value_name = "N", value_parser = clap::value_parser!(u8).range(1..=10))]
+        score: Option<u8>,
+
+        /// Effort in story points
+        #[arg(long, value_name = "POINTS")]
+        estimate: Option<u32>,
+
+        /// Repeat the task: daily, weekly:<weekday>, monthly:<1-31> or every:<days>
+        #[arg(long, value_name = "SPEC", value_parser = Recurrence::parse)]
+        recur: Option<Recurrence>,
+
+        /// Due date as YYYY-MM-DD or an RFC 3339 timestamp
+        #[arg(long, value_name = "DATE", value_parser = parse_due_date)]
+        due: Option<NaiveDate>,
+    },
+    /// List tasks with their numbers
+    List(ListOptions),
+    /// List tasks with a due date, soonest first, flagging overdue ones
     Scheduled,
-    Complete,
+    /// Find tasks whose description contains a keyword, ignoring case
+    Search {
+        #[arg(required = true)]
+        keyword: Vec<String>,
+    },
+    /// Mark a task, or a selection of tasks, as done
+    Complete {
+        /// Task number as shown by `list`
+        #[arg(required_unless_present_any = ["all", "ids"], conflicts_with_all = ["all", "ids"])]
+        id: Option<usize>,
+
+        #[command(flatten)]
+        selection: Selection,
+
+        /// What was done, up to 500 characters
+        #[arg(long, value_name = "TEXT")]
+        note: Option<String>,
+    },
+    /// Replace the description, score or estimate of a task, keeping everything else
+    Edit {
+        /// Task number as shown by `list`
+        id: usize,
+
+        /// The new description
+        #[arg(required_unless_present_any = ["score", "estimate"])]
+        description: Vec<String>,
+
+        /// The new priority score, from 1 to 10
+        #[arg(long, value_name = "N", value_parser = clap::value_parser!(u8).range(1..=10))]
+        score: Option<u8>,
+
+        /// The new effort in story points
+        #[arg(long, value_name = "N")]
+        estimate: Option<u32>,
+    },
+    /// Remove a task, or a selection of tasks
+    Delete {
+        /// Task number as shown by `list`
+        #[arg(required_unless_present_any = ["all", "ids"], conflicts_with_all = ["all", "ids"])]
+        id: Option<usize>,
+
+        #[command(flatten)]
+        selection: Selection,
+    },
+    /// Remove every completed task
+    ClearDone {
+        /// Move them to todo_done.json instead of deleting them
+        #[arg(long)]
+        archive: bool,
+    },
+    /// Restore the task list from before the last change
+    Undo,
+    /// Reapply the change reverted by the last undo
+    Redo,
+    /// Show completion progress, streak, estimates and tracked time
+    Stats {
+        /// Number of tasks to complete today
+        #[arg(long, value_name = "N")]
+        goal: Option<usize>,
+
+        /// List tasks by time spent
+        #[arg(long)]
+        time: bool,
+    },
+    /// Start the timer of a task
+    Start { id: usize },
+    /// Stop the timer of a task and add the elapsed time to it
+    Stop { id: usize },
+    /// Add tasks from a plain text, Markdown or Org file
+    Import {
+        file: PathBuf,
+
+        /// Defaults to the file extension, or plain text
+        #[arg(long, value_enum)]
+        format: Option<ImportFormat>,
+    },
+    /// Write the tasks to a file for other tools
+    Export {
+        #[arg(long, value_enum)]
+        format: ExportFormat,
+
+        /// Defaults to todo_export_<timestamp>.<ext> in the current directory
+        #[arg(short, long, value_name = "PATH")]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(clap::Args)]
+struct ListOptions {
+    /// Only show completed tasks, with their notes
+    #[arg(long)]
+    completed: bool,
+
+    /// Only show tasks whose estimate lies in the range, e.g. 2..5, 3.. or ..8
+    #[arg(long, value_name = "MIN..MAX", value_parser = parse_estimate_range)]
+    estimate_filter: Option<(u32, u32)>,
+
+    /// Only show tasks with this priority
+    #[arg(short, long, value_enum)]
+    priority: Option<TaskPriority>,
+
+    /// Only show tasks tagged @TAG
+    #[arg(long, value_parser = parse_tag)]
+    tag: Option<String>,
+
+    /// Order tasks by this key instead of file order
+    #[arg(long, visible_alias = "sort-by", value_enum)]
+    sort: Option<SortKey>,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum SortKey {
+    /// High, then Medium, then Low
+    Priority,
+    /// Soonest due date first, undated tasks last
+    Due,
+    /// Highest priority score first; tasks without one score by their priority
+    Score,
+}
+
+/// Source formats understood by `import`
+#[derive(Clone, Copy, ValueEnum)]
+enum ImportFormat {
+    /// One task per line
+    #[value(alias = "txt")]
+    Plain,
+    /// `- [ ] task` is pending, `- [x] task` is completed
+    #[value(alias = "md")]
+    Markdown,
+    /// `TODO task` / `DONE task` headlines
+    Org,
+}
+
+impl ImportFormat {
+    /// Guesses the format from the file extension, falling back to plain text
+    fn from_path(path: &Path) -> ImportFormat {
+        path.extension()
+            .and_then(|extension| extension.to_str())
+            .and_then(|extension| ImportFormat::from_str(extension, true).ok())
+            .unwrap_or(ImportFormat::Plain)
+    }
+
+    /// Returns `(description, done)` for each task line, skipping everything else
+    fn parse_tasks(self, contents: &str) -> Vec<(String, bool)> {
+        contents
+            .lines()
+            .map(str::trim)
+            .filter_map(|line| match self {
+                ImportFormat::Plain => (!line.is_empty()).then(|| (line.to_string(), false)),
+                ImportFormat::Markdown => {
+                    let item = line
+                        .strip_prefix("- ")
+                        .or_else(|| line.strip_prefix("* "))?;
+                    if let Some(text) = item.strip_prefix("[ ]") {
+                        Some((text.trim().to_string(), false))
+                    } else {
+                        let text = item
+                            .strip_prefix("[x]")
+                            .or_else(|| item.strip_prefix("[X]"))?;
+                        Some((text.trim().to_string(), true))
+                    }
+                }
+                ImportFormat::Org => {
+                    let headline = line.trim_start_matches('*').trim_start();
+                    if let Some(text) = headline.strip_prefix("TODO ") {
+                        Some((text.trim().to_string(), false))
+                    } else {
+                        headline
+                            .strip_prefix("DONE ")
+                            .map(|text| (text.trim().to_string(), true))
+                    }
+                }
+            })
+            .filter(|(description, _)| !description.is_empty())
+            .collect()
+    }
 }
 
+/// Target formats of `export`
+#[derive(Clone, Copy, ValueEnum)]
+enum ExportFormat {
+    /// iCalendar file with one all-day event per task with a due date
+    #[value(alias = "ics")]
+    Ical,
+    /// Spreadsheet-friendly table with one row per task
+    Csv,
+    /// Checklist of `- [ ]` items for notes and issue trackers
+    #[value(alias = "md")]
+    Markdown,
+}
+
+impl ExportFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Ical => "ics",
+            ExportFormat::Csv => "csv",
+            ExportFormat::Markdown => "md",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, ValueEnum, Serialize, Deserialize)]
 enum TaskPriority {
     Low,
     Medium,
     High,
 }
 
-fn todo_command(command: Command, task: Option<String>, priority: Option<TaskPriority>) {
-    match command {
-        Command::Add => {
-            if let Some(task_desc) = task {
-                let task_priority = match priority {
-                    Some(TaskPriority::Low) => "Low",
-                    Some(TaskPriority::Medium) => "Medium",
-                    Some(TaskPriority::High) => "High",
-                    None => "Medium",
+impl TaskPriority {
+    fn label(self) -> &'static str {
+        match self {
+            TaskPriority::Low => "Low",
+            TaskPriority::Medium => "Medium",
+            TaskPriority::High => "High",
+        }
+    }
+
+    /// `[High]` in red, `[Medium]` in yellow, `[Low]` in green. Plain when
+    /// stdout isn't a terminal or `NO_COLOR` is set.
+    fn colored_label(self) -> String {
+        let label = format!("[{}]", self.label());
+        match self {
+            TaskPriority::Low => label.green(),
+            TaskPriority::Medium => label.yellow(),
+            TaskPriority::High => label.red(),
+        }
+        .to_string()
+    }
+
+    /// Score of tasks without their own `priority_score`
+    fn default_score(self) -> u8 {
+        match self {
+            TaskPriority::Low => 2,
+            TaskPriority::Medium => 5,
+            TaskPriority::High => 8,
+        }
+    }
+
+    /// Higher is more urgent
+    fn rank(self) -> u8 {
+        match self {
+            TaskPriority::Low => 0,
+            TaskPriority::Medium => 1,
+            TaskPriority::High => 2,
+        }
+    }
+
+    fn from_label(label: &str) -> Option<TaskPriority> {
+        match label {
+            "Low" => Some(TaskPriority::Low),
+            "Medium" => Some(TaskPriority::Medium),
+            "High" => Some(TaskPriority::High),
+            _ => None,
+        }
+    }
+}
+
+/// How often a task comes back once completed. Stored as its spec, e.g. `weekly:Monday`.
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+enum Recurrence {
+    Daily,
+    /// `day` counts from 0 = Monday
+    Weekly {
+        day: u8,
+    },
+    Monthly {
+        day_of_month: u8,
+    },
+    EveryNDays(u32),
+}
+
+const WEEKDAY_NAMES: [&str; 7] = [
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+    "Sunday",
+];
+
+impl Recurrence {
+    /// Parses `daily`, `weekly:Monday`, `monthly:15` or `every:7`
+    fn parse(spec: &str) -> Result<Recurrence, String> {
+        let invalid = || {
+            format!(
+                "Invalid recurrence '{}'. Use daily, weekly:<weekday>, monthly:<1-31> or every:<days>.",
+                spec
+            )
+        };
+        let (kind, value) = spec.split_once(':').unwrap_or((spec, ""));
+        match kind.to_lowercase().as_str() {
+            "daily" if value.is_empty() => Ok(Recurrence::Daily),
+            "weekly" => {
+                let day = value.parse::<Weekday>().map_err(|_| invalid())?;
+                Ok(Recurrence::Weekly {
+                    day: day.num_days_from_monday() as u8,
+                })
+            }
+            "monthly" => match value.parse::<u8>() {
+                Ok(day_of_month @ 1..=31) => Ok(Recurrence::Monthly { day_of_month }),
+                _ => Err(invalid()),
+            },
+            "every" => match value.parse::<u32>() {
+                Ok(days) if days > 0 => Ok(Recurrence::EveryNDays(days)),
+                _ => Err(invalid()),
+            },
+            _ => Err(invalid()),
+        }
+    }
+
+    /// The first due date after `due` on this schedule
+    fn next_due(self, due: NaiveDate) -> NaiveDate {
+        match self {
+            Recurrence::Daily => due + Duration::days(1),
+            Recurrence::Weekly { day } => {
+                let current = due.weekday().num_days_from_monday() as i64;
+                let ahead = (day as i64 - current).rem_euclid(7);
+                due + Duration::days(if ahead == 0 { 7 } else { ahead })
+            }
+            Recurrence::Monthly { day_of_month } => {
+                // Short months clamp to their last day, e.g. monthly:31 falls on 30 April
+                let in_month = |year, month| {
+                    (1..=day_of_month as u32)
+                        .rev()
+                        .find_map(|day| NaiveDate::from_ymd_opt(year, month, day))
+                        .unwrap_or(due)
                 };
+                let this_month = in_month(due.year(), due.month());
+                if this_month > due {
+                    this_month
+                } else if due.month() == 12 {
+                    in_month(due.year() + 1, 1)
+                } else {
+                    in_month(due.year(), due.month() + 1)
+                }
+            }
+            Recurrence::EveryNDays(days) => due + Duration::days(days as i64),
+        }
+    }
+}
+
+impl TryFrom<String> for Recurrence {
+    type Error = String;
+
+    fn try_from(spec: String) -> Result<Recurrence, String> {
+        Recurrence::parse(&spec)
+    }
+}
+
+impl From<Recurrence> for String {
+    fn from(recurrence: Recurrence) -> String {
+        recurrence.to_string()
+    }
+}
+
+impl std::fmt::Display for Recurrence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Recurrence::Daily => write!(f, "daily"),
+            Recurrence::Weekly { day } => write!(f, "weekly:{}", WEEKDAY_NAMES[*day as usize % 7]),
+            Recurrence::Monthly { day_of_month } => write!(f, "monthly:{}", day_of_month),
+            Recurrence::EveryNDays(days) => write!(f, "every:{}", days),
+        }
+    }
+}
 
-                let mut file = fs::OpenOptions::new()
-                    .append(true)
-                    .open("todo.txt")
-                    .expect("Could not open todo.txt");
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum TaskStatus {
+    Pending,
+    Done,
+}
 
-                use std::io::Write;
+/// One task as stored in `todo.json`. The `id` stays with the task for its
+/// whole life, while the numbers shown by `list` follow the current order.
+#[derive(Serialize, Deserialize)]
+struct TodoEntry {
+    id: u64,
+    description: String,
+    priority: TaskPriority,
+    /// 1 to 10, finer than `priority`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    priority_score: Option<u8>,
+    status: TaskStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    due_date: Option<NaiveDate>,
+    /// `@word` tokens of the description, lowercased
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    estimate: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    recurrence: Option<Recurrence>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    created_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    completed_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    completion_note: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    started_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    total_time_seconds: u64,
+}
 
-                writeln!(file, "[{}] {}", task_priority, task_desc)
-                    .expect("Could not write to todo.txt");
+fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|timestamp| timestamp.with_timezone(&Utc))
+}
 
-                println!(
-                    "Added task: '{}' with priority: {}",
-                    task_desc, task_priority
-                );
+impl TodoEntry {
+    /// A pending task. Its `id` is assigned when it is added to the list.
+    fn new(priority: TaskPriority, description: &str) -> TodoEntry {
+        TodoEntry {
+            id: 0,
+            description: description.to_string(),
+            priority,
+            priority_score: None,
+            status: TaskStatus::Pending,
+            due_date: None,
+            tags: parse_tags(description),
+            estimate: None,
+            recurrence: None,
+            created_at: None,
+            completed_at: None,
+            completion_note: None,
+            started_at: None,
+            total_time_seconds: 0,
+        }
+    }
+
+    /// Parses a line of the `todo.txt` format. Metadata lives in leading bracket markers:
+    /// `[High] [Done:<time>] [Note:<text>] [Started:<time>] [Time:<seconds>] [Est:<points>]
+    /// [Due:<date>] [Recur:<spec>] [Created:<time>] description`
+    fn parse_legacy_line(line: &str) -> TodoEntry {
+        let mut entry = TodoEntry::new(TaskPriority::Medium, "");
+
+        // Markers are consumed until the first bracket that isn't one, so
+        // descriptions like `[Scheduled] Dentist` are kept as written
+        let mut rest = line.trim();
+        while let Some(inner) = rest.strip_prefix('[')
+            && let Some(end) = find_marker_end(inner)
+        {
+            let marker = &inner[..end];
+            if let Some(priority) = TaskPriority::from_label(marker) {
+                entry.priority = priority;
+            } else if marker == "Done" {
+                entry.status = TaskStatus::Done;
+            } else if let Some(value) = marker.strip_prefix("Done:") {
+                entry.status = TaskStatus::Done;
+                entry.completed_at = parse_timestamp(value);
+            } else if let Some(value) = marker.strip_prefix("Note:") {
+                entry.completion_note = Some(unescape_marker(value));
+            } else if let Some(value) = marker.strip_prefix("Started:") {
+                entry.started_at = parse_timestamp(value);
+            } else if let Some(value) = marker.strip_prefix("Time:")
+                && let Ok(seconds) = value.parse()
+            {
+                entry.total_time_seconds = seconds;
+            } else if let Some(value) = marker.strip_prefix("Est:")
+                && let Ok(points) = value.parse()
+            {
+                entry.estimate = Some(points);
+            } else if let Some(value) = marker.strip_prefix("Due:")
+                && let Ok(due_date) = value.parse()
+            {
+                entry.due_date = Some(due_date);
+            } else if let Some(value) = marker.strip_prefix("Recur:")
+                && let Ok(recurrence) = Recurrence::parse(value)
+            {
+                entry.recurrence = Some(recurrence);
+            } else if let Some(value) = marker.strip_prefix("Created:") {
+                entry.created_at = parse_timestamp(value);
             } else {
-                println!("No task description provided.");
-            }
-        }
-        Command::List => {
-            println!("Listing all tasks...");
-            let todos = File::open("todo.txt").expect("Could not open todo.txt");
-            let reader = BufReader::new(todos);
-            for (index, line) in reader.lines().enumerate() {
-                let line = line.expect("Could not read line");
-                println!("{}: {}", index + 1, line);
-            }
-        }
-        Command::Scheduled => {
-            println!("Listing scheduled tasks...");
-            let todos = File::open("todo.txt").expect("Could not open todo.txt");
-            let reader = BufReader::new(todos);
-            for (index, line) in reader.lines().enumerate() {
-                let line = line.expect("Could not read line");
-                if line.contains("[Scheduled]") {
-                    println!("{}: {}", index + 1, line);
+                break;
+            }
+            rest = inner[end + 1..].trim_start();
+        }
+
+        entry.description = rest.to_string();
+        entry.tags = parse_tags(rest);
+        entry
+    }
+
+    fn is_done(&self) -> bool {
+        self.status == TaskStatus::Done
+    }
+
+    /// The line as shown to the user, without the time-tracking markers
+    fn display(&self) -> String {
+        let mut line = self.priority.colored_label();
+        if self.is_done() {
+            line.push_str(" [Done]");
+        }
+        if let Some(score) = self.priority_score {
+            line.push_str(&format!(" [Score {}]", score));
+        }
+        line.push(' ');
+        line.push_str(&highlight_tags(&self.description));
+        if let Some(points) = self.estimate {
+            line.push_str(&format!(" ({} pts)", points));
+        }
+        if let Some(recurrence) = self.recurrence {
+            line.push_str(&format!(" ♻ {}", recurrence));
+        }
+        if let Some(due_date) = self.due_date {
+            line.push_str(&format!(" (due {})", due_date));
+        }
+        line
+    }
+
+    /// `priority_score`, or the default score of the priority
+    fn score(&self) -> u8 {
+        self.priority_score
+            .unwrap_or_else(|| self.priority.default_score())
+    }
+
+    /// Pending and due before today
+    fn is_overdue(&self, today: NaiveDate) -> bool {
+        !self.is_done() && self.due_date.is_some_and(|due_date| due_date < today)
+    }
+
+    /// Marks the task done. A recurring task returns its next occurrence,
+    /// due one interval after this one (or after today if it had no due date).
+    fn complete(&mut self, now: DateTime<Utc>, note: Option<String>) -> Option<TodoEntry> {
+        self.status = TaskStatus::Done;
+        self.completed_at = Some(now);
+        self.completion_note = note;
+
+        let recurrence = self.recurrence?;
+        let base = self
+            .due_date
+            .unwrap_or_else(|| now.with_timezone(&Local).date_naive());
+        let mut next = TodoEntry::new(self.priority, &self.description);
+        next.priority_score = self.priority_score;
+        next.estimate = self.estimate;
+        next.recurrence = Some(recurrence);
+        next.due_date = Some(recurrence.next_due(base));
+        next.created_at = Some(now);
+        Some(next)
+    }
+
+    /// Recorded time plus the running timer, if any
+    fn tracked_seconds(&self) -> u64 {
+        let running = self
+            .started_at
+            .map(|started| (Utc::now() - started).num_seconds().max(0) as u64)
+            .unwrap_or(0);
+        self.total_time_seconds + running
+    }
+}
+
+/// Index of the `]` closing a marker, skipping `\]` escapes inside free text
+fn find_marker_end(inner: &str) -> Option<usize> {
+    let mut escaped = false;
+    for (index, c) in inner.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            ']' => return Some(index),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn unescape_marker(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            unescaped.extend(chars.next());
+        } else {
+            unescaped.push(c);
+        }
+    }
+    unescaped
+}
+
+const MAX_NOTE_LENGTH: usize = 500;
+
+/// Replaces control characters (newlines included) so a note stays on its task's line
+fn sanitize_note(note: &str) -> Result<String, String> {
+    let note: String = note
+        .chars()
+        .map(|c| if c.is_control() { ' ' } else { c })
+        .collect();
+    let note = note.trim();
+    if note.is_empty() {
+        return Err("The note must not be empty.".to_string());
+    }
+    if note.chars().count() > MAX_NOTE_LENGTH {
+        return Err(format!(
+            "The note is too long (max {} characters).",
+            MAX_NOTE_LENGTH
+        ));
+    }
+    Ok(note.to_string())
+}
+
+/// Resolves the 1-based task number shown by `list` to an index into the entries
+fn task_index(entries: &[TodoEntry], id: usize) -> Result<usize, String> {
+    if !(1..=entries.len()).contains(&id) {
+        return Err(format!("No task with number {}.", id));
+    }
+    Ok(id - 1)
+}
+
+/// Which tasks a bulk `complete`/`delete` applies to
+#[derive(clap::Args)]
+struct Selection {
+    /// Apply to every task (narrowed by --priority and --tag)
+    #[arg(long)]
+    all: bool,
+
+    /// Apply to these task numbers, e.g. 1,3,7
+    #[arg(long, value_delimiter = ',', value_name = "IDS")]
+    ids: Vec<usize>,
+
+    /// Only tasks with this priority
+    #[arg(long, value_enum)]
+    priority: Option<TaskPriority>,
+
+    /// Only tasks tagged @TAG
+    #[arg(long, value_parser = parse_tag)]
+    tag: Option<String>,
+
+    /// Show the affected tasks without changing anything
+    #[arg(long)]
+    dry_run: bool,
+}
+
+fn parse_tag(input: &str) -> Result<String, String> {
+    Ok(input.trim_start_matches('@').to_lowercase())
+}
+
+impl Selection {
+    fn has_filters(&self) -> bool {
+        self.priority.is_some() || self.tag.is_some() || self.dry_run
+    }
+
+    /// Indices of the selected tasks, or an error for task numbers that don't exist
+    fn resolve(&self, entries: &[TodoEntry]) -> Result<Vec<usize>, String> {
+        let candidates: Vec<usize> = if self.all {
+            (0..entries.len()).collect()
+        } else {
+            let mut indices = Vec::new();
+            for &id in &self.ids {
+                if !(1..=entries.len()).contains(&id) {
+                    return Err(format!("No task with number {}.", id));
+                }
+                if !indices.contains(&(id - 1)) {
+                    indices.push(id - 1);
                 }
             }
+            indices
+        };
+
+        Ok(candidates
+            .into_iter()
+            .filter(|&index| {
+                self.priority
+                    .is_none_or(|priority| entries[index].priority == priority)
+            })
+            .filter(|&index| {
+                self.tag
+                    .as_deref()
+                    .is_none_or(|tag| has_tag(&entries[index], tag))
+            })
+            .collect())
+    }
+}
+
+/// The distinct `@word` tokens of a description, lowercased
+fn parse_tags(description: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in description
+        .split_whitespace()
+        .filter_map(|word| word.strip_prefix('@'))
+    {
+        let tag = tag.to_lowercase();
+        if !tag.is_empty() && !tags.contains(&tag) {
+            tags.push(tag);
         }
-        Command::Complete => {
-            if let Some(task_desc) = task {
-                println!("Marked task as complete: '{}'", task_desc);
+    }
+    tags
+}
+
+/// Colors the `@word` tokens of a description so tags stand out
+fn highlight_tags(description: &str) -> String {
+    description
+        .split(' ')
+        .map(|word| {
+            if word.len() > 1 && word.starts_with('@') {
+                word.cyan().to_string()
             } else {
-                println!("No task description provided to complete.");
+                word.to_string()
             }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn has_tag(entry: &TodoEntry, tag: &str) -> bool {
+    entry.tags.iter().any(|entry_tag| entry_tag == tag)
+}
+
+/// Completes or deletes every selected task, or only reports them with `--dry-run`
+fn apply_bulk(
+    todo_path: &Path,
+    selection: &Selection,
+    delete: bool,
+    note: Option<String>,
+) -> Result<(), String> {
+    let mut entries = load_entries(todo_path)
+        .map_err(|e| format!("Could not read {}: {}", todo_path.display(), e))?;
+    let mut selected = selection.resolve(&entries)?;
+    if !delete {
+        selected.retain(|&index| !entries[index].is_done());
+    }
+
+    let action = if delete { "delete" } else { "complete" };
+    if selected.is_empty() {
+        println!("No tasks to {}.", action);
+        return Ok(());
+    }
+
+    if selection.dry_run {
+        println!("Would {} {} task(s):", action, selected.len());
+    } else if delete {
+        println!("Deleted {} task(s):", selected.len());
+    } else {
+        println!("Completed {} task(s):", selected.len());
+    }
+    for &index in &selected {
+        println!("  {}: {}", index + 1, entries[index].display());
+    }
+    if selection.dry_run {
+        return Ok(());
+    }
+
+    if delete {
+        let mut index = 0;
+        entries.retain(|_| {
+            index += 1;
+            !selected.contains(&(index - 1))
+        });
+    } else {
+        let now = Utc::now();
+        let next_occurrences: Vec<TodoEntry> = selected
+            .iter()
+            .filter_map(|&index| entries[index].complete(now, note.clone()))
+            .collect();
+        for next in next_occurrences {
+            println!(
+                "♻ Next occurrence: '{}'{}",
+                next.description,
+                format_due(&next)
+            );
+            push_entry(&mut entries, next);
         }
     }
+
+    if let Err(e) = save_undo_point(todo_path) {
+        eprintln!("Warning: Could not save undo point: {}", e);
+    }
+    save_entries(todo_path, &entries)
+        .map_err(|e| format!("Could not write to {}: {}", todo_path.display(), e))?;
+    if !delete && let Err(e) = record_longest_streak(todo_path, &entries) {
+        eprintln!("Warning: Could not record the longest streak: {}", e);
+    }
+    Ok(())
 }
 
-fn main() {
-    println!("Welcome to the Todo CLI!");
+/// Removes the task with the given number from `list`, or every selected task in bulk mode
+fn delete_tasks(todo_path: &Path, id: Option<usize>, selection: &Selection) -> Result<(), String> {
+    let Some(id) = id else {
+        return apply_bulk(todo_path, selection, true, None);
+    };
+    if selection.has_filters() {
+        return Err("--priority, --tag and --dry-run need --all or --ids.".to_string());
+    }
+
+    let mut entries = load_entries(todo_path)
+        .map_err(|e| format!("Could not read {}: {}", todo_path.display(), e))?;
+    let removed = entries.remove(task_index(&entries, id)?);
+
+    if let Err(e) = save_undo_point(todo_path) {
+        eprintln!("Warning: Could not save undo point: {}", e);
+    }
+    save_entries(todo_path, &entries)
+        .map_err(|e| format!("Could not write to {}: {}", todo_path.display(), e))?;
+    println!("Deleted task: '{}'", removed.display());
+    Ok(())
+}
+
+/// Drops the completed tasks from the list, appending them to the archive file if asked to
+fn clear_done(todo_path: &Path, archive: bool) -> Result<(), String> {
+    let entries = load_entries(todo_path)
+        .map_err(|e| format!("Could not read {}: {}", todo_path.display(), e))?;
+    let (done, pending): (Vec<TodoEntry>, Vec<TodoEntry>) =
+        entries.into_iter().partition(TodoEntry::is_done);
+    if done.is_empty() {
+        println!("No completed tasks to clear.");
+        return Ok(());
+    }
+    let count = done.len();
+
+    if archive {
+        let archive_path = archive_path(todo_path);
+        let mut archived = load_entries(&archive_path)
+            .map_err(|e| format!("Could not read {}: {}", archive_path.display(), e))?;
+        archived.extend(done);
+        save_entries(&archive_path, &archived)
+            .map_err(|e| format!("Could not write to {}: {}", archive_path.display(), e))?;
+    }
+
+    if let Err(e) = save_undo_point(todo_path) {
+        eprintln!("Warning: Could not save undo point: {}", e);
+    }
+    save_entries(todo_path, &pending)
+        .map_err(|e| format!("Could not write to {}: {}", todo_path.display(), e))?;
+    if archive {
+        let archive_path = archive_path(todo_path);
+        let archive_name = archive_path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy();
+        println!("Archived {} completed task(s) to {}", count, archive_name);
+    } else {
+        println!("Removed {} completed task(s).", count);
+    }
+    Ok(())
+}
+
+/// Rewrites the description, score or estimate of one task. Tags are re-read from the new text.
+fn edit_task(
+    todo_path: &Path,
+    id: usize,
+    description: Option<&str>,
+    score: Option<u8>,
+    estimate: Option<u32>,
+) -> Result<(), String> {
+    let mut entries = load_entries(todo_path)
+        .map_err(|e| format!("Could not read {}: {}", todo_path.display(), e))?;
+    let index = task_index(&entries, id)?;
 
-    let filename = "todo.txt";
-    if !fs::metadata(filename).is_ok() {
-        fs::File::create(filename).expect("Could not create todo.txt");
+    let entry = &mut entries[index];
+    let before = description.map(|description| {
+        entry.tags = parse_tags(description);
+        std::mem::replace(&mut entry.description, description.to_string())
+    });
+    let score_before = entry.score();
+    if score.is_some() {
+        entry.priority_score = score;
     }
+    let score_after = entry.score();
+    let estimate_before = entry.estimate;
+    if estimate.is_some() {
+        entry.estimate = estimate;
+    }
+
+    if let Err(e) = save_undo_point(todo_path) {
+        eprintln!("Warning: Could not save undo point: {}", e);
+    }
+    save_entries(todo_path, &entries)
+        .map_err(|e| format!("Could not write to {}: {}", todo_path.display(), e))?;
+    println!("Edited task {}:", id);
+    if let (Some(before), Some(description)) = (before, description) {
+        println!("  before: '{}'", before);
+        println!("  after:  '{}'", description);
+    }
+    if score.is_some() {
+        println!("  score:  {} -> {}", score_before, score_after);
+    }
+    if let Some(points) = estimate {
+        match estimate_before {
+            Some(before) => println!("  estimate: {} -> {} pts", before, points),
+            None => println!("  estimate: {} pts", points),
+        }
+    }
+    Ok(())
+}
+
+/// Marks one task done, or every selected task in bulk mode.
+/// The file is rewritten atomically, so an interrupted completion leaves it intact.
+fn complete_task(
+    todo_path: &Path,
+    id: Option<usize>,
+    selection: &Selection,
+    note: Option<&str>,
+) -> Result<(), String> {
+    let note = note.map(sanitize_note).transpose()?;
+    let Some(id) = id else {
+        return apply_bulk(todo_path, selection, false, note);
+    };
+    if selection.has_filters() {
+        return Err("--priority, --tag and --dry-run need --all or --ids.".to_string());
+    }
+
+    let mut entries = load_entries(todo_path)
+        .map_err(|e| format!("Could not read {}: {}", todo_path.display(), e))?;
+    let index = task_index(&entries, id)?;
+
+    let entry = &mut entries[index];
+    if entry.is_done() {
+        println!("Task already complete: '{}'", entry.description);
+        return Ok(());
+    }
+    let next = entry.complete(Utc::now(), note);
+    let description = entry.description.clone();
+    let next_message = next.as_ref().map(|next| {
+        format!(
+            "♻ Next occurrence: '{}'{}",
+            next.description,
+            format_due(next)
+        )
+    });
+    if let Some(next) = next {
+        push_entry(&mut entries, next);
+    }
+
+    if let Err(e) = save_undo_point(todo_path) {
+        eprintln!("Warning: Could not save undo point: {}", e);
+    }
+    save_entries(todo_path, &entries)
+        .map_err(|e| format!("Could not write to {}: {}", todo_path.display(), e))?;
+    if let Err(e) = record_longest_streak(todo_path, &entries) {
+        eprintln!("Warning: Could not record the longest streak: {}", e);
+    }
+    println!("Marked task as complete: '{}'", description);
+    if let Some(next_message) = next_message {
+        println!("{}", next_message);
+    }
+    Ok(())
+}
+
+fn load_entries(todo_path: &Path) -> io::Result<Vec<TodoEntry>> {
+    let contents = read_to_string_or_empty(todo_path)?;
+    if contents.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    serde_json::from_str(&contents).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+fn save_entries(todo_path: &Path, entries: &[TodoEntry]) -> io::Result<()> {
+    let mut contents = serde_json::to_string_pretty(entries).map_err(io::Error::other)?;
+    contents.push('\n');
+    write_atomic(todo_path, contents.as_bytes())
+}
+
+/// Appends a task, giving it the next unused id
+fn push_entry(entries: &mut Vec<TodoEntry>, mut entry: TodoEntry) {
+    entry.id = entries.iter().map(|entry| entry.id).max().unwrap_or(0) + 1;
+    entries.push(entry);
+}
 
+/// Converts the `todo.txt` of earlier versions into `todo.json` when there is
+/// no `todo.json` yet. The old file is left in place.
+fn migrate_legacy_file(data_dir: &Path, todo_path: &Path) -> io::Result<()> {
+    let legacy_path = data_dir.join(LEGACY_TODO_FILE_NAME);
+    if todo_path.exists() || !legacy_path.exists() {
+        return Ok(());
+    }
+
+    let mut entries = Vec::new();
+    for line in fs::read_to_string(&legacy_path)?
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+    {
+        push_entry(&mut entries, TodoEntry::parse_legacy_line(line));
+    }
+    save_entries(todo_path, &entries)?;
+    eprintln!(
+        "Migrated {} task(s) from {} to {}",
+        entries.len(),
+        legacy_path.display(),
+        todo_path.display()
+    );
+    Ok(())
+}
+
+/// `1h23m`, or just minutes/seconds for shorter spans
+fn format_duration(seconds: u64) -> String {
+    let (hours, minutes) = (seconds / 3600, seconds % 3600 / 60);
+    if hours > 0 {
+        format!("{}h{}m", hours, minutes)
+    } else if minutes > 0 {
+        format!("{}m", minutes)
+    } else {
+        format!("{}s", seconds)
+    }
+}
+
+/// Where `clear-done --archive` keeps completed tasks, e.g. `todo_done.json` next to `todo.json`
+fn archive_path(todo_path: &Path) -> PathBuf {
+    let stem = todo_path.file_stem().unwrap_or_default().to_string_lossy();
+    todo_path.with_file_name(format!("{}_done.json", stem))
+}
+
+/// Path next to the task file with an extra suffix, e.g. `todo.json.bak`
+fn sibling_path(todo_path: &Path, suffix: &str) -> PathBuf {
+    let mut path = todo_path.as_os_str().to_owned();
+    path.push(suffix);
+    PathBuf::from(path)
+}
+
+/// Saves the current task file as the undo point before a mutating command.
+/// A new change also invalidates whatever could have been redone.
+fn save_undo_point(todo_path: &Path) -> io::Result<()> {
+    fs::copy(todo_path, sibling_path(todo_path, ".bak"))?;
+    let redo_path = sibling_path(todo_path, ".redo");
+    if redo_path.exists() {
+        fs::remove_file(redo_path)?;
+    }
+    Ok(())
+}
+
+/// Swaps the task file with the state stored under `from_suffix`, keeping the
+/// current state under `to_suffix` so the swap can be reversed.
+/// Returns false when there is no stored state to restore.
+fn restore_state(todo_path: &Path, from_suffix: &str, to_suffix: &str) -> io::Result<bool> {
+    let from_path = sibling_path(todo_path, from_suffix);
+    if !from_path.exists() {
+        return Ok(false);
+    }
+    fs::rename(todo_path, sibling_path(todo_path, to_suffix))?;
+    fs::rename(from_path, todo_path)?;
+    Ok(true)
+}
+
+/// Renders e.g. `[████████░░░░░░░░░░░░] 40%`
+fn draw_progress_bar(completed: usize, total: usize, width: usize) -> String {
+    let filled = (completed * width).checked_div(total).unwrap_or(0);
+    let percent = (completed * 100).checked_div(total).unwrap_or(0);
+    format!(
+        "[{}{}] {}%",
+        "█".repeat(filled),
+        "░".repeat(width - filled),
+        percent
+    )
+}
+
+/// Local dates on which at least one task was completed
+fn completion_days(todos: &[TodoEntry]) -> BTreeSet<NaiveDate> {
+    todos
+        .iter()
+        .filter_map(|entry| entry.completed_at)
+        .map(|completed_at| completed_at.with_timezone(&Local).date_naive())
+        .collect()
+}
+
+/// Number of consecutive days with at least one completed task, ending today.
+/// A streak that reached yesterday is still alive until today is over.
+fn compute_streak(todos: &[TodoEntry], today: NaiveDate) -> u32 {
+    let days = completion_days(todos);
+    let mut day = if days.contains(&today) {
+        today
+    } else {
+        today - Duration::days(1)
+    };
+    let mut streak = 0;
+    while days.contains(&day) {
+        streak += 1;
+        day -= Duration::days(1);
+    }
+    streak
+}
+
+/// Longest run of consecutive completion days in the task history
+fn longest_streak(todos: &[TodoEntry]) -> u32 {
+    let mut longest = 0;
+    let mut current = 0;
+    let mut previous: Option<NaiveDate> = None;
+    for day in completion_days(todos) {
+        current = if previous.is_some_and(|previous| day - previous == Duration::days(1)) {
+            current + 1
+        } else {
+            1
+        };
+        longest = longest.max(current);
+        previous = Some(day);
+    }
+    longest
+}
+
+/// Stats kept across runs, since completed tasks may be cleared or archived
+#[derive(Default, Serialize, Deserialize)]
+struct StatsHistory {
+    longest_streak: u32,
+}
+
+/// Where the stats history is kept, e.g. `todo_stats.json` next to `todo.json`
+fn stats_history_path(todo_path: &Path) -> PathBuf {
+    let stem = todo_path.file_stem().unwrap_or_default().to_string_lossy();
+    todo_path.with_file_name(format!("{}_stats.json", stem))
+}
+
+/// The stored stats history, empty when there is none yet
+fn load_stats_history(todo_path: &Path) -> io::Result<StatsHistory> {
+    let contents = read_to_string_or_empty(&stats_history_path(todo_path))?;
+    if contents.trim().is_empty() {
+        return Ok(StatsHistory::default());
+    }
+    serde_json::from_str(&contents).map_err(io::Error::other)
+}
+
+/// Records the longest streak in `todos` if it beats the stored one
+fn record_longest_streak(todo_path: &Path, todos: &[TodoEntry]) -> io::Result<()> {
+    let mut history = load_stats_history(todo_path)?;
+    let streak = longest_streak(todos);
+    if streak > history.longest_streak {
+        history.longest_streak = streak;
+        let json = serde_json::to_string_pretty(&history).map_err(io::Error::other)?;
+        write_atomic(&stats_history_path(todo_path), json.as_bytes())?;
+    }
+    Ok(())
+}
+
+fn show_stats(todo_path: &Path, goal: Option<usize>, by_time: bool) -> Result<(), String> {
+    let entries = load_entries(todo_path)
+        .map_err(|e| format!("Could not read {}: {}", todo_path.display(), e))?;
+
+    println!("📊 Task Statistics:");
+    if entries.is_empty() {
+        println!("No tasks yet.");
+        return Ok(());
+    }
+
+    // Summary counts come from a single pass over the tasks
+    let today = Local::now().date_naive();
+    let mut completed = 0;
+    let mut by_priority = [0; 3];
+    let mut overdue = 0;
+    let mut has_due_dates = false;
+    let mut completed_on: Vec<NaiveDate> = Vec::new();
+    for entry in &entries {
+        if entry.is_done() {
+            completed += 1;
+        }
+        by_priority[entry.priority.rank() as usize] += 1;
+        if entry.is_overdue(today) {
+            overdue += 1;
+        }
+        has_due_dates |= entry.due_date.is_some();
+        if let Some(completed_at) = entry.completed_at {
+            completed_on.push(completed_at.with_timezone(&Local).date_naive());
+        }
+    }
+
+    let total = entries.len();
+    let percent = |count: usize| count as f64 / total as f64 * 100.0;
+    println!("Total tasks: {}", total);
+    println!(
+        "Pending: {} ({:.1}%)",
+        total - completed,
+        percent(total - completed)
+    );
+    println!("Done: {} ({:.1}%)", completed, percent(completed));
+    for priority in [TaskPriority::High, TaskPriority::Medium, TaskPriority::Low] {
+        let count = by_priority[priority.rank() as usize];
+        println!("{}: {} ({:.1}%)", priority.label(), count, percent(count));
+    }
+    if has_due_dates {
+        println!("Overdue: {} ({:.1}%)", overdue, percent(overdue));
+    }
+
+    println!("Completed {} of {} tasks", completed, total);
+    println!("{}", draw_progress_bar(completed, total, 20));
+    let streak = compute_streak(&entries, today);
+    let recorded = load_stats_history(todo_path).map_or_else(
+        |e| {
+            eprintln!(
+                "Warning: Could not read {}: {}",
+                stats_history_path(todo_path).display(),
+                e
+            );
+            0
+        },
+        |history| history.longest_streak,
+    );
+    let best = recorded.max(longest_streak(&entries));
+    if streak == 0 && best > 0 {
+        println!("Streak broken: 0 days (best was {})", best);
+    } else {
+        println!("🔥 {}-day streak", streak);
+    }
+    let week_start = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+    let done_this_week = completed_on
+        .iter()
+        .filter(|day| **day >= week_start)
+        .count();
+    println!("Completed this week: {}", done_this_week);
+
+    if let Some(goal) = goal {
+        let done_today = completed_on.iter().filter(|day| **day == today).count();
+        let status = if done_today >= goal {
+            "goal reached!"
+        } else {
+            "keep going"
+        };
+        println!(
+            "Today: {}/{} tasks completed ({})",
+            done_today, goal, status
+        );
+    }
+
+    if entries.iter().any(|entry| entry.estimate.is_some()) {
+        show_estimate_stats(&entries, today);
+    }
+
+    if by_time {
+        let mut timed: Vec<(usize, &TodoEntry)> = entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| entry.tracked_seconds() > 0)
+            .collect();
+        timed.sort_by_key(|(_, entry)| std::cmp::Reverse(entry.tracked_seconds()));
+
+        println!("⏱ Time spent:");
+        if timed.is_empty() {
+            println!("No time tracked yet.");
+        }
+        for (index, entry) in timed {
+            println!(
+                "{}: {} - {}",
+                index + 1,
+                entry.description,
+                format_duration(entry.tracked_seconds())
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Accepts `YYYY-MM-DD` or an RFC 3339 timestamp, which is reduced to its local date
+fn parse_due_date(input: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(input, "%Y-%m-%d")
+        .ok()
+        .or_else(|| {
+            parse_timestamp(input).map(|timestamp| timestamp.with_timezone(&Local).date_naive())
+        })
+        .ok_or_else(|| {
+            format!(
+                "Invalid date '{}'. Use YYYY-MM-DD or an RFC 3339 timestamp.",
+                input
+            )
+        })
+}
+
+fn show_scheduled(todo_path: &Path) -> Result<(), String> {
+    println!("Listing scheduled tasks...");
+    let entries = load_entries(todo_path)
+        .map_err(|e| format!("Could not read {}: {}", todo_path.display(), e))?;
+    if entries.is_empty() {
+        println!("No tasks yet.");
+        return Ok(());
+    }
+    let mut scheduled: Vec<(usize, &TodoEntry)> = entries
+        .iter()
+        .enumerate()
+        .filter(|(_, entry)| entry.due_date.is_some())
+        .collect();
+    scheduled.sort_by_key(|(_, entry)| entry.due_date);
+
+    let today = Local::now().date_naive();
+    for (index, entry) in scheduled {
+        if entry.is_overdue(today) {
+            println!("{}: {} ⚠ OVERDUE", index + 1, entry.display());
+        } else {
+            println!("{}: {}", index + 1, entry.display());
+        }
+    }
+    Ok(())
+}
+
+fn search_tasks(todo_path: &Path, keyword: &str) -> Result<(), String> {
+    let entries = load_entries(todo_path)
+        .map_err(|e| format!("Could not read {}: {}", todo_path.display(), e))?;
+    let needle = keyword.to_lowercase();
+
+    let mut found = false;
+    for (index, entry) in entries.iter().enumerate() {
+        if entry.description.to_lowercase().contains(&needle) {
+            println!("{}: {}", index + 1, entry.display());
+            found = true;
+        }
+    }
+    if !found {
+        println!("No tasks matching '{}'.", keyword);
+    }
+    Ok(())
+}
+
+fn format_due(entry: &TodoEntry) -> String {
+    entry
+        .due_date
+        .map(|due_date| format!(" due {}", due_date))
+        .unwrap_or_default()
+}
+
+/// Parses `min..max`, where either bound may be left out
+fn parse_estimate_range(input: &str) -> Result<(u32, u32), String> {
+    let invalid = || {
+        format!(
+            "Invalid estimate range '{}'. Use MIN..MAX, MIN.. or ..MAX.",
+            input
+        )
+    };
+    let (min, max) = input.split_once("..").ok_or_else(invalid)?;
+    let min = if min.is_empty() {
+        0
+    } else {
+        min.parse().map_err(|_| invalid())?
+    };
+    let max = if max.is_empty() {
+        u32::MAX
+    } else {
+        max.parse().map_err(|_| invalid())?
+    };
+    Ok((min, max))
+}
+
+fn list_tasks(todo_path: &Path, options: &ListOptions) -> Result<(), String> {
+    let completed_only = options.completed;
+    if completed_only {
+        println!("Listing completed tasks...");
+    } else {
+        println!("Listing all tasks...");
+    }
+    let entries = load_entries(todo_path)
+        .map_err(|e| format!("Could not read {}: {}", todo_path.display(), e))?;
+    if entries.is_empty() {
+        println!("No tasks yet.");
+        return Ok(());
+    }
+
+    // Task numbers stay those of the file order; stable sorts keep ties in that order
+    let mut ordered: Vec<(usize, &TodoEntry)> = entries.iter().enumerate().collect();
+    match options.sort {
+        Some(SortKey::Priority) => {
+            ordered.sort_by_key(|(_, entry)| std::cmp::Reverse(entry.priority.rank()))
+        }
+        Some(SortKey::Due) => {
+            ordered.sort_by_key(|(_, entry)| (entry.due_date.is_none(), entry.due_date))
+        }
+        Some(SortKey::Score) => ordered.sort_by_key(|(_, entry)| std::cmp::Reverse(entry.score())),
+        None => {}
+    }
+
+    for (index, entry) in ordered {
+        if completed_only && !entry.is_done() {
+            continue;
+        }
+        if options
+            .priority
+            .is_some_and(|priority| entry.priority != priority)
+        {
+            continue;
+        }
+        if options
+            .tag
+            .as_deref()
+            .is_some_and(|tag| !has_tag(entry, tag))
+        {
+            continue;
+        }
+        if let Some((min, max)) = options.estimate_filter
+            && !entry
+                .estimate
+                .is_some_and(|points| (min..=max).contains(&points))
+        {
+            continue;
+        }
+        let tracked = entry.tracked_seconds();
+        if tracked > 0 || entry.started_at.is_some() {
+            println!(
+                "{}: {} ⏱ {}",
+                index + 1,
+                entry.display(),
+                format_duration(tracked)
+            );
+        } else {
+            println!("{}: {}", index + 1, entry.display());
+        }
+        if completed_only && let Some(note) = &entry.completion_note {
+            println!("   📝 {}", note);
+        }
+        if let Some(completed_at) = entry.completed_at
+            && entry.is_done()
+        {
+            println!(
+                "   ✔ Completed {}",
+                completed_at.with_timezone(&Local).format("%Y-%m-%d %H:%M")
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Story point totals and velocity: points per day over the last 7 completed tasks
+fn show_estimate_stats(entries: &[TodoEntry], today: NaiveDate) {
+    let backlog: u32 = entries
+        .iter()
+        .filter(|entry| !entry.is_done())
+        .filter_map(|entry| entry.estimate)
+        .sum();
+
+    let mut completed: Vec<(NaiveDate, u32)> = entries
+        .iter()
+        .filter_map(|entry| Some((entry.completed_at?, entry.estimate?)))
+        .map(|(completed_at, points)| (completed_at.with_timezone(&Local).date_naive(), points))
+        .collect();
+    completed.sort_by_key(|(day, _)| std::cmp::Reverse(*day));
+
+    let week_start = today.week(Weekday::Mon).first_day();
+    let points_since = |start: NaiveDate| -> u32 {
+        completed
+            .iter()
+            .filter(|(day, _)| *day >= start)
+            .map(|(_, points)| points)
+            .sum()
+    };
+
+    println!("Backlog: {} points", backlog);
+    println!("Points completed this week: {}", points_since(week_start));
+    println!(
+        "Points completed this month: {}",
+        points_since(today.with_day(1).unwrap_or(today))
+    );
+
+    let recent = &completed[..completed.len().min(7)];
+    if let (Some((newest, _)), Some((oldest, _))) = (recent.first(), recent.last()) {
+        let days = (*newest - *oldest).num_days() + 1;
+        let points: u32 = recent.iter().map(|(_, points)| points).sum();
+        println!("Velocity: {:.2} points/day", points as f64 / days as f64);
+    }
+}
+
+/// Adds the tasks found in a plain, Markdown or Org file. Tasks whose
+/// description already exists are reported and skipped.
+fn import_tasks(todo_path: &Path, file: &Path, format: Option<ImportFormat>) -> Result<(), String> {
+    let format = format.unwrap_or_else(|| ImportFormat::from_path(file));
+    let contents = fs::read_to_string(file)
+        .map_err(|e| format!("Could not read {}: {}", file.display(), e))?;
+    let mut entries = load_entries(todo_path)
+        .map_err(|e| format!("Could not read {}: {}", todo_path.display(), e))?;
+
+    let mut imported = 0;
+    for (description, done) in format.parse_tasks(&contents) {
+        if entries
+            .iter()
+            .any(|entry| entry.description.eq_ignore_ascii_case(&description))
+        {
+            eprintln!("Warning: Skipping duplicate task '{}'", description);
+            continue;
+        }
+        let mut entry = TodoEntry::new(TaskPriority::Medium, &description);
+        if done {
+            entry.status = TaskStatus::Done;
+        }
+        push_entry(&mut entries, entry);
+        imported += 1;
+    }
+
+    if imported > 0 {
+        if let Err(e) = save_undo_point(todo_path) {
+            eprintln!("Warning: Could not save undo point: {}", e);
+        }
+        save_entries(todo_path, &entries)
+            .map_err(|e| format!("Could not write to {}: {}", todo_path.display(), e))?;
+    }
+    println!("Imported {} task(s) from {}", imported, file.display());
+    Ok(())
+}
+
+fn export_tasks(
+    todo_path: &Path,
+    format: ExportFormat,
+    output: Option<PathBuf>,
+) -> Result<(), String> {
+    let entries = load_entries(todo_path)
+        .map_err(|e| format!("Could not read {}: {}", todo_path.display(), e))?;
+    let path = output.unwrap_or_else(|| {
+        let timestamp = Local::now().format("%Y%m%d_%H%M%S");
+        PathBuf::from(format!("todo_export_{}.{}", timestamp, format.extension()))
+    });
+
+    match format {
+        ExportFormat::Ical => export_ical(&entries, &path),
+        ExportFormat::Csv => export_csv(&entries, &path),
+        ExportFormat::Markdown => export_markdown(&entries, &path),
+    }
+    .map_err(|e| format!("Could not write {}: {}", path.display(), e))?;
+    println!("Exported tasks to {}", path.display());
+    Ok(())
+}
+
+/// Writes an RFC 5545 calendar with an all-day `VEVENT` for every task that has a due date
+fn export_ical(todos: &[TodoEntry], path: &Path) -> io::Result<()> {
+    let stamp = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//75HC-Rust//todo_cli//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+    ];
+
+    for entry in todos {
+        let Some(due_date) = entry.due_date else {
+            continue;
+        };
+        let date = due_date.format("%Y%m%d");
+        let priority = match entry.priority {
+            TaskPriority::High => 1,
+            TaskPriority::Medium => 5,
+            TaskPriority::Low => 9,
+        };
+        let status = if entry.is_done() {
+            "COMPLETED"
+        } else {
+            "NEEDS-ACTION"
+        };
+
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:todo-{}@todo_cli", entry.id));
+        lines.push(format!("DTSTAMP:{}", stamp));
+        lines.push(format!("DTSTART;VALUE=DATE:{}", date));
+        lines.push(format!("DTEND;VALUE=DATE:{}", date));
+        lines.push(format!("SUMMARY:{}", escape_ical_text(&entry.description)));
+        lines.push(format!("PRIORITY:{}", priority));
+        lines.push(format!("STATUS:{}", status));
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    let contents: String = lines.iter().map(|line| fold_ical_line(line)).collect();
+    fs::write(path, contents)
+}
+
+/// Writes one row per task, leaving the quoting of commas and quotes to the `csv` crate
+fn export_csv(todos: &[TodoEntry], path: &Path) -> io::Result<()> {
+    let mut writer = csv::Writer::from_path(path)?;
+    writer.write_record([
+        "id",
+        "description",
+        "priority",
+        "status",
+        "due",
+        "tags",
+        "note",
+    ])?;
+    for entry in todos {
+        let due = entry
+            .due_date
+            .map(|date| date.to_string())
+            .unwrap_or_default();
+        writer.write_record([
+            entry.id.to_string().as_str(),
+            &entry.description,
+            entry.priority.label(),
+            if entry.is_done() { "done" } else { "pending" },
+            &due,
+            &entry.tags.join(" "),
+            entry.completion_note.as_deref().unwrap_or_default(),
+        ])?;
+    }
+    writer.flush()
+}
+
+/// Writes a Markdown checklist, ticking the completed tasks
+fn export_markdown(todos: &[TodoEntry], path: &Path) -> io::Result<()> {
+    let mut contents = String::new();
+    for entry in todos {
+        let mark = if entry.is_done() { 'x' } else { ' ' };
+        contents.push_str(&format!("- [{}] {}", mark, entry.description));
+        if let Some(due_date) = entry.due_date {
+            contents.push_str(&format!(" (due {})", due_date));
+        }
+        contents.push('\n');
+    }
+    fs::write(path, contents)
+}
+
+/// Escapes the characters that are special in iCalendar TEXT values
+fn escape_ical_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Ends a content line with CRLF, folding it so no physical line exceeds 75 octets.
+/// Continuation lines start with a space, and multi-byte characters are never split.
+fn fold_ical_line(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len() + 8);
+    let mut octets = 0;
+    for c in line.chars() {
+        if octets + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            octets = 1;
+        }
+        folded.push(c);
+        octets += c.len_utf8();
+    }
+    folded.push_str("\r\n");
+    folded
+}
+
+/// Starts or stops the timer of one task
+fn track_time(todo_path: &Path, id: usize, start: bool) -> Result<(), String> {
+    let mut entries = load_entries(todo_path)
+        .map_err(|e| format!("Could not read {}: {}", todo_path.display(), e))?;
+    let index = task_index(&entries, id)?;
+    let entry = &mut entries[index];
+
+    let now = Utc::now();
+    let message = match (start, entry.started_at) {
+        (true, Some(_)) => {
+            println!("Timer already running for task {}.", id);
+            return Ok(());
+        }
+        (false, None) => {
+            println!("No timer running for task {}.", id);
+            return Ok(());
+        }
+        (true, None) => {
+            entry.started_at = Some(now);
+            format!("Started timer for task {}: '{}'", id, entry.description)
+        }
+        (false, Some(started_at)) => {
+            let elapsed = (now - started_at).num_seconds().max(0) as u64;
+            entry.total_time_seconds += elapsed;
+            entry.started_at = None;
+            format!(
+                "Stopped timer for task {}: +{} (total {})",
+                id,
+                format_duration(elapsed),
+                format_duration(entry.total_time_seconds)
+            )
+        }
+    };
+
+    if let Err(e) = save_undo_point(todo_path) {
+        eprintln!("Warning: Could not save undo point: {}", e);
+    }
+    save_entries(todo_path, &entries)
+        .map_err(|e| format!("Could not write to {}: {}", todo_path.display(), e))?;
+    println!("{}", message);
+    Ok(())
+}
+
+fn add_task(
+    todo_path: &Path,
+    description: &str,
+    priority: TaskPriority,
+    score: Option<u8>,
+    estimate: Option<u32>,
+    recurrence: Option<Recurrence>,
+    due_date: Option<NaiveDate>,
+) -> Result<(), String> {
+    let mut entries = load_entries(todo_path)
+        .map_err(|e| format!("Could not read {}: {}", todo_path.display(), e))?;
+    let mut entry = TodoEntry::new(priority, description);
+    entry.priority_score = score;
+    entry.estimate = estimate;
+    entry.recurrence = recurrence;
+    entry.due_date = due_date;
+    push_entry(&mut entries, entry);
+
+    if let Err(e) = save_undo_point(todo_path) {
+        eprintln!("Warning: Could not save undo point: {}", e);
+    }
+    save_entries(todo_path, &entries)
+        .map_err(|e| format!("Could not write to {}: {}", todo_path.display(), e))?;
+
+    println!(
+        "Added task: '{}' with priority: {}",
+        description,
+        priority.label()
+    );
+    Ok(())
+}
+
+/// Runs one command. Errors are returned so the caller can report them and
+/// exit with a non-zero status.
+fn todo_command(command: Command, todo_path: &Path) -> Result<(), String> {
+    match command {
+        Command::Add {
+            description,
+            priority,
+            score,
+            estimate,
+            recur,
+            due,
+        } => add_task(
+            todo_path,
+            &description.join(" "),
+            priority,
+            score,
+            estimate,
+            recur,
+            due,
+        )?,
+        Command::List(options) => list_tasks(todo_path, &options)?,
+        Command::Scheduled => show_scheduled(todo_path)?,
+        Command::Search { keyword } => search_tasks(todo_path, &keyword.join(" "))?,
+        Command::Complete {
+            id,
+            selection,
+            note,
+        } => complete_task(todo_path, id, &selection, note.as_deref())?,
+        Command::Edit {
+            id,
+            description,
+            score,
+            estimate,
+        } => {
+            let description = (!description.is_empty()).then(|| description.join(" "));
+            edit_task(todo_path, id, description.as_deref(), score, estimate)?
+        }
+        Command::Delete { id, selection } => delete_tasks(todo_path, id, &selection)?,
+        Command::Stats { goal, time } => show_stats(todo_path, goal, time)?,
+        Command::Start { id } => track_time(todo_path, id, true)?,
+        Command::Stop { id } => track_time(todo_path, id, false)?,
+        Command::Import { file, format } => import_tasks(todo_path, &file, format)?,
+        Command::Export { format, output } => export_tasks(todo_path, format, output)?,
+        Command::ClearDone { archive } => clear_done(todo_path, archive)?,
+        Command::Undo => {
+            if restore_state(todo_path, ".bak", ".redo")
+                .map_err(|e| format!("Could not undo: {}", e))?
+            {
+                println!("Undid the last change.");
+            } else {
+                println!("Warning: Nothing to undo.");
+            }
+        }
+        Command::Redo => {
+            if restore_state(todo_path, ".redo", ".bak")
+                .map_err(|e| format!("Could not redo: {}", e))?
+            {
+                println!("Redid the last undone change.");
+            } else {
+                println!("Warning: Nothing to redo.");
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Splits an interactive line into arguments like a shell would for simple
+/// cases: whitespace separates words, quotes group them.
+fn split_args(line: &str) -> Result<Vec<String>, String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_word = false;
+    let mut quote = None;
+
+    for c in line.chars() {
+        match (quote, c) {
+            (Some(open), c) if c == open => quote = None,
+            (Some(_), c) => current.push(c),
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                in_word = true;
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_word {
+                    args.push(std::mem::take(&mut current));
+                    in_word = false;
+                }
+            }
+            (None, c) => {
+                current.push(c);
+                in_word = true;
+            }
+        }
+    }
+
+    if quote.is_some() {
+        return Err("Unterminated quote.".to_string());
+    }
+    if in_word {
+        args.push(current);
+    }
+    Ok(args)
+}
+
+/// Reads commands from stdin until `exit`. Returns false if any command failed.
+fn run_interactive(todo_path: &Path) -> bool {
+    println!("Welcome to the Todo CLI!");
+
+    let mut had_error = false;
     loop {
-        println!("Please enter a command (add, list, scheduled, complete) or 'exit' to quit:");
+        println!(
+            "Please enter a command (add, list, scheduled, search, complete, edit, delete, clear-done, start, stop, stats, import, export, undo, redo, help) or 'exit' to quit:"
+        );
 
         let mut input = String::new();
-        io::stdin()
-            .read_line(&mut input)
-            .expect("Failed to read line");
+        match io::stdin().read_line(&mut input) {
+            // End of input ends the session like `exit`
+            Ok(0) => break,
+            Ok(_) => {}
+            Err(e) => {
+                eprintln!("Could not read input: {}", e);
+                had_error = true;
+                break;
+            }
+        }
         let trimmed_input = input.trim();
 
         if trimmed_input.eq_ignore_ascii_case("exit") {
             break;
         }
 
-        let parts: Vec<&str> = trimmed_input.split_whitespace().collect();
-        if parts.is_empty() {
+        let mut args = match split_args(trimmed_input) {
+            Ok(args) => args,
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                had_error = true;
+                continue;
+            }
+        };
+        let Some(command_name) = args.first_mut() else {
             continue;
-        }
+        };
+        *command_name = command_name.to_lowercase();
 
-        let command_str = parts[0];
-        let command = match command_str.to_lowercase().as_str() {
-            "add" => Command::Add,
-            "list" => Command::List,
-            "scheduled" => Command::Scheduled,
-            "complete" => Command::Complete,
-            _ => {
-                println!("Unknown command: {}", command_str);
+        let line = match InteractiveLine::try_parse_from(args) {
+            Ok(line) => line,
+            Err(e) => {
+                let _ = e.print();
+                had_error |= e.use_stderr();
                 continue;
             }
         };
 
-        let task = if parts.len() > 1 {
-            Some(parts[1..].join(" "))
-        } else {
-            None
-        };
+        if let Err(e) = todo_command(line.command, todo_path) {
+            eprintln!("Error: {}", e);
+            had_error = true;
+        }
+    }
+    !had_error
+}
 
-        let priority = if let Some(task_desc) = &task {
-            if task_desc.contains("[High]") {
-                Some(TaskPriority::High)
-            } else if task_desc.contains("[Low]") {
-                Some(TaskPriority::Low)
-            } else {
-                Some(TaskPriority::Medium)
+fn main() {
+    let args = Args::parse();
+
+    let todo_path = match args.file {
+        Some(file) => file,
+        None => {
+            let data_dir = args.data_dir.unwrap_or_else(default_data_dir);
+            if let Err(e) = fs::create_dir_all(&data_dir) {
+                eprintln!(
+                    "Could not create data directory {}: {}",
+                    data_dir.display(),
+                    e
+                );
+                std::process::exit(1);
             }
-        } else {
-            None
-        };
 
-        todo_command(command, task, priority);
+            let todo_path = data_dir.join(TODO_FILE_NAME);
+            if let Err(e) = migrate_legacy_file(&data_dir, &todo_path) {
+                eprintln!("Could not migrate {}: {}", LEGACY_TODO_FILE_NAME, e);
+                std::process::exit(1);
+            }
+            todo_path
+        }
+    };
+    if fs::metadata(&todo_path).is_err()
+        && let Err(e) = fs::File::create(&todo_path)
+    {
+        eprintln!("Could not create {}: {}", todo_path.display(), e);
+        std::process::exit(1);
+    }
+
+    let succeeded = match args.command {
+        Some(command) => match todo_command(command, &todo_path) {
+            Ok(()) => true,
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                false
+            }
+        },
+        None => run_interactive(&todo_path),
+    };
+
+    if !succeeded {
+        std::process::exit(1);
     }
 }
@@ -0,0 +1,110 @@
+//! The `--detect-language` heuristic. The letter trigrams of a file, with a
+//! space marking the start and end of each word, are compared against the most
+//! common trigrams of each language. Good enough for a paragraph or more of
+//! ordinary prose, not for short or mixed-language text.
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+const ENGLISH: [&str; 30] = [
+    " th", "the", "he ", "and", " an", "nd ", "ing", "ng ", "ion", " of", "of ", " to", "to ",
+    "ed ", "er ", "tio", "is ", " in", "in ", "ent", "hat", "tha", " wa", "es ", "for", " fo",
+    "re ", "ter", "at ", "ly ",
+];
+
+const SPANISH: [&str; 30] = [
+    " de", "de ", "os ", " la", "la ", "ión", "que", " qu", "ue ", "el ", " el", "as ", "es ",
+    " en", "en ", " co", "ado", "con", " lo", "los", "nte", "ien", "cio", "aci", "par", " pa",
+    "ara", "do ", "del", " se",
+];
+
+const FRENCH: [&str; 30] = [
+    " de", "es ", "de ", "ent", "le ", " le", "ion", "nt ", " la", "la ", "que", " qu", "ue ",
+    " et", "et ", "les", "on ", "re ", "des", "tio", " pa", "men", " co", "ait", "eme", "our",
+    "ur ", "té ", " un", "ne ",
+];
+
+const GERMAN: [&str; 30] = [
+    "en ", "er ", "der", " de", "ie ", "ich", "ein", "die", " di", "sch", "che", "und", " un",
+    "nd ", "ch ", "den", "in ", " ei", "gen", "ung", "cht", "te ", "ine", " zu", "ten", "ber",
+    "ste", " da", "das", "ist",
+];
+
+const ITALIAN: [&str; 30] = [
+    " di", "di ", "to ", "la ", " la", "che", " ch", "he ", "re ", "ell", " de", "one", "del",
+    "lla", "ne ", "no ", "zio", "ion", "per", " pe", "er ", "le ", " co", "ato", " il", "il ",
+    "nte", "are", "ta ", "gli",
+];
+
+const PROFILES: [(&str, &[&str]); 5] = [
+    ("English", &ENGLISH),
+    ("Spanish", &SPANISH),
+    ("French", &FRENCH),
+    ("German", &GERMAN),
+    ("Italian", &ITALIAN),
+];
+
+/// How many of a file's most common trigrams are compared against the profiles
+const TOP_TRIGRAMS: usize = 300;
+
+#[derive(Clone, Serialize)]
+pub struct DetectedLanguage {
+    pub language: &'static str,
+    /// Share of the matching trigrams that belong to this language, 0-100
+    pub confidence: f64,
+}
+
+impl std::fmt::Display for DetectedLanguage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({:.0}% confidence)", self.language, self.confidence)
+    }
+}
+
+/// Counts the trigrams of a file one line at a time
+#[derive(Default)]
+pub struct TrigramCounter {
+    counts: HashMap<String, usize>,
+}
+
+impl TrigramCounter {
+    pub fn add_line(&mut self, line: &str) {
+        for word in line
+            .split(|c: char| !c.is_alphabetic())
+            .filter(|word| !word.is_empty())
+        {
+            let padded: Vec<char> = std::iter::once(' ')
+                .chain(word.chars().flat_map(char::to_lowercase))
+                .chain(std::iter::once(' '))
+                .collect();
+            for trigram in padded.windows(3) {
+                *self.counts.entry(trigram.iter().collect()).or_default() += 1;
+            }
+        }
+    }
+
+    /// The best matching language, or `None` when nothing matched at all
+    pub fn detect(&self) -> Option<DetectedLanguage> {
+        let mut top: Vec<(&String, &usize)> = self.counts.iter().collect();
+        top.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+        top.truncate(TOP_TRIGRAMS);
+
+        let scores: Vec<(&'static str, usize)> = PROFILES
+            .iter()
+            .map(|(language, profile)| {
+                let score = top
+                    .iter()
+                    .filter(|(trigram, _)| profile.contains(&trigram.as_str()))
+                    .map(|(_, count)| **count)
+                    .sum();
+                (*language, score)
+            })
+            .collect();
+        let total: usize = scores.iter().map(|(_, score)| score).sum();
+        let (language, best) = scores.into_iter().max_by_key(|(_, score)| *score)?;
+        (best > 0).then(|| DetectedLanguage {
+            language,
+            confidence: best as f64 * 100.0 / total as f64,
+        })
+    }
+}
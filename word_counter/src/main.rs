@@ -1,18 +1,30 @@
 use std::fs::File;
-use std::io::{self, BufRead, Write};
-use std::path::Path;
-use clap::{Parser, ValueEnum};
+use std::io::{self, BufRead, BufWriter, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use clap::{Parser, Subcommand, ValueEnum};
 use serde::{Deserialize, Serialize};
-use walkdir::WalkDir;
+use walkdir::{DirEntry, WalkDir};
 use indicatif::{ProgressBar, ProgressStyle};
 use rayon::prelude::*;
+use sha2::{Digest, Sha256};
 use std::sync::Mutex;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::time::Instant;
+
+mod language;
+mod readability;
+
+use language::{DetectedLanguage, TrigramCounter};
+use readability::{Readability, TextStats};
 
 #[derive(Parser)]
 #[command(name = "word_counter")]
 #[command(about = "A tool to count characters in text files")]
+#[command(subcommand_negates_reqs = true)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     #[arg(short, long, num_args = 1.., required = true)]
     input: Vec<String>,
 
@@ -28,6 +40,10 @@ struct Args {
     #[arg(short, long)]
     recursive: bool,
 
+    /// Also process hidden files and directories when recursing
+    #[arg(long)]
+    include_hidden: bool,
+
     #[arg(short = 'x', long, num_args = 0..)]
     extensions: Vec<String>,
 
@@ -36,6 +52,109 @@ struct Args {
 
     #[arg(long)]
     delimiters: Option<String>,
+
+    /// Report per-file processing details on stderr
+    #[arg(long)]
+    verbose: bool,
+
+    /// Write plain "Progress: N% (x/y files)" lines to stderr instead of the progress bar.
+    /// This is the default when stdout is not a terminal
+    #[arg(long, conflicts_with = "quiet")]
+    progress_stderr: bool,
+
+    /// Show no progress at all, not even the "Processing complete" message
+    #[arg(short, long)]
+    quiet: bool,
+
+    /// Leave out lines with fewer characters than this
+    #[arg(long)]
+    min_chars: Option<usize>,
+
+    /// Leave out lines with more characters than this
+    #[arg(long)]
+    max_chars: Option<usize>,
+
+    /// Add the SHA-256 of each file's contents to the JSON and CSV output
+    #[arg(long)]
+    hash: bool,
+
+    /// Also count letters, digits, whitespace, punctuation and other characters
+    #[arg(long)]
+    char_breakdown: bool,
+
+    /// Files larger than this many bytes are split into chunks that are counted in parallel
+    #[arg(long, value_name = "BYTES", default_value_t = 64 * 1024 * 1024, value_parser = clap::value_parser!(u64).range(1..))]
+    chunk_size: u64,
+
+    /// Also count words, splitting on whitespace and each of these characters, e.g. "_-"
+    #[arg(long, value_name = "CHARS", allow_hyphen_values = true)]
+    word_delimiters: Option<String>,
+
+    /// Also count words, splitting camelCase words into their parts
+    #[arg(long)]
+    camel_case: bool,
+
+    /// Guess the language of each file: English, Spanish, French, German or Italian
+    #[arg(long)]
+    detect_language: bool,
+
+    /// Score how easy each file is to read (Flesch Reading Ease), from "Very Easy" to "Very Confusing"
+    #[arg(long)]
+    readability: bool,
+
+    /// Look of the progress bar; `none` shows no progress at all
+    /// (defaults to the saved preference, then `detailed`)
+    #[arg(long, value_enum)]
+    bar_style: Option<BarStyle>,
+
+    /// Filled, current and empty characters of the progress bar (defaults to the saved preference, then "#>-")
+    #[arg(long, value_name = "CHARS", value_parser = parse_bar_theme)]
+    bar_theme: Option<String>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Show the preferences saved in ~/.wordcounter_config.toml, or save new ones
+    Config {
+        #[arg(long, value_enum)]
+        bar_style: Option<BarStyle>,
+
+        #[arg(long, value_name = "CHARS", value_parser = parse_bar_theme)]
+        bar_theme: Option<String>,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum BarStyle {
+    /// Only `done/total`
+    Minimal,
+    /// Spinner, elapsed time, bar, `done/total` and ETA
+    Detailed,
+    None,
+}
+
+fn parse_bar_theme(input: &str) -> Result<String, String> {
+    if input.chars().count() == 3 {
+        Ok(input.to_string())
+    } else {
+        Err("expected 3 characters: filled, current and empty, e.g. \"#>-\"".to_string())
+    }
+}
+
+impl Args {
+    fn has_threshold(&self) -> bool {
+        self.min_chars.is_some() || self.max_chars.is_some()
+    }
+
+    fn counts_words(&self) -> bool {
+        self.word_delimiters.is_some() || self.camel_case
+    }
+
+    fn within_threshold(&self, char_count: usize) -> bool {
+        self.min_chars.is_none_or(|min| char_count >= min)
+            && self.max_chars.is_none_or(|max| char_count <= max)
+    }
 }
 
 #[derive(Clone, ValueEnum, PartialEq)]
@@ -60,9 +179,15 @@ impl std::str::FromStr for OutputFormat {
 
 #[derive(Serialize)]
 struct LineResult {
+    file: String,
     line_number: usize,
     content: String,
     char_count: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    char_breakdown: Option<CharBreakdown>,
+    /// The words of the line, with `--word-delimiters`/`--camel-case`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    words: Option<Vec<String>>,
 }
 
 #[derive(Serialize)]
@@ -70,6 +195,64 @@ struct Summary {
     total_lines: usize,
     total_chars: usize,
     average_chars_per_line: f64,
+    /// Lines left out by `--min-chars`/`--max-chars`
+    filtered_lines: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    char_breakdown: Option<CharBreakdown>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    total_words: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    readability: Option<Readability>,
+}
+
+/// Character counts by category, with `--char-breakdown`.
+/// Unlike the char count, delimiters are included.
+#[derive(Clone, Copy, Default, Serialize)]
+struct CharBreakdown {
+    letters: usize,
+    digits: usize,
+    whitespace: usize,
+    /// ASCII punctuation; other Unicode punctuation counts as `other`
+    punctuation: usize,
+    other: usize,
+}
+
+impl CharBreakdown {
+    fn add(&mut self, other: &CharBreakdown) {
+        self.letters += other.letters;
+        self.digits += other.digits;
+        self.whitespace += other.whitespace;
+        self.punctuation += other.punctuation;
+        self.other += other.other;
+    }
+}
+
+impl std::fmt::Display for CharBreakdown {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Letters: {}, Digits: {}, Whitespace: {}, Punctuation: {}, Other: {}",
+            self.letters, self.digits, self.whitespace, self.punctuation, self.other
+        )
+    }
+}
+
+fn analyze_chars(s: &str) -> CharBreakdown {
+    let mut breakdown = CharBreakdown::default();
+    for c in s.chars() {
+        if c.is_alphabetic() {
+            breakdown.letters += 1;
+        } else if c.is_numeric() {
+            breakdown.digits += 1;
+        } else if c.is_whitespace() {
+            breakdown.whitespace += 1;
+        } else if c.is_ascii_punctuation() {
+            breakdown.punctuation += 1;
+        } else {
+            breakdown.other += 1;
+        }
+    }
+    breakdown
 }
 
 #[derive(Deserialize)]
@@ -80,18 +263,214 @@ struct Config {
     include_patterns: Option<Vec<String>>,
 }
 
+/// Defaults kept in `~/.wordcounter_config.toml`, set with `word_counter config`
+#[derive(Default, Serialize, Deserialize)]
+struct Preferences {
+    bar_style: Option<BarStyle>,
+    bar_theme: Option<String>,
+}
+
+impl Preferences {
+    fn path() -> Option<PathBuf> {
+        dirs::home_dir().map(|home| home.join(".wordcounter_config.toml"))
+    }
+
+    /// The saved preferences, or none when there is no readable file
+    fn load() -> Preferences {
+        Preferences::path()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|content| match toml::from_str(&content) {
+                Ok(preferences) => Some(preferences),
+                Err(e) => {
+                    eprintln!("Ignoring invalid preferences file: {}", e);
+                    None
+                }
+            })
+            .unwrap_or_default()
+    }
+
+    fn save(&self) -> io::Result<PathBuf> {
+        let path = Preferences::path().ok_or_else(|| io::Error::other("no home directory"))?;
+        let content = toml::to_string(self).map_err(io::Error::other)?;
+        std::fs::write(&path, content)?;
+        Ok(path)
+    }
+}
+
+/// `word_counter config`: saves the given preferences, or prints the current ones
+fn run_config(bar_style: Option<BarStyle>, bar_theme: Option<String>) -> io::Result<()> {
+    let mut preferences = Preferences::load();
+    if bar_style.is_none() && bar_theme.is_none() {
+        print!(
+            "{}",
+            toml::to_string(&preferences).map_err(io::Error::other)?
+        );
+        return Ok(());
+    }
+    preferences.bar_style = bar_style.or(preferences.bar_style);
+    preferences.bar_theme = bar_theme.or(preferences.bar_theme);
+    let path = preferences.save()?;
+    println!("Saved preferences to {}", path.display());
+    Ok(())
+}
+
+/// How progress is reported while files are processed
+enum Progress {
+    Bar(ProgressBar),
+    Plain { done: usize, total: usize },
+    Quiet,
+}
+
+impl Progress {
+    fn new(args: &Args, total: usize) -> Self {
+        if args.quiet {
+            return Progress::Quiet;
+        }
+        if args.progress_stderr {
+            return Progress::Plain { done: 0, total };
+        }
+        let preferences = Preferences::load();
+        let style = args
+            .bar_style
+            .or(preferences.bar_style)
+            .unwrap_or(BarStyle::Detailed);
+        if style == BarStyle::None {
+            return Progress::Quiet;
+        }
+        if !io::stdout().is_terminal() {
+            return Progress::Plain { done: 0, total };
+        }
+
+        let template = match style {
+            BarStyle::Minimal => "{pos}/{len} {msg}",
+            _ => {
+                "{spinner:.green} [{elapsed_precise}] [{wide_bar:.cyan/blue}] {pos}/{len} ({eta}) {msg}"
+            }
+        };
+        let theme = args
+            .bar_theme
+            .clone()
+            .or(preferences.bar_theme)
+            .unwrap_or_else(|| "#>-".to_string());
+        let pb = ProgressBar::new(total as u64);
+        pb.set_style(
+            ProgressStyle::default_bar()
+                .template(template)
+                .unwrap()
+                .progress_chars(&theme),
+        );
+        Progress::Bar(pb)
+    }
+
+    fn file_done(&mut self, filename: &str, verbose: bool) {
+        match self {
+            Progress::Bar(pb) => {
+                if verbose {
+                    pb.set_message(filename.to_string());
+                }
+                pb.inc(1);
+            }
+            Progress::Plain { done, total } => {
+                *done += 1;
+                eprintln!(
+                    "Progress: {}% ({}/{} files)",
+                    *done * 100 / *total,
+                    done,
+                    total
+                );
+            }
+            Progress::Quiet => {}
+        }
+    }
+
+    fn finish(self) {
+        match self {
+            Progress::Bar(pb) => pb.finish_with_message("Processing complete"),
+            Progress::Plain { .. } => eprintln!("Processing complete"),
+            Progress::Quiet => {}
+        }
+    }
+}
+
+/// Per-file totals, listed in the JSON output when `--hash`, `--detect-language` or `--readability` is set
+#[derive(Serialize)]
+struct FileSummary {
+    file: String,
+    lines: usize,
+    chars: usize,
+    content_hash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    language: Option<DetectedLanguage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    readability: Option<Readability>,
+}
+
+/// Writes `{"results": [...], "summary": {...}}` one line result at a time,
+/// so memory use doesn't grow with the size of the input.
+/// Files are processed in parallel, so their lines may interleave.
+struct JsonStream {
+    writer: Box<dyn Write + Send>,
+    has_results: bool,
+}
+
+impl JsonStream {
+    fn start(output: Option<&str>) -> io::Result<Self> {
+        let writer: Box<dyn Write + Send> = match output {
+            Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+            None => Box::new(BufWriter::new(io::stdout())),
+        };
+        let mut stream = JsonStream {
+            writer,
+            has_results: false,
+        };
+        stream.writer.write_all(b"{\"results\": [")?;
+        Ok(stream)
+    }
+
+    fn write_result(&mut self, result: &LineResult) -> io::Result<()> {
+        let separator: &[u8] = if self.has_results { b",\n  " } else { b"\n  " };
+        self.writer.write_all(separator)?;
+        serde_json::to_writer(&mut self.writer, result)?;
+        self.has_results = true;
+        Ok(())
+    }
+
+    /// Closes the results and adds the per-file totals, if any, and the summary
+    fn finish(mut self, files: &[FileSummary], summary: &Summary) -> io::Result<()> {
+        self.writer
+            .write_all(if self.has_results { b"\n]" } else { b"]" })?;
+        if !files.is_empty() {
+            self.writer.write_all(b", \"files\": ")?;
+            serde_json::to_writer(&mut self.writer, files)?;
+        }
+        self.writer.write_all(b", \"summary\": ")?;
+        serde_json::to_writer(&mut self.writer, summary)?;
+        self.writer.write_all(b"}\n")?;
+        self.writer.flush()
+    }
+}
+
 struct FileProcessingResult {
     results: Vec<LineResult>,
     chars: usize,
     lines: usize,
+    filtered_lines: usize,
+    /// SHA-256 of the file contents as lowercase hex, with `--hash`
+    content_hash: Option<String>,
+    char_breakdown: Option<CharBreakdown>,
+    words: usize,
+    language: Option<DetectedLanguage>,
+    text_stats: Option<TextStats>,
 }
 
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where
-    P: AsRef<Path>,
-{
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
+fn hash_file(path: &str) -> io::Result<String> {
+    let mut hasher = Sha256::new();
+    io::copy(&mut File::open(path)?, &mut hasher)?;
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect())
 }
 
 fn collect_files(args: &Args, exclude_patterns: &HashSet<String>, include_patterns: &HashSet<String>) -> Vec<String> {
@@ -105,7 +484,11 @@ fn collect_files(args: &Args, exclude_patterns: &HashSet<String>, include_patter
                 files.push(input.clone());
             }
         } else if path.is_dir() && args.recursive {
-            for entry in WalkDir::new(path).into_iter().filter_map(|e| e.ok()) {
+            // The directory given on the command line is always walked, even if it is hidden
+            let walker = WalkDir::new(path)
+                .into_iter()
+                .filter_entry(|e| args.include_hidden || e.depth() == 0 || !is_hidden(e));
+            for entry in walker.filter_map(|e| e.ok()) {
                 if entry.file_type().is_file() && should_include_file(entry.path(), &extensions, exclude_patterns, include_patterns) {
                     files.push(entry.path().to_string_lossy().to_string());
                 }
@@ -115,6 +498,25 @@ fn collect_files(args: &Args, exclude_patterns: &HashSet<String>, include_patter
     files
 }
 
+/// Dotfiles, and on Windows also files with the hidden attribute
+fn is_hidden(entry: &DirEntry) -> bool {
+    if entry.file_name().to_string_lossy().starts_with('.') {
+        return true;
+    }
+    #[cfg(windows)]
+    {
+        use std::os::windows::fs::MetadataExt;
+        const FILE_ATTRIBUTE_HIDDEN: u32 = 0x2;
+        if entry
+            .metadata()
+            .is_ok_and(|metadata| metadata.file_attributes() & FILE_ATTRIBUTE_HIDDEN != 0)
+        {
+            return true;
+        }
+    }
+    false
+}
+
 fn should_include_file(path: &Path, extensions: &HashSet<String>, exclude_patterns: &HashSet<String>, include_patterns: &HashSet<String>) -> bool {
 
     if !extensions.is_empty() && !has_valid_extension(path, extensions) {
@@ -151,29 +553,259 @@ fn has_valid_extension(path: &Path, extensions: &std::collections::HashSet<Strin
         .unwrap_or(false)
 }
 
-fn process_file(filename: &str, args: &Args, delimiters: &str) -> FileProcessingResult {
-    let mut file_results = Vec::new();
-    let mut file_chars = 0;
-    let mut file_lines = 0;
+/// Collects the line results of one file. With a JSON stream the line results
+/// are written straight to it instead of being collected.
+struct LineCounter<'a> {
+    filename: &'a str,
+    args: &'a Args,
+    json: Option<&'a Mutex<JsonStream>>,
+    results: Vec<LineResult>,
+    chars: usize,
+    lines: usize,
+    filtered_lines: usize,
+    char_breakdown: Option<CharBreakdown>,
+    /// Extra word delimiters, `None` unless words are counted
+    word_delimiters: Option<Vec<char>>,
+    words: usize,
+    trigrams: Option<TrigramCounter>,
+    text_stats: Option<TextStats>,
+}
+
+impl<'a> LineCounter<'a> {
+    fn new(filename: &'a str, args: &'a Args, json: Option<&'a Mutex<JsonStream>>) -> Self {
+        LineCounter {
+            filename,
+            args,
+            json,
+            results: Vec::new(),
+            chars: 0,
+            lines: 0,
+            filtered_lines: 0,
+            char_breakdown: args.char_breakdown.then(CharBreakdown::default),
+            word_delimiters: args.counts_words().then(|| {
+                args.word_delimiters
+                    .as_deref()
+                    .unwrap_or_default()
+                    .chars()
+                    .collect()
+            }),
+            words: 0,
+            trigrams: args.detect_language.then(TrigramCounter::default),
+            text_stats: args.readability.then(TextStats::default),
+        }
+    }
+
+    fn add(&mut self, line_number: usize, content: String, char_count: usize) {
+        if !self.args.within_threshold(char_count) {
+            self.filtered_lines += 1;
+            return;
+        }
+        self.chars += char_count;
+        self.lines += 1;
+        if let Some(trigrams) = self.trigrams.as_mut() {
+            trigrams.add_line(&content);
+        }
+        if let Some(text_stats) = self.text_stats.as_mut() {
+            text_stats.add_line(&content);
+        }
+        let char_breakdown = self.char_breakdown.as_mut().map(|total| {
+            let breakdown = analyze_chars(&content);
+            total.add(&breakdown);
+            breakdown
+        });
+        let words = self
+            .word_delimiters
+            .as_deref()
+            .map(|delimiters| split_words(&content, delimiters, self.args.camel_case));
+        if let Some(words) = &words {
+            self.words += words.len();
+        }
+
+        if self.args.format == OutputFormat::Text && self.args.output.is_none() {
+            print!(
+                "File: {} - Line {}: {} - Char count: {}",
+                self.filename, line_number, content, char_count
+            );
+            if let Some(words) = &words {
+                print!(" - Word count: {}", words.len());
+            }
+            match &char_breakdown {
+                Some(breakdown) => println!(" - {}", breakdown),
+                None => println!(),
+            }
+        }
+
+        let result = LineResult {
+            file: self.filename.to_string(),
+            line_number,
+            content,
+            char_count,
+            char_breakdown,
+            words,
+        };
+        match self.json {
+            Some(stream) => stream
+                .lock()
+                .unwrap()
+                .write_result(&result)
+                .expect("Failed to write JSON"),
+            None => self.results.push(result),
+        }
+    }
 
-    match read_lines(filename) {
-        Ok(lines) => {
-            for (line_number, line) in lines.enumerate() {
+    fn finish(self, content_hash: Option<String>) -> FileProcessingResult {
+        FileProcessingResult {
+            results: self.results,
+            chars: self.chars,
+            lines: self.lines,
+            filtered_lines: self.filtered_lines,
+            content_hash,
+            char_breakdown: self.char_breakdown,
+            words: self.words,
+            language: self.trigrams.and_then(|trigrams| trigrams.detect()),
+            text_stats: self.text_stats,
+        }
+    }
+}
+
+fn count_chars(content: &str, delimiters: &str) -> usize {
+    content.chars().filter(|c| !delimiters.contains(*c)).count()
+}
+
+/// Splits a line into words at whitespace and `delimiters`. With `camel` a
+/// lowercase letter followed by an uppercase one starts a new word too, so
+/// `camelCaseWord` becomes `camel`, `Case` and `Word`.
+fn split_words(line: &str, delimiters: &[char], camel: bool) -> Vec<String> {
+    let mut words = Vec::new();
+    for part in line.split(|c: char| c.is_whitespace() || delimiters.contains(&c)) {
+        if part.is_empty() {
+            continue;
+        }
+        if !camel {
+            words.push(part.to_string());
+            continue;
+        }
+        let mut word = String::new();
+        let mut previous_lowercase = false;
+        for c in part.chars() {
+            if previous_lowercase && c.is_uppercase() {
+                words.push(std::mem::take(&mut word));
+            }
+            previous_lowercase = c.is_lowercase();
+            word.push(c);
+        }
+        words.push(word);
+    }
+    words
+}
+
+/// Splits `data` into at most `num_chunks` pieces of similar size. Every piece
+/// but the last ends with `\n`, so no line is cut in half.
+fn split_at_newline_boundaries(data: &[u8], num_chunks: usize) -> Vec<&[u8]> {
+    let mut chunks = Vec::with_capacity(num_chunks);
+    let mut start = 0;
+    for i in 1..num_chunks {
+        let target = (data.len() * i / num_chunks).max(start);
+        let Some(offset) = data[target..].iter().position(|&byte| byte == b'\n') else {
+            break;
+        };
+        let end = target + offset + 1;
+        chunks.push(&data[start..end]);
+        start = end;
+    }
+    if start < data.len() {
+        chunks.push(&data[start..]);
+    }
+    chunks
+}
+
+/// The text and character count of each line of a chunk, or why it isn't valid UTF-8.
+/// Line endings are stripped the way `BufRead::lines` does it.
+fn count_chunk_lines(
+    chunk: &[u8],
+    delimiters: &str,
+) -> Vec<Result<(String, usize), std::str::Utf8Error>> {
+    let (body, terminated) = match chunk.strip_suffix(b"\n") {
+        Some(body) => (body, true),
+        None => (chunk, false),
+    };
+    let line_count = body.split(|&byte| byte == b'\n').count();
+    body.split(|&byte| byte == b'\n')
+        .enumerate()
+        .map(|(index, line)| {
+            let line = if terminated || index + 1 < line_count {
+                line.strip_suffix(b"\r").unwrap_or(line)
+            } else {
+                line
+            };
+            let content = std::str::from_utf8(line)?;
+            Ok((content.to_string(), count_chars(content, delimiters)))
+        })
+        .collect()
+}
+
+/// Counts a large file by splitting it into chunks that are counted in parallel.
+/// Chunks are counted one batch per thread at a time and handed over in order,
+/// so only that batch's lines are held in memory, however large the file is.
+fn process_file_in_chunks(
+    counter: &mut LineCounter,
+    file: &File,
+    size: u64,
+    delimiters: &str,
+) -> io::Result<()> {
+    // SAFETY: the map is only read, and like any reader we assume the file
+    // isn't truncated while it is being counted
+    let data = unsafe { memmap2::Mmap::map(file)? };
+    let num_chunks = size.div_ceil(counter.args.chunk_size) as usize;
+    let chunks = split_at_newline_boundaries(&data, num_chunks);
+
+    let mut line_number = 0;
+    for batch in chunks.chunks(rayon::current_num_threads()) {
+        let counted: Vec<_> = batch
+            .par_iter()
+            .map(|chunk| count_chunk_lines(chunk, delimiters))
+            .collect();
+        for line in counted.into_iter().flatten() {
+            line_number += 1;
+            match line {
+                Ok((content, char_count)) => counter.add(line_number, content, char_count),
+                Err(e) => eprintln!(
+                    "Error reading line {} in {}: {}",
+                    line_number, counter.filename, e
+                ),
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Counts the lines of one file, in parallel chunks when it is larger than `--chunk-size`
+fn process_file(
+    filename: &str,
+    args: &Args,
+    delimiters: &str,
+    json: Option<&Mutex<JsonStream>>,
+) -> FileProcessingResult {
+    let mut counter = LineCounter::new(filename, args, json);
+
+    let file = File::open(filename);
+    let size = file
+        .as_ref()
+        .ok()
+        .and_then(|file| file.metadata().ok())
+        .map_or(0, |metadata| metadata.len());
+    match file {
+        Ok(file) if size > args.chunk_size => {
+            if let Err(e) = process_file_in_chunks(&mut counter, &file, size, delimiters) {
+                eprintln!("Error reading file {}: {}", filename, e);
+            }
+        }
+        Ok(file) => {
+            for (line_number, line) in io::BufReader::new(file).lines().enumerate() {
                 match line {
                     Ok(content) => {
-                        let char_count = content.chars().filter(|c| !delimiters.contains(*c)).count();
-                        file_chars += char_count;
-                        file_lines += 1;
-
-                        file_results.push(LineResult {
-                            line_number: line_number + 1,
-                            content: content.clone(),
-                            char_count,
-                        });
-
-                        if args.format == OutputFormat::Text && args.output.is_none() {
-                            println!("File: {} - Line {}: {} - Char count: {}", filename, line_number + 1, content, char_count);
-                        }
+                        let char_count = count_chars(&content, delimiters);
+                        counter.add(line_number + 1, content, char_count);
                     }
                     Err(e) => eprintln!("Error reading line {} in {}: {}", line_number + 1, filename, e),
                 }
@@ -182,26 +814,41 @@ fn process_file(filename: &str, args: &Args, delimiters: &str) -> FileProcessing
         Err(e) => eprintln!("Error reading file {}: {}", filename, e),
     }
 
-    FileProcessingResult {
-        results: file_results,
-        chars: file_chars,
-        lines: file_lines,
-    }
+    let content_hash = if args.hash {
+        hash_file(filename)
+            .map_err(|e| eprintln!("Error hashing file {}: {}", filename, e))
+            .ok()
+    } else {
+        None
+    };
+    counter.finish(content_hash)
 }
 
 fn main() {
     let mut args = Args::parse();
 
+    if let Some(Command::Config {
+        bar_style,
+        bar_theme,
+    }) = args.command.take()
+    {
+        if let Err(e) = run_config(bar_style, bar_theme) {
+            eprintln!("Error saving preferences: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
     // Load config if specified
     let config = if let Some(config_path) = &args.config {
         match std::fs::read_to_string(config_path) {
             Ok(content) => match toml::from_str::<Config>(&content) {
                 Ok(cfg) => {
                     // Apply config defaults
-                    if let Some(fmt) = &cfg.default_format {
-                        if let Ok(f) = fmt.parse::<OutputFormat>() {
-                            args.format = f;
-                        }
+                    if let Some(fmt) = &cfg.default_format
+                        && let Ok(f) = fmt.parse::<OutputFormat>()
+                    {
+                        args.format = f;
                     }
                     Some(cfg)
                 }
@@ -235,6 +882,16 @@ fn main() {
         .map(|p| p.iter().cloned().collect())
         .unwrap_or_default();
 
+    if let (Some(min), Some(max)) = (args.min_chars, args.max_chars)
+        && min > max
+    {
+        eprintln!(
+            "--min-chars ({}) must not be greater than --max-chars ({})",
+            min, max
+        );
+        std::process::exit(1);
+    }
+
     let files = collect_files(&args, &exclude_patterns, &include_patterns);
 
     if files.is_empty() {
@@ -242,63 +899,205 @@ fn main() {
         return;
     }
 
-    let pb = ProgressBar::new(files.len() as u64);
-    pb.set_style(
-        ProgressStyle::default_bar()
-            .template("{spinner:.green} [{elapsed_precise}] [{wide_bar:.cyan/blue}] {pos}/{len} ({eta})")
-            .unwrap()
-            .progress_chars("#>-"),
-    );
-
-    let pb_mutex = Mutex::new(pb);
+    let progress = Mutex::new(Progress::new(&args, files.len()));
+    let json = (args.format == OutputFormat::Json).then(|| {
+        Mutex::new(JsonStream::start(args.output.as_deref()).expect("Failed to create output file"))
+    });
 
     let file_results: Vec<FileProcessingResult> = files
         .par_iter()
         .map(|filename| {
-            let result = process_file(filename, &args, &delimiters);
-            {
-                let pb = pb_mutex.lock().unwrap();
-                pb.inc(1);
+            let start = Instant::now();
+            if args.verbose {
+                let size = std::fs::metadata(filename).map(|m| m.len()).unwrap_or(0);
+                eprintln!("Processing: {} ({} bytes)", filename, size);
             }
+
+            let result = process_file(filename, &args, &delimiters, json.as_ref());
+
+            if args.verbose {
+                eprintln!("Done: {} in {}ms", filename, start.elapsed().as_millis());
+            }
+            progress.lock().unwrap().file_done(filename, args.verbose);
             result
         })
         .collect();
 
-    let pb = pb_mutex.into_inner().unwrap();
-    pb.finish_with_message("Processing complete");
+    progress.into_inner().unwrap().finish();
 
     let mut all_results = Vec::new();
     let mut total_chars = 0;
     let mut total_lines = 0;
+    let mut filtered_lines = 0;
+    let mut total_words = 0;
+    let mut text_stats = args.readability.then(TextStats::default);
+    let mut char_breakdown = args.char_breakdown.then(CharBreakdown::default);
+    let mut file_summaries = Vec::new();
 
-    for result in file_results {
+    for (filename, result) in files.iter().zip(file_results) {
         all_results.extend(result.results);
         total_chars += result.chars;
         total_lines += result.lines;
+        filtered_lines += result.filtered_lines;
+        total_words += result.words;
+        if let (Some(total), Some(breakdown)) = (char_breakdown.as_mut(), result.char_breakdown) {
+            total.add(&breakdown);
+        }
+        if let (Some(total), Some(stats)) = (text_stats.as_mut(), &result.text_stats) {
+            total.add(stats);
+        }
+        if args.hash || args.detect_language || args.readability {
+            file_summaries.push(FileSummary {
+                file: filename.clone(),
+                lines: result.lines,
+                chars: result.chars,
+                content_hash: result.content_hash,
+                language: result.language,
+                readability: result.text_stats.and_then(|stats| stats.readability()),
+            });
+        }
     }
 
+    let average = if total_lines > 0 {
+        total_chars as f64 / total_lines as f64
+    } else {
+        0.0
+    };
+    let summary = Summary {
+        total_lines,
+        total_chars,
+        average_chars_per_line: average,
+        filtered_lines,
+        char_breakdown,
+        total_words: args.counts_words().then_some(total_words),
+        readability: text_stats.and_then(|stats| stats.readability()),
+    };
+
     match args.format {
         OutputFormat::Text => {
             if let Some(output_file) = &args.output {
                 let mut file = File::create(output_file).expect("Failed to create output file");
                 for result in &all_results {
-                    writeln!(file, "Line {}: {} - Char count: {}", result.line_number, result.content, result.char_count).unwrap();
+                    write!(
+                        file,
+                        "Line {}: {} - Char count: {}",
+                        result.line_number, result.content, result.char_count
+                    )
+                    .unwrap();
+                    if let Some(words) = &result.words {
+                        write!(file, " - Word count: {}", words.len()).unwrap();
+                    }
+                    match &result.char_breakdown {
+                        Some(breakdown) => writeln!(file, " - {}", breakdown).unwrap(),
+                        None => writeln!(file).unwrap(),
+                    }
+                }
+                for summary in &file_summaries {
+                    if let Some(language) = &summary.language {
+                        writeln!(file, "File: {} - Language: {}", summary.file, language).unwrap();
+                    }
+                    if let Some(readability) = &summary.readability {
+                        writeln!(
+                            file,
+                            "File: {} - Readability: {}",
+                            summary.file, readability
+                        )
+                        .unwrap();
+                    }
+                }
+            } else {
+                for summary in &file_summaries {
+                    if let Some(language) = &summary.language {
+                        println!("File: {} - Language: {}", summary.file, language);
+                    }
+                    if let Some(readability) = &summary.readability {
+                        println!("File: {} - Readability: {}", summary.file, readability);
+                    }
                 }
             }
         }
         OutputFormat::Json => {
-            let json = serde_json::to_string_pretty(&all_results).unwrap();
-            if let Some(output_file) = &args.output {
-                std::fs::write(output_file, &json).expect("Failed to write JSON");
-            } else {
-                println!("{}", json);
+            if let Some(stream) = json {
+                stream
+                    .into_inner()
+                    .unwrap()
+                    .finish(&file_summaries, &summary)
+                    .expect("Failed to write JSON");
             }
         }
         OutputFormat::Csv => {
+            let hashes: HashMap<&str, &str> = file_summaries
+                .iter()
+                .filter_map(|summary| {
+                    Some((summary.file.as_str(), summary.content_hash.as_deref()?))
+                })
+                .collect();
+            let languages: HashMap<&str, &DetectedLanguage> = file_summaries
+                .iter()
+                .filter_map(|summary| Some((summary.file.as_str(), summary.language.as_ref()?)))
+                .collect();
+            let readabilities: HashMap<&str, &Readability> = file_summaries
+                .iter()
+                .filter_map(|summary| Some((summary.file.as_str(), summary.readability.as_ref()?)))
+                .collect();
             let mut csv = String::new();
-            csv.push_str("line_number,content,char_count\n");
+            csv.push_str("line_number,content,char_count");
+            if args.counts_words() {
+                csv.push_str(",word_count");
+            }
+            if args.char_breakdown {
+                csv.push_str(",letters,digits,whitespace,punctuation,other");
+            }
+            if args.hash {
+                csv.push_str(",content_hash");
+            }
+            if args.detect_language {
+                csv.push_str(",language,language_confidence");
+            }
+            csv.push_str(if args.readability {
+                ",readability,readability_level\n"
+            } else {
+                "\n"
+            });
             for result in &all_results {
-                csv.push_str(&format!("{},{},{}\n", result.line_number, result.content.replace(",", "\\,"), result.char_count));
+                csv.push_str(&format!(
+                    "{},{},{}",
+                    result.line_number,
+                    result.content.replace(",", "\\,"),
+                    result.char_count
+                ));
+                if let Some(words) = &result.words {
+                    csv.push_str(&format!(",{}", words.len()));
+                }
+                if let Some(b) = &result.char_breakdown {
+                    csv.push_str(&format!(
+                        ",{},{},{},{},{}",
+                        b.letters, b.digits, b.whitespace, b.punctuation, b.other
+                    ));
+                }
+                if args.hash {
+                    csv.push_str(&format!(
+                        ",{}",
+                        hashes.get(result.file.as_str()).unwrap_or(&"")
+                    ));
+                }
+                if args.detect_language {
+                    match languages.get(result.file.as_str()) {
+                        Some(detected) => csv.push_str(&format!(
+                            ",{},{:.1}",
+                            detected.language, detected.confidence
+                        )),
+                        None => csv.push_str(",,"),
+                    }
+                }
+                if args.readability {
+                    match readabilities.get(result.file.as_str()) {
+                        Some(readability) => csv
+                            .push_str(&format!(",{:.1},{}", readability.score, readability.level)),
+                        None => csv.push_str(",,"),
+                    }
+                }
+                csv.push('\n');
             }
             if let Some(output_file) = &args.output {
                 std::fs::write(output_file, &csv).expect("Failed to write CSV");
@@ -308,18 +1107,24 @@ fn main() {
         }
     }
 
-    if args.summary {
-        let average = if total_lines > 0 { total_chars as f64 / total_lines as f64 } else { 0.0 };
-        let summary = Summary {
-            total_lines,
-            total_chars,
-            average_chars_per_line: average,
-        };
-        if args.format == OutputFormat::Json {
-            let json = serde_json::to_string_pretty(&summary).unwrap();
-            println!("Summary:\n{}", json);
-        } else {
-            println!("Summary: Total lines: {}, Total chars: {}, Average chars per line: {:.2}", total_lines, total_chars, average);
+    // The JSON output always carries the summary
+    if args.summary && args.format != OutputFormat::Json {
+        print!(
+            "Summary: Total lines: {}, Total chars: {}, Average chars per line: {:.2}",
+            summary.total_lines, summary.total_chars, summary.average_chars_per_line
+        );
+        if let Some(total_words) = summary.total_words {
+            print!(", Total words: {}", total_words);
+        }
+        if args.has_threshold() {
+            print!(", Filtered lines: {}", summary.filtered_lines);
+        }
+        println!();
+        if let Some(breakdown) = &summary.char_breakdown {
+            println!("Character breakdown: {}", breakdown);
+        }
+        if let Some(readability) = &summary.readability {
+            println!("Readability: {}", readability);
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,103 @@
+//! The `--readability` Flesch Reading Ease score. Words are whitespace-separated
+//! tokens with at least one letter, sentences end at runs of `.`, `!` or `?`,
+//! and syllables are estimated as runs of vowels.
+
+use serde::Serialize;
+
+/// Word, sentence and syllable counts of some text
+#[derive(Clone, Copy, Default)]
+pub struct TextStats {
+    words: u64,
+    sentences: u64,
+    syllables: u64,
+    /// Whether words were seen since the last sentence end
+    open_sentence: bool,
+}
+
+impl TextStats {
+    pub fn add_line(&mut self, line: &str) {
+        for token in line.split_whitespace() {
+            if token.chars().any(char::is_alphabetic) {
+                self.words += 1;
+                self.syllables += count_syllables(token) as u64;
+                self.open_sentence = true;
+            }
+            // "Wait... what?!" ends one sentence per run of terminators
+            let mut in_terminator = false;
+            for c in token.chars() {
+                let is_terminator = matches!(c, '.' | '!' | '?');
+                if is_terminator && !in_terminator && self.open_sentence {
+                    self.sentences += 1;
+                    self.open_sentence = false;
+                }
+                in_terminator = is_terminator;
+            }
+        }
+    }
+
+    pub fn add(&mut self, other: &TextStats) {
+        self.words += other.words;
+        self.sentences += other.sentences + u64::from(other.open_sentence);
+        self.syllables += other.syllables;
+    }
+
+    /// The score, or `None` for text without words
+    pub fn readability(&self) -> Option<Readability> {
+        if self.words == 0 {
+            return None;
+        }
+        // Text that trails off without a full stop still is a sentence
+        let sentences = self.sentences + u64::from(self.open_sentence);
+        Some(Readability::new(flesch_kincaid_score(
+            self.words,
+            sentences.max(1),
+            self.syllables,
+        )))
+    }
+}
+
+/// Flesch Reading Ease: higher is easier, around 60-70 is plain English
+pub fn flesch_kincaid_score(words: u64, sentences: u64, syllables: u64) -> f64 {
+    206.835 - 1.015 * (words as f64 / sentences as f64) - 84.6 * (syllables as f64 / words as f64)
+}
+
+/// Each run of vowels (including y) counts as one syllable, with at least one per word
+pub fn count_syllables(word: &str) -> usize {
+    let mut syllables = 0;
+    let mut in_vowels = false;
+    for c in word.chars().flat_map(char::to_lowercase) {
+        let is_vowel = matches!(c, 'a' | 'e' | 'i' | 'o' | 'u' | 'y');
+        if is_vowel && !in_vowels {
+            syllables += 1;
+        }
+        in_vowels = is_vowel;
+    }
+    syllables.max(1)
+}
+
+#[derive(Clone, Serialize)]
+pub struct Readability {
+    pub score: f64,
+    pub level: &'static str,
+}
+
+impl Readability {
+    fn new(score: f64) -> Readability {
+        let level = match score {
+            s if s >= 90.0 => "Very Easy",
+            s if s >= 80.0 => "Easy",
+            s if s >= 70.0 => "Fairly Easy",
+            s if s >= 60.0 => "Standard",
+            s if s >= 50.0 => "Fairly Difficult",
+            s if s >= 30.0 => "Difficult",
+            _ => "Very Confusing",
+        };
+        Readability { score, level }
+    }
+}
+
+impl std::fmt::Display for Readability {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:.1} ({})", self.score, self.level)
+    }
+}